@@ -0,0 +1,581 @@
+//! MCP Streamable HTTP transport.
+//!
+//! Lets one server process serve several agent sessions. Each client
+//! starts an MCP session by POSTing `initialize` to `/mcp`, naming its
+//! Maestro session in the `X-Maestro-*` headers, and gets back an
+//! `Mcp-Session-Id` header to send with every later request. Responses are
+//! plain JSON, or an SSE stream when the client accepts `text/event-stream`.
+//!
+//! The server never sends requests of its own, so `GET /mcp` (the
+//! server-to-client stream) is not offered.
+//!
+//! Clients that go away without a `DELETE` leave their session behind, so
+//! sessions with no request for `SESSION_IDLE_TIMEOUT` are dropped.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::mcp_protocol::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, McpError, McpServer, SUPPORTED_PROTOCOL_VERSIONS,
+};
+
+/// The single MCP endpoint.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Assigned on `initialize`, echoed by the client on every later request.
+pub const SESSION_HEADER: &str = "mcp-session-id";
+/// Protocol version the client negotiated, sent after `initialize`.
+pub const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// Maestro session the client belongs to.
+pub const MAESTRO_SESSION_HEADER: &str = "x-maestro-session-id";
+/// Token that authenticates the session's status reports.
+pub const MAESTRO_TOKEN_HEADER: &str = "x-maestro-status-token";
+/// Directory the agent runs in, for resources and prompts.
+pub const MAESTRO_WORKING_DIR_HEADER: &str = "x-maestro-working-dir";
+
+/// How long an MCP session may go without a request before it is dropped.
+/// Sessions with a request still running (e.g. a long `maestro_ask_user`)
+/// are never idle.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// How often idle sessions are looked for.
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Settings shared by every session the server hosts. The session ID and
+/// token are only defaults, used when `initialize` doesn't name them.
+#[derive(Debug, Clone, Default)]
+pub struct HttpConfig {
+    pub status_url: Option<String>,
+    pub instance_id: Option<String>,
+    pub session_id: Option<u32>,
+    pub status_token: Option<String>,
+}
+
+struct HttpSession {
+    server: Arc<McpServer>,
+    last_request: Instant,
+}
+
+struct HttpState {
+    config: HttpConfig,
+    /// Live MCP sessions by `Mcp-Session-Id`.
+    sessions: Mutex<HashMap<String, HttpSession>>,
+}
+
+impl HttpState {
+    /// Drops sessions that are not handling a request and, as of `now`,
+    /// had none for `idle_timeout`. Returns how many were dropped.
+    fn evict_idle(&self, now: Instant, idle_timeout: Duration) -> usize {
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, session| {
+            session.server.is_busy()
+                || now.saturating_duration_since(session.last_request) < idle_timeout
+        });
+        before - sessions.len()
+    }
+}
+
+/// Evicts idle sessions every `EVICTION_INTERVAL` until the router is gone.
+async fn evict_idle_sessions(state: Weak<HttpState>) {
+    let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let evicted = state.evict_idle(Instant::now(), SESSION_IDLE_TIMEOUT);
+        if evicted > 0 {
+            eprintln!(
+                "[maestro-mcp-server] Dropped {} idle MCP session(s)",
+                evicted
+            );
+        }
+    }
+}
+
+/// Router serving [`MCP_PATH`]. Must be called within a Tokio runtime,
+/// which runs the idle session eviction.
+pub fn router(config: HttpConfig) -> Router {
+    let state = Arc::new(HttpState {
+        config,
+        sessions: Mutex::new(HashMap::new()),
+    });
+    tokio::spawn(evict_idle_sessions(Arc::downgrade(&state)));
+    Router::new()
+        .route(
+            MCP_PATH,
+            post(handle_post).delete(handle_delete).get(|| async {
+                (
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    [(header::ALLOW, "POST, DELETE")],
+                )
+            }),
+        )
+        .with_state(state)
+}
+
+/// Bind `addr`, print the endpoint URL on stdout so whoever started us can
+/// find it, and serve until the process exits.
+pub async fn serve(addr: &str, config: HttpConfig) -> Result<(), McpError> {
+    let listener = TcpListener::bind(addr).await?;
+    let url = format!("http://{}{}", listener.local_addr()?, MCP_PATH);
+    println!("{}", url);
+    eprintln!("[maestro-mcp-server] Serving Streamable HTTP on {}", url);
+    axum::serve(listener, router(config)).await?;
+    Ok(())
+}
+
+async fn handle_post(
+    State(state): State<Arc<HttpState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(rejection) = check_origin(&headers) {
+        return rejection.into_response();
+    }
+    if let Some(version) = headers.get(PROTOCOL_VERSION_HEADER) {
+        let supported = version
+            .to_str()
+            .is_ok_and(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(&v));
+        if !supported {
+            return (StatusCode::BAD_REQUEST, "Unsupported MCP-Protocol-Version").into_response();
+        }
+    }
+
+    let (messages, is_batch) = match serde_json::from_slice::<Value>(&body) {
+        Ok(Value::Array(items)) => (items, true),
+        Ok(item) => (vec![item], false),
+        Err(e) => {
+            return json_rpc_rejection(JsonRpcError::parse_error(format!("Parse error: {}", e)))
+        }
+    };
+
+    // Messages without a method are responses to server requests. We never
+    // send any, so there is nothing to do with them.
+    let mut requests = Vec::new();
+    for message in messages {
+        if message.get("method").is_none() {
+            continue;
+        }
+        match serde_json::from_value::<JsonRpcRequest>(message) {
+            Ok(request) => requests.push(request),
+            Err(e) => {
+                return json_rpc_rejection(JsonRpcError::invalid_request(format!(
+                    "Invalid request: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    let initializing = requests.iter().any(|r| r.method == "initialize");
+    let (server, new_session) = if initializing {
+        if is_batch {
+            return json_rpc_rejection(JsonRpcError::invalid_request(
+                "initialize must not be part of a batch",
+            ));
+        }
+        let server = Arc::new(session_server(&state.config, &headers));
+        let session_id = uuid::Uuid::new_v4().simple().to_string();
+        state.sessions.lock().unwrap().insert(
+            session_id.clone(),
+            HttpSession {
+                server: Arc::clone(&server),
+                last_request: Instant::now(),
+            },
+        );
+        (server, Some(session_id))
+    } else {
+        match find_session(&state, &headers) {
+            Ok((server, _)) => (server, None),
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+
+    let pending = FuturesUnordered::new();
+    for request in requests {
+        match request.id.clone() {
+            Some(id) => {
+                let (tx, rx) = oneshot::channel();
+                server.spawn_request(id, request, move |response| {
+                    let _ = tx.send(response);
+                });
+                pending.push(rx);
+            }
+            None => server.handle_notification(&request).await,
+        }
+    }
+
+    let mut response = if pending.is_empty() {
+        StatusCode::ACCEPTED.into_response()
+    } else if accepts_event_stream(&headers) {
+        // A cancelled request drops its sender, which just ends its part
+        // of the stream.
+        let events = pending.filter_map(|outcome| async move {
+            let response = outcome.ok()?;
+            let data = serde_json::to_string(&response).ok()?;
+            Some(Ok::<_, Infallible>(
+                Event::default().event("message").data(data),
+            ))
+        });
+        Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    } else {
+        let responses: Vec<JsonRpcResponse> = pending
+            .filter_map(|outcome| async move { outcome.ok() })
+            .collect()
+            .await;
+        match (is_batch, responses.len()) {
+            (_, 0) => StatusCode::ACCEPTED.into_response(),
+            (false, _) => Json(responses.into_iter().next()).into_response(),
+            (true, _) => Json(responses).into_response(),
+        }
+    };
+
+    if let Some(session_id) = new_session {
+        if let Ok(value) = HeaderValue::from_str(&session_id) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+/// Ends an MCP session, aborting anything it still has running.
+async fn handle_delete(State(state): State<Arc<HttpState>>, headers: HeaderMap) -> Response {
+    if let Err(rejection) = check_origin(&headers) {
+        return rejection.into_response();
+    }
+    match find_session(&state, &headers) {
+        Ok((server, session_id)) => {
+            state.sessions.lock().unwrap().remove(&session_id);
+            server.abort_all();
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// Builds the server for a new MCP session from the `X-Maestro-*` headers,
+/// falling back to the process-wide defaults.
+fn session_server(config: &HttpConfig, headers: &HeaderMap) -> McpServer {
+    let header_str = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let session_id = header_str(MAESTRO_SESSION_HEADER)
+        .and_then(|v| v.parse().ok())
+        .or(config.session_id);
+    let status_token = header_str(MAESTRO_TOKEN_HEADER)
+        .map(str::to_string)
+        .or_else(|| config.status_token.clone());
+
+    let server = McpServer::new(
+        config.status_url.clone(),
+        session_id,
+        config.instance_id.clone(),
+        status_token,
+    );
+    match header_str(MAESTRO_WORKING_DIR_HEADER) {
+        Some(dir) if !dir.is_empty() => server.with_working_dir(PathBuf::from(dir)),
+        _ => server,
+    }
+}
+
+fn find_session(
+    state: &HttpState,
+    headers: &HeaderMap,
+) -> Result<(Arc<McpServer>, String), (StatusCode, &'static str)> {
+    let Some(session_id) = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return Err((StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header"));
+    };
+    match state.sessions.lock().unwrap().get_mut(session_id) {
+        Some(session) => {
+            session.last_request = Instant::now();
+            Ok((Arc::clone(&session.server), session_id.to_string()))
+        }
+        None => Err((StatusCode::NOT_FOUND, "Unknown MCP session")),
+    }
+}
+
+/// Rejects browser requests from anywhere but this machine, so a web page
+/// can't drive a local agent's tools (DNS rebinding).
+fn check_origin(headers: &HeaderMap) -> Result<(), (StatusCode, &'static str)> {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return Ok(());
+    };
+    let host = origin
+        .to_str()
+        .ok()
+        .and_then(|o| o.split_once("://"))
+        .map(|(_, rest)| match rest.strip_prefix('[') {
+            Some(v6) => v6.split(']').next().unwrap_or(""),
+            None => rest.split(':').next().unwrap_or(""),
+        });
+    match host {
+        Some("localhost" | "127.0.0.1" | "::1") => Ok(()),
+        _ => Err((StatusCode::FORBIDDEN, "Origin not allowed")),
+    }
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains("text/event-stream"))
+}
+
+fn json_rpc_rejection(error: JsonRpcError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(JsonRpcResponse::new(Value::Null, Err(error))),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn start() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}{}", listener.local_addr().unwrap(), MCP_PATH);
+        tokio::spawn(async move {
+            axum::serve(listener, router(HttpConfig::default()))
+                .await
+                .unwrap();
+        });
+        url
+    }
+
+    fn rpc(id: u64, method: &str) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": {} })
+    }
+
+    async fn initialize(client: &reqwest::Client, url: &str) -> String {
+        let response = client
+            .post(url)
+            .header(MAESTRO_SESSION_HEADER, "7")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": { "protocolVersion": "2025-06-18" }
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let session = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"]["protocolVersion"], "2025-06-18");
+        session
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let url = start().await;
+        let client = reqwest::Client::new();
+        let session = initialize(&client, &url).await;
+
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 202);
+
+        let body: Value = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .header(PROTOCOL_VERSION_HEADER, "2025-06-18")
+            .json(&rpc(2, "tools/list"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["id"], 2);
+        assert!(body["result"]["tools"].as_array().unwrap().len() > 1);
+
+        let batch: Value = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&json!([rpc(3, "ping"), rpc(4, "ping")]))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(batch.as_array().unwrap().len(), 2);
+
+        let response = client
+            .delete(&url)
+            .header(SESSION_HEADER, &session)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .json(&rpc(5, "ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_response() {
+        let url = start().await;
+        let client = reqwest::Client::new();
+        let session = initialize(&client, &url).await;
+
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&rpc(2, "ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream"));
+        let text = response.text().await.unwrap();
+        let data = text
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let message: Value = serde_json::from_str(data).unwrap();
+        assert_eq!(message["id"], 2);
+        assert!(text.contains("event: message"));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_requests() {
+        let url = start().await;
+        let client = reqwest::Client::new();
+
+        // No session yet
+        let response = client
+            .post(&url)
+            .json(&rpc(1, "ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, "nope")
+            .json(&rpc(1, "ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        let response = client
+            .post(&url)
+            .header(header::ORIGIN, "https://evil.example")
+            .json(&rpc(1, "initialize"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 403);
+
+        let session = initialize(&client, &url).await;
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .header(PROTOCOL_VERSION_HEADER, "1999-01-01")
+            .json(&rpc(2, "ping"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &session)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body("{not json")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], -32700);
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), 405);
+    }
+
+    #[test]
+    fn test_idle_sessions_are_evicted() {
+        let state = HttpState {
+            config: HttpConfig::default(),
+            sessions: Mutex::new(HashMap::new()),
+        };
+        let start = Instant::now();
+        let later = start + SESSION_IDLE_TIMEOUT;
+        for (id, last_request) in [("stale", start), ("fresh", later)] {
+            state.sessions.lock().unwrap().insert(
+                id.to_string(),
+                HttpSession {
+                    server: Arc::new(McpServer::new(None, None, None, None)),
+                    last_request,
+                },
+            );
+        }
+
+        assert_eq!(state.evict_idle(later, SESSION_IDLE_TIMEOUT), 1);
+        let sessions = state.sessions.lock().unwrap();
+        assert!(sessions.contains_key("fresh"));
+        assert!(!sessions.contains_key("stale"));
+    }
+
+    #[test]
+    fn test_check_origin() {
+        let mut headers = HeaderMap::new();
+        assert!(check_origin(&headers).is_ok());
+        for allowed in [
+            "http://localhost:3000",
+            "http://127.0.0.1",
+            "http://[::1]:8080",
+        ] {
+            headers.insert(header::ORIGIN, HeaderValue::from_static(allowed));
+            assert!(check_origin(&headers).is_ok(), "{}", allowed);
+        }
+        for denied in ["http://localhost.evil.com", "null", "https://example.com"] {
+            headers.insert(header::ORIGIN, HeaderValue::from_static(denied));
+            assert!(check_origin(&headers).is_err(), "{}", denied);
+        }
+    }
+}
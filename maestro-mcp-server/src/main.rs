@@ -2,13 +2,49 @@
 //!
 //! This server implements the Model Context Protocol (MCP) over stdio,
 //! providing the `maestro_status` tool that reports agent status to
-//! the Maestro application via HTTP POST.
+//! the Maestro application via HTTP POST, and the `maestro_ask_user` tool
+//! that waits for the user's answer to a question shown in Maestro.
+//!
+//! With `--http [ADDR]` (or `MAESTRO_MCP_TRANSPORT=http`, listening on
+//! `MAESTRO_MCP_HTTP_ADDR`) it instead serves MCP Streamable HTTP, so one
+//! process can serve every session. The endpoint URL is printed on stdout.
 
+mod http_transport;
 mod mcp_protocol;
+mod prompts;
+mod resources;
 mod status_reporter;
 
+use http_transport::HttpConfig;
 use mcp_protocol::McpServer;
 use std::env;
+use std::sync::Arc;
+
+/// Where the HTTP transport listens unless told otherwise.
+const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:0";
+
+/// The address to serve HTTP on, or `None` for stdio.
+fn http_addr() -> Option<String> {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--http" {
+            return Some(
+                args.next()
+                    .filter(|a| !a.starts_with("--"))
+                    .unwrap_or_else(|| DEFAULT_HTTP_ADDR.to_string()),
+            );
+        }
+        if let Some(addr) = arg.strip_prefix("--http=") {
+            return Some(addr.to_string());
+        }
+    }
+    if env::var("MAESTRO_MCP_TRANSPORT").is_ok_and(|t| t.eq_ignore_ascii_case("http")) {
+        return Some(
+            env::var("MAESTRO_MCP_HTTP_ADDR").unwrap_or_else(|_| DEFAULT_HTTP_ADDR.to_string()),
+        );
+    }
+    None
+}
 
 #[tokio::main]
 async fn main() {
@@ -18,15 +54,31 @@ async fn main() {
         .ok()
         .and_then(|s| s.parse().ok());
     let instance_id = env::var("MAESTRO_INSTANCE_ID").ok();
+    let status_token = env::var("MAESTRO_STATUS_TOKEN").ok();
 
-    // Log configuration for debugging (to stderr so it doesn't interfere with MCP protocol)
+    // Log configuration for debugging (to stderr so it doesn't interfere with MCP protocol).
+    // The token itself is a secret, so only log whether it's set.
     eprintln!(
-        "[maestro-mcp-server] Starting with config: status_url={:?}, session_id={:?}, instance_id={:?}",
-        status_url, session_id, instance_id
+        "[maestro-mcp-server] Starting with config: status_url={:?}, session_id={:?}, instance_id={:?}, has_token={}",
+        status_url, session_id, instance_id, status_token.is_some()
     );
 
+    if let Some(addr) = http_addr() {
+        let config = HttpConfig {
+            status_url,
+            instance_id,
+            session_id,
+            status_token,
+        };
+        if let Err(e) = http_transport::serve(&addr, config).await {
+            eprintln!("[maestro-mcp-server] Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Create and run the MCP server
-    let server = McpServer::new(status_url, session_id, instance_id);
+    let server = Arc::new(McpServer::new(status_url, session_id, instance_id, status_token));
 
     if let Err(e) = server.run().await {
         eprintln!("[maestro-mcp-server] Error: {}", e);
@@ -1,14 +1,34 @@
-//! MCP protocol implementation over stdio.
+//! MCP protocol implementation.
 //!
-//! Implements the Model Context Protocol (MCP) JSON-RPC over stdio,
-//! providing the `maestro_status` tool for reporting agent state.
+//! Implements the Model Context Protocol (MCP) JSON-RPC over stdio (or, see
+//! `http_transport`, over Streamable HTTP),
+//! providing the `maestro_status` tool for reporting agent state,
+//! `maestro_progress` for reporting the agent's plan and position in it, the
+//! `maestro_ask_user` tool for asking the user a question through the
+//! Maestro UI, `maestro_list_sessions`, `maestro_send_message` and
+//! `maestro_read_messages` for coordinating with the project's other
+//! sessions, and the `maestro_*_task` tools for working the project's task
+//! board.
+//!
+//! Also serves session resources (see `resources`) and the project's task
+//! templates as prompts (see `prompts`), negotiates the protocol version
+//! with the client, and honors `notifications/cancelled`.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::io::{self, BufRead, Write};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
 
-use crate::status_reporter::StatusReporter;
+use crate::prompts;
+use crate::resources::{self, SessionInfo};
+use crate::status_reporter::{Delivery, ProgressUpdate, StatusError, StatusReporter};
 
 #[derive(Debug, Error)]
 pub enum McpError {
@@ -20,20 +40,37 @@ pub enum McpError {
     Status(#[from] crate::status_reporter::StatusError),
 }
 
+/// Protocol revisions this server speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Picks the protocol version to answer `initialize` with: the client's
+/// own if we support it, otherwise our newest, which the client may then
+/// reject.
+pub fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|version| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .find(|supported| **supported == version)
+        })
+        .copied()
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0])
+}
+
 /// JSON-RPC request structure.
 #[derive(Debug, Deserialize)]
-struct JsonRpcRequest {
+pub(crate) struct JsonRpcRequest {
     #[allow(dead_code)]
     jsonrpc: String,
-    id: Option<Value>,
-    method: String,
+    pub(crate) id: Option<Value>,
+    pub(crate) method: String,
     #[serde(default)]
     params: Value,
 }
 
 /// JSON-RPC response structure.
 #[derive(Debug, Serialize)]
-struct JsonRpcResponse {
+pub(crate) struct JsonRpcResponse {
     jsonrpc: String,
     id: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -42,15 +79,83 @@ struct JsonRpcResponse {
     error: Option<JsonRpcError>,
 }
 
+impl JsonRpcResponse {
+    pub(crate) fn new(id: Value, outcome: Result<Value, JsonRpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
-struct JsonRpcError {
+pub(crate) struct JsonRpcError {
     code: i32,
     message: String,
 }
 
+impl JsonRpcError {
+    pub(crate) fn parse_error(message: impl Into<String>) -> Self {
+        Self {
+            code: -32700,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            code: -32600,
+            message: message.into(),
+        }
+    }
+
+    fn method_not_found(method: &str) -> Self {
+        Self {
+            code: -32601,
+            message: format!("Method not found: {}", method),
+        }
+    }
+
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: -32602,
+            message: message.into(),
+        }
+    }
+
+    fn resource_not_found(uri: &str) -> Self {
+        Self {
+            code: -32002,
+            message: format!("Resource not found: {}", uri),
+        }
+    }
+}
+
+/// How long `maestro_ask_user` waits for an answer by default.
+const DEFAULT_ASK_TIMEOUT_SECS: u64 = 300;
+
+/// Upper bound on a caller-supplied `maestro_ask_user` timeout.
+const MAX_ASK_TIMEOUT_SECS: u64 = 3600;
+
+/// Longest single long-poll; Maestro caps waits at this anyway.
+const REPLY_POLL_SECS: u64 = 60;
+
+/// How long requests may keep running after the client closes stdin.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
 /// MCP server implementation.
 pub struct McpServer {
     status_reporter: StatusReporter,
+    session: SessionInfo,
+    /// Requests still being handled, keyed by their serialized JSON-RPC ID,
+    /// so `notifications/cancelled` can abort them.
+    in_flight: Mutex<HashMap<String, AbortHandle>>,
 }
 
 impl McpServer {
@@ -58,20 +163,53 @@ impl McpServer {
         status_url: Option<String>,
         session_id: Option<u32>,
         instance_id: Option<String>,
+        status_token: Option<String>,
     ) -> Self {
+        let working_dir = std::env::current_dir().unwrap_or_else(|_| ".".into());
         Self {
-            status_reporter: StatusReporter::new(status_url, session_id, instance_id),
+            status_reporter: StatusReporter::new(
+                status_url,
+                session_id,
+                instance_id.clone(),
+                status_token,
+            ),
+            session: SessionInfo {
+                session_id,
+                instance_id,
+                working_dir,
+            },
+            in_flight: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Use `dir` instead of the process's working directory as the
+    /// session's directory for resources and prompts.
+    pub fn with_working_dir(mut self, dir: PathBuf) -> Self {
+        self.session.working_dir = dir;
+        self
+    }
+
     /// Run the MCP server, reading from stdin and writing to stdout.
-    pub async fn run(&self) -> Result<(), McpError> {
-        let stdin = io::stdin();
-        let mut stdout = io::stdout();
+    ///
+    /// Each request is handled in its own task, so a long `maestro_ask_user`
+    /// doesn't hold up `ping`s and can be cancelled. Responses may therefore
+    /// arrive out of order.
+    pub async fn run(self: Arc<Self>) -> Result<(), McpError> {
+        let (responses, mut outgoing) = mpsc::unbounded_channel::<JsonRpcResponse>();
+        let writer = tokio::spawn(async move {
+            let mut stdout = tokio::io::stdout();
+            while let Some(response) = outgoing.recv().await {
+                let mut output = serde_json::to_string(&response)?;
+                output.push('\n');
+                stdout.write_all(output.as_bytes()).await?;
+                stdout.flush().await?;
+            }
+            Ok::<(), McpError>(())
+        });
 
-        for line in stdin.lock().lines() {
-            let line = line?;
-            if line.is_empty() {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
                 continue;
             }
 
@@ -83,66 +221,144 @@ impl McpServer {
                 }
             };
 
-            let response = self.handle_request(&request).await;
+            match request.id.clone() {
+                Some(id) => {
+                    let responses = responses.clone();
+                    self.spawn_request(id, request, move |response| {
+                        let _ = responses.send(response);
+                    });
+                }
+                None => self.handle_notification(&request).await,
+            }
+        }
 
-            if let Some(resp) = response {
-                let output = serde_json::to_string(&resp)?;
-                writeln!(stdout, "{}", output)?;
-                stdout.flush()?;
+        // The client closed stdin. Let quick requests finish, but don't keep
+        // it waiting on long ones like `maestro_ask_user`.
+        drop(responses);
+        let mut writer = writer;
+        match tokio::time::timeout(SHUTDOWN_GRACE, &mut writer).await {
+            Ok(result) => result.unwrap_or(Ok(())),
+            Err(_) => {
+                self.abort_all();
+                writer.await.unwrap_or(Ok(()))
             }
         }
+    }
 
-        Ok(())
+    /// Handle a request in its own task, passing its response to `deliver`
+    /// unless it gets cancelled first.
+    pub(crate) fn spawn_request(
+        self: &Arc<Self>,
+        id: Value,
+        request: JsonRpcRequest,
+        deliver: impl FnOnce(JsonRpcResponse) + Send + 'static,
+    ) {
+        let key = id.to_string();
+        let server = Arc::clone(self);
+
+        // Hold the lock until the handle is registered, so a quick request
+        // can't finish before there is an entry for it to remove.
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let task_key = key.clone();
+        let task = tokio::spawn(async move {
+            let response = server.handle_request(id, &request).await;
+            // Gone if the request was cancelled in the meantime
+            let wanted = server.in_flight.lock().unwrap().remove(&task_key).is_some();
+            if wanted {
+                deliver(response);
+            }
+        });
+        in_flight.insert(key, task.abort_handle());
     }
 
-    /// Handle a single JSON-RPC request.
-    async fn handle_request(&self, request: &JsonRpcRequest) -> Option<JsonRpcResponse> {
-        // Notifications (no id) don't get responses
-        let id = request.id.clone()?;
+    /// Whether any request is still being handled.
+    pub(crate) fn is_busy(&self) -> bool {
+        !self.in_flight.lock().unwrap().is_empty()
+    }
 
-        let (result, error) = match request.method.as_str() {
-            "initialize" => (Some(self.handle_initialize()), None),
+    /// Abort every request still being handled, without responding.
+    pub(crate) fn abort_all(&self) {
+        for (_, task) in self.in_flight.lock().unwrap().drain() {
+            task.abort();
+        }
+    }
+
+    /// Handle a notification. Notifications never get a response.
+    pub(crate) async fn handle_notification(&self, request: &JsonRpcRequest) {
+        match request.method.as_str() {
             "notifications/initialized" => {
                 // Auto-report "idle" status when Claude connects
                 eprintln!("[maestro-mcp-server] Initialized - reporting idle status");
                 let _ = self.status_reporter.report_status("idle", "Ready", None).await;
-                return None;
-            }
-            "tools/list" => (Some(self.handle_tools_list()), None),
-            "tools/call" => match self.handle_tools_call(&request.params).await {
-                Ok(result) => (Some(result), None),
-                Err(e) => (
-                    None,
-                    Some(JsonRpcError {
-                        code: -32000,
-                        message: e.to_string(),
-                    }),
-                ),
-            },
-            "ping" => (Some(json!({})), None),
-            _ => (
-                None,
-                Some(JsonRpcError {
-                    code: -32601,
-                    message: format!("Method not found: {}", request.method),
-                }),
-            ),
-        };
+            }
+            "notifications/cancelled" => {
+                let Some(request_id) = request.params.get("requestId") else {
+                    return;
+                };
+                let task = self
+                    .in_flight
+                    .lock()
+                    .unwrap()
+                    .remove(&request_id.to_string());
+                if let Some(task) = task {
+                    task.abort();
+                    eprintln!(
+                        "[maestro-mcp-server] Cancelled request {}: {}",
+                        request_id,
+                        request
+                            .params
+                            .get("reason")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("no reason given")
+                    );
+                }
+            }
+            other => {
+                eprintln!("[maestro-mcp-server] Ignoring notification {}", other);
+            }
+        }
+    }
 
-        Some(JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id,
-            result,
-            error,
-        })
+    /// Handle a single JSON-RPC request.
+    async fn handle_request(&self, id: Value, request: &JsonRpcRequest) -> JsonRpcResponse {
+        let params = &request.params;
+        let outcome = match request.method.as_str() {
+            "initialize" => Ok(self.handle_initialize(params)),
+            "tools/list" => Ok(self.handle_tools_list()),
+            "tools/call" => self.handle_tools_call(params).await.map_err(|e| JsonRpcError {
+                code: -32000,
+                message: e.to_string(),
+            }),
+            "resources/list" => Ok(resources::list()),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": [] })),
+            "resources/read" => {
+                let uri = params.get("uri").and_then(|v| v.as_str()).unwrap_or("");
+                resources::read(uri, &self.session)
+                    .await
+                    .ok_or_else(|| JsonRpcError::resource_not_found(uri))
+            }
+            "prompts/list" => Ok(self.handle_prompts_list().await),
+            "prompts/get" => self.handle_prompts_get(params).await,
+            "ping" => Ok(json!({})),
+            method => Err(JsonRpcError::method_not_found(method)),
+        };
+        JsonRpcResponse::new(id, outcome)
     }
 
     /// Handle the initialize request.
-    fn handle_initialize(&self) -> Value {
+    fn handle_initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+        let version = negotiate_protocol_version(requested);
+        eprintln!(
+            "[maestro-mcp-server] Client offered protocol {:?}, using {}",
+            requested, version
+        );
         json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": version,
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "resources": {},
+                "prompts": {}
             },
             "serverInfo": {
                 "name": "maestro-mcp-server",
@@ -151,6 +367,58 @@ impl McpServer {
         })
     }
 
+    /// Handle the prompts/list request.
+    async fn handle_prompts_list(&self) -> Value {
+        let dir = self.session.working_dir.clone();
+        let templates = tokio::task::spawn_blocking(move || {
+            prompts::load_templates(&prompts::template_roots(&dir))
+        })
+        .await
+        .unwrap_or_default();
+        let entries: Vec<Value> = templates.iter().map(|t| t.to_list_entry()).collect();
+        json!({ "prompts": entries })
+    }
+
+    /// Handle the prompts/get request.
+    async fn handle_prompts_get(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        let arguments = params
+            .get("arguments")
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        let dir = self.session.working_dir.clone();
+        let lookup = name.clone();
+        let template = tokio::task::spawn_blocking(move || {
+            prompts::find_template(&prompts::template_roots(&dir), &lookup)
+        })
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("Unknown prompt: {}", name)))?;
+
+        let text = template
+            .render(&arguments)
+            .map_err(JsonRpcError::invalid_params)?;
+        Ok(json!({
+            "description": template.description,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": {
+                        "type": "text",
+                        "text": text
+                    }
+                }
+            ]
+        }))
+    }
+
     /// Handle the tools/list request.
     fn handle_tools_list(&self) -> Value {
         json!({
@@ -177,6 +445,166 @@ impl McpServer {
                         },
                         "required": ["state", "message"]
                     }
+                },
+                {
+                    "name": "maestro_progress",
+                    "description": "Report your plan and how far through it you are, so the Maestro UI can show a progress bar and step list. Send the whole plan each time; it may change as you go.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "steps": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Your plan, one short line per step"
+                            },
+                            "currentStep": {
+                                "type": "integer",
+                                "description": "Zero-based index of the step you are on; the number of steps once all are done"
+                            },
+                            "percent": {
+                                "type": "integer",
+                                "description": "Overall percent complete (0-100), if you can estimate it"
+                            },
+                            "files": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Files you have touched so far, relative to the worktree"
+                            },
+                            "prUrl": {
+                                "type": "string",
+                                "description": "URL of the pull request you opened, if any"
+                            }
+                        },
+                        "required": ["steps", "currentStep"]
+                    }
+                },
+                {
+                    "name": "maestro_ask_user",
+                    "description": "Ask the user a question in the Maestro UI and wait for their answer. Use this instead of asking in the terminal when you are blocked on a decision.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "question": {
+                                "type": "string",
+                                "description": "The question to show the user"
+                            },
+                            "timeoutSeconds": {
+                                "type": "integer",
+                                "description": "How long to wait for an answer (default 300, max 3600)"
+                            }
+                        },
+                        "required": ["question"]
+                    }
+                },
+                {
+                    "name": "maestro_list_sessions",
+                    "description": "List the other agent sessions working on this project in Maestro, with their mode, branch and status.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "maestro_send_message",
+                    "description": "Send a message to another session of this project, e.g. to hand it a task or report a result. Use maestro_list_sessions to find session IDs.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "toSession": {
+                                "type": "integer",
+                                "description": "ID of the session to send to"
+                            },
+                            "text": {
+                                "type": "string",
+                                "description": "The message"
+                            }
+                        },
+                        "required": ["toSession", "text"]
+                    }
+                },
+                {
+                    "name": "maestro_read_messages",
+                    "description": "Read (and clear) the messages other sessions have sent you.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "waitSeconds": {
+                                "type": "integer",
+                                "description": "If no message is waiting, wait up to this long for one (default 0, max 60)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "maestro_list_tasks",
+                    "description": "List the tasks on this project's Maestro task board with their status and assignee.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "maestro_create_task",
+                    "description": "Add a task to this project's task board for any session to pick up.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "body": {
+                                "type": "string",
+                                "description": "What needs to be done"
+                            }
+                        },
+                        "required": ["body"]
+                    }
+                },
+                {
+                    "name": "maestro_claim_task",
+                    "description": "Claim a task from this project's task board so no other session works on it. Claims the oldest open task unless taskId is given. Tasks still claimed when this session ends go back to the board.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "taskId": {
+                                "type": "integer",
+                                "description": "Specific task to claim"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "maestro_complete_task",
+                    "description": "Mark a task you claimed as done.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "taskId": {
+                                "type": "integer",
+                                "description": "The task you finished"
+                            },
+                            "result": {
+                                "type": "string",
+                                "description": "Short summary of the outcome"
+                            }
+                        },
+                        "required": ["taskId"]
+                    }
+                },
+                {
+                    "name": "maestro_fail_task",
+                    "description": "Mark a task you claimed as failed, e.g. when it turns out to be impossible.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "taskId": {
+                                "type": "integer",
+                                "description": "The task that failed"
+                            },
+                            "reason": {
+                                "type": "string",
+                                "description": "Why it failed"
+                            }
+                        },
+                        "required": ["taskId"]
+                    }
                 }
             ]
         })
@@ -209,19 +637,50 @@ impl McpServer {
                     .map(|s| s.to_string());
 
                 // Report status via HTTP
-                self.status_reporter
+                let delivery = self
+                    .status_reporter
                     .report_status(state, message, needs_input_prompt)
                     .await?;
 
+                let text = match delivery {
+                    Delivery::Sent => format!("Status reported: {} - {}", state, message),
+                    Delivery::Queued => format!(
+                        "Status queued: {} - {} (Maestro is unreachable; it will be \
+                         delivered when Maestro is back)",
+                        state, message
+                    ),
+                };
                 Ok(json!({
                     "content": [
                         {
                             "type": "text",
-                            "text": format!("Status reported: {} - {}", state, message)
+                            "text": text
                         }
                     ]
                 }))
             }
+            "maestro_progress" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.report_progress(&arguments).await
+            }
+            "maestro_ask_user" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.ask_user(&arguments).await
+            }
+            "maestro_list_sessions" => self.list_sessions().await,
+            "maestro_send_message" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.send_message(&arguments).await
+            }
+            "maestro_read_messages" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.read_messages(&arguments).await
+            }
+            "maestro_list_tasks" | "maestro_create_task" | "maestro_claim_task"
+            | "maestro_complete_task" | "maestro_fail_task" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                self.task_tool(name, &arguments).await
+            }
             _ => Ok(json!({
                 "content": [
                     {
@@ -233,4 +692,278 @@ impl McpServer {
             })),
         }
     }
+
+    /// Handle `maestro_progress`.
+    async fn report_progress(&self, arguments: &Value) -> Result<Value, McpError> {
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error("Not connected to Maestro"));
+        }
+        let strings = |key: &str| -> Vec<String> {
+            arguments
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| item.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let steps = strings("steps");
+        let Some(current_step) = arguments.get("currentStep").and_then(|v| v.as_u64()) else {
+            return Ok(tool_error("currentStep is required"));
+        };
+        let percent = arguments.get("percent").and_then(|v| v.as_u64());
+        let progress = ProgressUpdate {
+            current_step: current_step as usize,
+            percent,
+            files: strings("files"),
+            pr_url: arguments
+                .get("prUrl")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            steps,
+        };
+
+        match self.status_reporter.report_progress(&progress).await {
+            Ok(()) => {
+                let position = match progress.steps.get(progress.current_step) {
+                    Some(step) => format!(
+                        "step {}/{}: {}",
+                        progress.current_step + 1,
+                        progress.steps.len(),
+                        step
+                    ),
+                    None => "all steps done".to_string(),
+                };
+                Ok(tool_text(&format!("Progress reported: {}", position)))
+            }
+            Err(StatusError::Rejected(message)) => Ok(tool_error(&message)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handle `maestro_ask_user`: show the question as a `needs_input`
+    /// prompt and long-poll Maestro until the user answers or the timeout
+    /// passes.
+    async fn ask_user(&self, arguments: &Value) -> Result<Value, McpError> {
+        let question = arguments
+            .get("question")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim();
+        if question.is_empty() {
+            return Ok(tool_error("question must not be empty"));
+        }
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error(
+                "Not connected to Maestro; ask the user in the terminal instead",
+            ));
+        }
+        let timeout_secs = arguments
+            .get("timeoutSeconds")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_ASK_TIMEOUT_SECS)
+            .clamp(1, MAX_ASK_TIMEOUT_SECS);
+
+        // Drop answers left over from an earlier prompt before asking
+        if let Err(e) = self.status_reporter.fetch_replies(Duration::ZERO).await {
+            return Ok(tool_error(&format!(
+                "Could not reach Maestro ({}); ask the user in the terminal instead",
+                e
+            )));
+        }
+        self.status_reporter.report_question(question).await?;
+
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            let wait = remaining.min(Duration::from_secs(REPLY_POLL_SECS));
+            let replies = self.status_reporter.fetch_replies(wait).await?;
+            if !replies.is_empty() {
+                let answer = replies
+                    .into_iter()
+                    .map(|r| r.reply)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.status_reporter
+                    .report_status("working", "Received answer", None)
+                    .await?;
+                return Ok(json!({
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": answer
+                        }
+                    ]
+                }));
+            }
+        }
+
+        self.status_reporter
+            .report_status("working", "No answer received", None)
+            .await?;
+        Ok(json!({
+            "content": [
+                {
+                    "type": "text",
+                    "text": format!("The user did not answer within {} seconds.", timeout_secs)
+                }
+            ]
+        }))
+    }
+
+    /// Handle `maestro_list_sessions`.
+    async fn list_sessions(&self) -> Result<Value, McpError> {
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error("Not connected to Maestro"));
+        }
+        let peers = self.status_reporter.list_peers().await?;
+        if peers.is_empty() {
+            return Ok(tool_text("No other sessions are running for this project."));
+        }
+        Ok(tool_text(&serde_json::to_string_pretty(&peers)?))
+    }
+
+    /// Handle `maestro_send_message`.
+    async fn send_message(&self, arguments: &Value) -> Result<Value, McpError> {
+        let Some(to_session) = arguments
+            .get("toSession")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+        else {
+            return Ok(tool_error("toSession must be a session ID"));
+        };
+        let text = arguments.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        if text.trim().is_empty() {
+            return Ok(tool_error("text must not be empty"));
+        }
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error("Not connected to Maestro"));
+        }
+
+        match self.status_reporter.send_message(to_session, text).await {
+            Ok(id) => Ok(tool_text(&format!(
+                "Message {} sent to session {}",
+                id, to_session
+            ))),
+            Err(StatusError::Status(404)) => Ok(tool_error(&format!(
+                "Session {} is not another session of this project",
+                to_session
+            ))),
+            Err(StatusError::Status(413)) => Ok(tool_error("Message is too long")),
+            Err(StatusError::Status(429)) => Ok(tool_error(&format!(
+                "Session {} has too many unread messages",
+                to_session
+            ))),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handle `maestro_read_messages`.
+    async fn read_messages(&self, arguments: &Value) -> Result<Value, McpError> {
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error("Not connected to Maestro"));
+        }
+        let wait_secs = arguments
+            .get("waitSeconds")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            .min(REPLY_POLL_SECS);
+
+        let messages = self
+            .status_reporter
+            .fetch_messages(Duration::from_secs(wait_secs))
+            .await?;
+        if messages.is_empty() {
+            return Ok(tool_text("No new messages."));
+        }
+        let text = messages
+            .iter()
+            .map(|m| format!("[from session {}] {}", m.from_session, m.text))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(tool_text(&text))
+    }
+
+    /// Handle the task board tools.
+    async fn task_tool(&self, name: &str, arguments: &Value) -> Result<Value, McpError> {
+        if !self.status_reporter.is_configured() {
+            return Ok(tool_error("Not connected to Maestro"));
+        }
+        let task_id = arguments.get("taskId").and_then(|v| v.as_u64());
+        let text = |key: &str| arguments.get(key).and_then(|v| v.as_str());
+
+        let result = match name {
+            "maestro_list_tasks" => self.status_reporter.list_tasks().await,
+            "maestro_create_task" => {
+                self.status_reporter
+                    .create_task(text("body").unwrap_or(""))
+                    .await
+            }
+            "maestro_claim_task" => self.status_reporter.claim_task(task_id).await,
+            _ => {
+                let Some(task_id) = task_id else {
+                    return Ok(tool_error("taskId is required"));
+                };
+                if name == "maestro_complete_task" {
+                    self.status_reporter
+                        .finish_task(task_id, true, text("result"))
+                        .await
+                } else {
+                    self.status_reporter
+                        .finish_task(task_id, false, text("reason"))
+                        .await
+                }
+            }
+        };
+
+        match result {
+            Ok(value) => Ok(tool_text(&serde_json::to_string_pretty(&value)?)),
+            Err(StatusError::Rejected(message)) => Ok(tool_error(&message)),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// A successful tool result with a single text block.
+fn tool_text(text: &str) -> Value {
+    json!({
+        "content": [
+            {
+                "type": "text",
+                "text": text
+            }
+        ]
+    })
+}
+
+/// A tool result reporting a failure to the model.
+fn tool_error(message: &str) -> Value {
+    json!({
+        "content": [
+            {
+                "type": "text",
+                "text": message
+            }
+        ],
+        "isError": true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_protocol_version() {
+        assert_eq!(negotiate_protocol_version(Some("2025-03-26")), "2025-03-26");
+        assert_eq!(negotiate_protocol_version(Some("2024-11-05")), "2024-11-05");
+        assert_eq!(negotiate_protocol_version(Some("1999-01-01")), "2025-06-18");
+        assert_eq!(negotiate_protocol_version(None), "2025-06-18");
+    }
 }
@@ -0,0 +1,222 @@
+//! MCP prompts served from the project's task templates.
+//!
+//! Every `.maestro/prompts/<name>.md` file in the worktree (or, failing
+//! that, the main checkout) becomes a prompt called `<name>`. The first
+//! non-empty line is its description, and each `{{placeholder}}` in the
+//! body becomes a required argument.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+/// Where templates live, relative to a checkout.
+pub const PROMPTS_DIR: &str = ".maestro/prompts";
+
+/// Longest description shown in `prompts/list`, in characters.
+const MAX_DESCRIPTION_CHARS: usize = 120;
+
+/// A task template loaded from disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub description: String,
+    /// Placeholder names, in order of first appearance.
+    pub arguments: Vec<String>,
+    pub body: String,
+}
+
+impl PromptTemplate {
+    pub fn parse(name: &str, body: &str) -> Self {
+        let description: String = body
+            .lines()
+            .map(|line| line.trim().trim_start_matches('#').trim())
+            .find(|line| !line.is_empty())
+            .unwrap_or(name)
+            .chars()
+            .take(MAX_DESCRIPTION_CHARS)
+            .collect();
+
+        let mut arguments: Vec<String> = Vec::new();
+        for placeholder in placeholders(body) {
+            if !arguments.iter().any(|a| a == placeholder) {
+                arguments.push(placeholder.to_string());
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            description,
+            arguments,
+            body: body.to_string(),
+        }
+    }
+
+    /// Fills in the placeholders. Fails naming the first missing argument.
+    pub fn render(&self, arguments: &Map<String, Value>) -> Result<String, String> {
+        let mut rendered = String::with_capacity(self.body.len());
+        let mut rest = self.body.as_str();
+        while let Some((before, name, after)) = next_placeholder(rest) {
+            let value = arguments
+                .get(name)
+                .ok_or_else(|| format!("Missing argument '{}'", name))?;
+            rendered.push_str(before);
+            match value {
+                Value::String(s) => rendered.push_str(s),
+                other => rendered.push_str(&other.to_string()),
+            }
+            rest = after;
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
+    /// Entry for `prompts/list`.
+    pub fn to_list_entry(&self) -> Value {
+        let arguments: Vec<Value> = self
+            .arguments
+            .iter()
+            .map(|name| json!({ "name": name, "required": true }))
+            .collect();
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "arguments": arguments
+        })
+    }
+}
+
+/// Splits `text` around its first `{{name}}` placeholder.
+fn next_placeholder(text: &str) -> Option<(&str, &str, &str)> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let inner_start = start + 2;
+        let len = text[inner_start..].find("}}")?;
+        let name = text[inner_start..inner_start + len].trim();
+        if is_valid_name(name) {
+            return Some((&text[..start], name, &text[inner_start + len + 2..]));
+        }
+        offset = inner_start;
+    }
+    None
+}
+
+fn placeholders(mut text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    while let Some((_, name, rest)) = next_placeholder(text) {
+        names.push(name);
+        text = rest;
+    }
+    names
+}
+
+/// Prompt and argument names: ASCII letters, digits, `-` and `_`.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Loads the templates from the first of `checkouts` that has a prompts
+/// directory, sorted by name.
+pub fn load_templates(checkouts: &[PathBuf]) -> Vec<PromptTemplate> {
+    let Some(dir) = checkouts
+        .iter()
+        .map(|checkout| checkout.join(PROMPTS_DIR))
+        .find(|dir| dir.is_dir())
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut templates: Vec<PromptTemplate> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            if !is_valid_name(&name) {
+                return None;
+            }
+            let body = std::fs::read_to_string(&path).ok()?;
+            Some(PromptTemplate::parse(&name, &body))
+        })
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
+
+/// Finds one template by name.
+pub fn find_template(checkouts: &[PathBuf], name: &str) -> Option<PromptTemplate> {
+    if !is_valid_name(name) {
+        return None;
+    }
+    load_templates(checkouts).into_iter().find(|t| t.name == name)
+}
+
+/// Checkouts to look for templates in: the worktree, then the main
+/// checkout it was created from, then the working directory itself.
+pub fn template_roots(working_dir: &Path) -> Vec<PathBuf> {
+    let git = crate::resources::GitContext::probe(working_dir);
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in [git.worktree_path, git.main_checkout, Some(working_dir.to_path_buf())]
+        .into_iter()
+        .flatten()
+    {
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let template = PromptTemplate::parse(
+            "review",
+            "\n# Review {{branch}}\n\nReview {{branch}} against {{ base }}. {{not valid}}",
+        );
+        assert_eq!(template.description, "Review {{branch}}");
+        assert_eq!(template.arguments, vec!["branch", "base"]);
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = PromptTemplate::parse("fix", "Fix issue #{{issue}} on {{branch}}");
+        let mut args = Map::new();
+        args.insert("issue".into(), json!(42));
+        args.insert("branch".into(), json!("main"));
+        assert_eq!(template.render(&args).unwrap(), "Fix issue #42 on main");
+
+        args.remove("branch");
+        assert_eq!(
+            template.render(&args).unwrap_err(),
+            "Missing argument 'branch'"
+        );
+    }
+
+    #[test]
+    fn test_load_templates() {
+        let dir = std::env::temp_dir().join(format!("maestro-prompts-{}", std::process::id()));
+        let prompts = dir.join(PROMPTS_DIR);
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(prompts.join("b-task.md"), "Second").unwrap();
+        std::fs::write(prompts.join("a-task.md"), "First {{x}}").unwrap();
+        std::fs::write(prompts.join("notes.txt"), "ignored").unwrap();
+
+        let templates = load_templates(&[dir.join("missing"), dir.clone()]);
+        let names: Vec<_> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["a-task", "b-task"]);
+        let roots = [dir.clone()];
+        assert!(find_template(&roots, "a-task").is_some());
+        assert!(find_template(&roots, "../a-task").is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+//! MCP resources describing the session the server belongs to.
+//!
+//! Agents read these instead of shelling out to find which Maestro session,
+//! branch and worktree they are running in. Branch and worktree are looked
+//! up with git on every read, since the agent may switch branches.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::{json, Value};
+
+/// Session ID, instance ID, branch and worktree as one JSON document.
+pub const SESSION_URI: &str = "maestro://session";
+/// The checked-out branch, as plain text.
+pub const BRANCH_URI: &str = "maestro://session/branch";
+/// Absolute path of the worktree the session runs in, as plain text.
+pub const WORKTREE_URI: &str = "maestro://session/worktree";
+
+/// What the server knows about its session.
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub session_id: Option<u32>,
+    pub instance_id: Option<String>,
+    /// Directory the agent (and so this server) was started in.
+    pub working_dir: PathBuf,
+}
+
+/// Result of `resources/list`.
+pub fn list() -> Value {
+    json!({
+        "resources": [
+            {
+                "uri": SESSION_URI,
+                "name": "session",
+                "title": "Maestro session",
+                "description": "This Maestro session's ID, branch and worktree",
+                "mimeType": "application/json"
+            },
+            {
+                "uri": BRANCH_URI,
+                "name": "branch",
+                "title": "Current branch",
+                "description": "The git branch checked out in this session",
+                "mimeType": "text/plain"
+            },
+            {
+                "uri": WORKTREE_URI,
+                "name": "worktree",
+                "title": "Worktree path",
+                "description": "Absolute path of the git worktree this session runs in",
+                "mimeType": "text/plain"
+            }
+        ]
+    })
+}
+
+/// Result of `resources/read`, or `None` for an unknown URI.
+pub async fn read(uri: &str, info: &SessionInfo) -> Option<Value> {
+    if ![SESSION_URI, BRANCH_URI, WORKTREE_URI].contains(&uri) {
+        return None;
+    }
+    let dir = info.working_dir.clone();
+    let git = tokio::task::spawn_blocking(move || GitContext::probe(&dir))
+        .await
+        .unwrap_or_default();
+
+    let (mime_type, text) = match uri {
+        SESSION_URI => {
+            let doc = json!({
+                "sessionId": info.session_id,
+                "instanceId": info.instance_id,
+                "workingDirectory": info.working_dir,
+                "branch": git.branch,
+                "worktreePath": git.worktree_path,
+                "isLinkedWorktree": git.is_linked_worktree,
+            });
+            ("application/json", serde_json::to_string_pretty(&doc).ok()?)
+        }
+        BRANCH_URI => ("text/plain", git.branch.unwrap_or_default()),
+        _ => {
+            let path = git.worktree_path.unwrap_or_else(|| info.working_dir.clone());
+            ("text/plain", path.to_string_lossy().into_owned())
+        }
+    };
+    Some(json!({
+        "contents": [
+            {
+                "uri": uri,
+                "mimeType": mime_type,
+                "text": text
+            }
+        ]
+    }))
+}
+
+/// Git facts about a directory. Fields are `None` outside a repository.
+#[derive(Debug, Default)]
+pub struct GitContext {
+    pub branch: Option<String>,
+    pub worktree_path: Option<PathBuf>,
+    /// Directory of the main checkout, which differs from `worktree_path`
+    /// in a linked worktree.
+    pub main_checkout: Option<PathBuf>,
+    pub is_linked_worktree: bool,
+}
+
+impl GitContext {
+    pub fn probe(dir: &Path) -> Self {
+        let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]);
+        let worktree_path = git(dir, &["rev-parse", "--show-toplevel"]).map(PathBuf::from);
+        let main_checkout = git(
+            dir,
+            &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        )
+        .and_then(|common| PathBuf::from(common).parent().map(Path::to_path_buf));
+        let is_linked_worktree = match (&worktree_path, &main_checkout) {
+            (Some(worktree), Some(main)) => !same_path(worktree, main),
+            _ => false,
+        };
+        Self {
+            branch,
+            worktree_path,
+            main_checkout,
+            is_linked_worktree,
+        }
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Runs git in `dir` and returns its trimmed stdout on success.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
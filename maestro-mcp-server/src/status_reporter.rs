@@ -3,18 +3,104 @@
 //! Reports agent status via HTTP POST to the Maestro application's
 //! status endpoint. This replaces the previous file-based approach
 //! to eliminate race conditions and provide real-time updates.
+//!
+//! Also reads back the user's answers to `needs_input` prompts from the
+//! status server's `/sessions/{id}/replies` endpoint, and exchanges
+//! messages with the other sessions of the project through their mailboxes
+//! and works the project's task board.
+//!
+//! Statuses that can't be delivered because Maestro is unreachable (e.g.
+//! while it restarts) wait in an in-process outbox, where a newer status
+//! replaces an older one of the same session. A background task retries
+//! them with exponential backoff, and tries again right away whenever
+//! another request gets through. Statuses for a session Maestro has not
+//! registered (yet) are kept the same way.
+//!
+//! A restarted Maestro may come back on another port of its default range.
+//! When the configured port stops answering, or answers for another
+//! Maestro instance (`410 Gone`), the reporter looks for its instance on
+//! the other ports of the range and moves there.
+//!
+//! `MAESTRO_STATUS_URL` is either the `http://` URL of the `/status` route
+//! or, when Maestro serves over a Unix domain socket, `unix:<socket path>`.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
-use serde::Serialize;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::sync::Notify;
+
+/// First wait before retrying queued statuses.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait between retries of queued statuses.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Ports Maestro's status server picks from by default.
+const STATUS_PORTS: RangeInclusive<u16> = 9900..=9999;
+
+/// How long to wait for each port while looking for Maestro.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
 
 #[derive(Debug, Error)]
 pub enum StatusError {
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
+    #[error("Request over Unix socket failed: {0}")]
+    Socket(String),
+    #[error("Request timed out")]
+    Timeout,
+    /// Maestro answered with an unsuccessful HTTP status.
+    #[error("Maestro answered HTTP {0}")]
+    Status(u16),
+    #[error("Invalid response from Maestro: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    #[error("Not connected to Maestro (MAESTRO_STATUS_URL or MAESTRO_SESSION_ID is not set)")]
+    NotConfigured,
+    /// Maestro refused the request; the message explains why.
+    #[error("{0}")]
+    Rejected(String),
+}
+
+impl StatusError {
+    /// Whether Maestro may accept the same request later, e.g. because it
+    /// is restarting and has not registered the session again yet (409),
+    /// or moved to another port (410).
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::HttpError(_) | Self::Socket(_) | Self::Timeout => true,
+            Self::Status(code) => *code >= 500 || matches!(*code, 409 | 410 | 429),
+            _ => false,
+        }
+    }
+
+    /// Whether the request did not reach our Maestro instance at all, so it
+    /// may be listening somewhere else.
+    fn is_misdirected(&self) -> bool {
+        match self {
+            Self::HttpError(_) | Self::Socket(_) | Self::Timeout => true,
+            Self::Status(code) => *code == 410,
+            _ => false,
+        }
+    }
+}
+
+/// What became of a reported status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Maestro accepted it.
+    Sent,
+    /// Maestro is unreachable; it will be sent once Maestro is back.
+    Queued,
 }
 
 /// Payload sent to Maestro's status endpoint.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct StatusPayload {
     pub session_id: u32,
     pub instance_id: String,
@@ -22,15 +108,308 @@ pub struct StatusPayload {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub needs_input_prompt: Option<String>,
+    /// The agent asked through `maestro_ask_user` and waits for the answer.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub awaiting_reply: bool,
     pub timestamp: String,
 }
 
-/// Reports status to Maestro via HTTP POST.
-pub struct StatusReporter {
+/// A user's answer to a `needs_input` prompt, as returned by Maestro.
+#[derive(Debug, Deserialize)]
+pub struct PromptReply {
+    #[allow(dead_code)]
+    pub prompt: Option<String>,
+    pub reply: String,
+    #[allow(dead_code)]
+    pub timestamp: u64,
+}
+
+/// Another session of the same project.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PeerSession {
+    pub session_id: u32,
+    pub mode: Option<String>,
+    pub branch: Option<String>,
+    pub status: Option<String>,
+    pub message: Option<String>,
+}
+
+/// A message received from another session.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AgentMessage {
+    pub id: u64,
+    pub from_session: u32,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+/// The agent's plan and position, as sent by `maestro_progress`.
+#[derive(Debug)]
+pub struct ProgressUpdate {
+    pub steps: Vec<String>,
+    /// Index into `steps`; `steps.len()` once all are done.
+    pub current_step: usize,
+    pub percent: Option<u64>,
+    pub files: Vec<String>,
+    pub pr_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SendMessageResponse {
+    message_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstanceInfo {
+    instance_id: String,
+}
+
+/// Where Maestro's status server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Endpoint {
+    /// Base URL the routes hang off, e.g. `http://127.0.0.1:9900`.
+    Http(String),
+    /// Path of the Unix domain socket.
+    Unix(std::path::PathBuf),
+}
+
+impl Endpoint {
+    fn parse(status_url: &str) -> Self {
+        match status_url.strip_prefix("unix:") {
+            Some(path) => Self::Unix(path.trim_start_matches("//").into()),
+            None => Self::Http(
+                status_url
+                    .strip_suffix("/status")
+                    .unwrap_or(status_url)
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/// Status code and body of a response from Maestro.
+struct Reply {
+    status: StatusCode,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    /// The body as JSON, or `Status` for an unsuccessful response.
+    fn json<T: DeserializeOwned>(self) -> Result<T, StatusError> {
+        if !self.status.is_success() {
+            return Err(StatusError::Status(self.status.as_u16()));
+        }
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// How to reach Maestro's status server.
+#[derive(Clone)]
+struct Connection {
     client: reqwest::Client,
+    /// Shared by all clones, so moving to another port moves them all.
+    endpoint: Arc<RwLock<Endpoint>>,
+    /// ID of the Maestro instance that started us.
+    instance_id: Option<String>,
+    /// Per-session secret sent as a bearer token.
+    status_token: Option<String>,
+}
+
+impl Connection {
+    /// Send one request to the status server, with the session's bearer
+    /// token if it has one.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<Reply, StatusError> {
+        let token = self.status_token.as_deref();
+        let endpoint = self.endpoint().clone();
+        match &endpoint {
+            Endpoint::Http(base) => {
+                let mut request = self
+                    .client
+                    .request(method, format!("{}{}", base, path))
+                    .timeout(timeout);
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                if let Some(body) = body {
+                    request = request
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(body);
+                }
+                let response = request.send().await?;
+                let status = response.status();
+                let body = response.bytes().await?.to_vec();
+                Ok(Reply { status, body })
+            }
+            Endpoint::Unix(socket) => {
+                tokio::time::timeout(timeout, unix_request(socket, method, path, token, body))
+                    .await
+                    .map_err(|_| StatusError::Timeout)?
+            }
+        }
+    }
+
+    /// POST a status to `/status`.
+    async fn post_status(&self, payload: &StatusPayload) -> Result<(), StatusError> {
+        let body = serde_json::to_vec(payload)?;
+        let reply = self
+            .send(Method::POST, "/status", Some(body), Duration::from_secs(5))
+            .await?;
+        eprintln!("[maestro-mcp-server] Status response: {}", reply.status);
+        if !reply.status.is_success() {
+            return Err(StatusError::Status(reply.status.as_u16()));
+        }
+        Ok(())
+    }
+
+    fn endpoint(&self) -> std::sync::RwLockReadGuard<'_, Endpoint> {
+        self.endpoint.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Look for our Maestro instance on the other ports of its default
+    /// range, and send requests there from now on. Returns whether it was
+    /// found somewhere new.
+    async fn relocate(&self) -> bool {
+        let Some(instance_id) = &self.instance_id else {
+            return false;
+        };
+        let Endpoint::Http(base) = self.endpoint().clone() else {
+            return false;
+        };
+        let Ok(mut url) = reqwest::Url::parse(&base) else {
+            return false;
+        };
+        let current = url.port_or_known_default();
+        // Only a local Maestro can have moved within its port range
+        let local = url.host_str().is_some_and(|host| {
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            host == "localhost"
+                || host
+                    .parse::<std::net::IpAddr>()
+                    .is_ok_and(|ip| ip.is_loopback())
+        });
+        if !local {
+            return false;
+        }
+
+        for port in STATUS_PORTS.filter(|port| Some(*port) != current) {
+            if url.set_port(Some(port)).is_err() {
+                return false;
+            }
+            let probe = url.as_str().trim_end_matches('/').to_string();
+            let found = match self
+                .client
+                .get(format!("{}/instance", probe))
+                .timeout(PROBE_TIMEOUT)
+                .send()
+                .await
+            {
+                Ok(response) => response
+                    .json::<InstanceInfo>()
+                    .await
+                    .is_ok_and(|info| &info.instance_id == instance_id),
+                Err(_) => false,
+            };
+            if found {
+                eprintln!("[maestro-mcp-server] Maestro moved to {}", probe);
+                *self.endpoint.write().unwrap_or_else(|e| e.into_inner()) = Endpoint::Http(probe);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Statuses waiting for Maestro to become reachable again.
+#[derive(Default)]
+struct Outbox {
+    state: Mutex<OutboxState>,
+    /// Cuts the flush task's backoff short.
+    wakeup: Notify,
+}
+
+#[derive(Default)]
+struct OutboxState {
+    /// Latest undelivered status of each session.
+    pending: HashMap<u32, StatusPayload>,
+    /// Whether a flush task is running.
+    flushing: bool,
+}
+
+impl Outbox {
+    fn has_pending(&self, session_id: u32) -> bool {
+        self.lock().pending.contains_key(&session_id)
+    }
+
+    /// Queue `payload` in place of any older status of its session. Returns
+    /// whether a flush task needs to be started.
+    fn push(&self, payload: StatusPayload) -> bool {
+        let mut state = self.lock();
+        state.pending.insert(payload.session_id, payload);
+        !std::mem::replace(&mut state.flushing, true)
+    }
+
+    /// Retry queued statuses until none are left.
+    async fn flush(self: Arc<Self>, connection: Connection) {
+        let mut delay = INITIAL_RETRY_DELAY;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = self.wakeup.notified() => {}
+            }
+
+            let queued: Vec<StatusPayload> = self.lock().pending.values().cloned().collect();
+            let mut unreachable = false;
+            for payload in queued {
+                match connection.post_status(&payload).await {
+                    Ok(()) => {}
+                    Err(e) if e.is_transient() => {
+                        // Retry at once if Maestro turns up on another port
+                        unreachable = !(e.is_misdirected() && connection.relocate().await);
+                        break;
+                    }
+                    Err(e) => eprintln!(
+                        "[maestro-mcp-server] Dropping queued status for session {}: {}",
+                        payload.session_id, e
+                    ),
+                }
+                // Keep it if a newer status replaced it while it was sent
+                let mut state = self.lock();
+                if state.pending.get(&payload.session_id) == Some(&payload) {
+                    state.pending.remove(&payload.session_id);
+                }
+            }
+
+            delay = if unreachable {
+                (delay * 2).min(MAX_RETRY_DELAY)
+            } else {
+                INITIAL_RETRY_DELAY
+            };
+            let mut state = self.lock();
+            if state.pending.is_empty() {
+                state.flushing = false;
+                return;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, OutboxState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Reports status to Maestro over HTTP, or over its Unix socket.
+pub struct StatusReporter {
     status_url: Option<String>,
+    connection: Option<Connection>,
     session_id: Option<u32>,
     instance_id: Option<String>,
+    outbox: Arc<Outbox>,
 }
 
 impl StatusReporter {
@@ -38,28 +417,58 @@ impl StatusReporter {
         status_url: Option<String>,
         session_id: Option<u32>,
         instance_id: Option<String>,
+        status_token: Option<String>,
     ) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            connection: status_url.as_deref().map(|url| Connection {
+                client: reqwest::Client::new(),
+                endpoint: Arc::new(RwLock::new(Endpoint::parse(url))),
+                instance_id: instance_id.clone(),
+                status_token,
+            }),
             status_url,
             session_id,
             instance_id,
+            outbox: Arc::default(),
         }
     }
 
     /// Report status to Maestro.
     ///
-    /// Returns Ok(()) if the status was successfully reported, or if
-    /// no status URL is configured (graceful degradation).
+    /// Returns `Delivery::Sent` if the status was successfully reported, or
+    /// if no status URL is configured (graceful degradation). If Maestro is
+    /// unreachable the status is queued and retried in the background.
     pub async fn report_status(
         &self,
         state: &str,
         message: &str,
         needs_input_prompt: Option<String>,
-    ) -> Result<(), StatusError> {
-        let status_url = match &self.status_url {
-            Some(url) => url,
-            None => return Ok(()), // Graceful degradation if not configured
+    ) -> Result<Delivery, StatusError> {
+        self.send_status(state, message, needs_input_prompt, false)
+            .await
+    }
+
+    /// Report that the agent asked the user `question` and waits for the
+    /// answer through `fetch_replies`, so Maestro offers an answer box.
+    pub async fn report_question(&self, question: &str) -> Result<Delivery, StatusError> {
+        self.send_status(
+            "needs_input",
+            "Waiting for your answer",
+            Some(question.to_string()),
+            true,
+        )
+        .await
+    }
+
+    async fn send_status(
+        &self,
+        state: &str,
+        message: &str,
+        needs_input_prompt: Option<String>,
+        awaiting_reply: bool,
+    ) -> Result<Delivery, StatusError> {
+        let (Some(status_url), Some(connection)) = (&self.status_url, &self.connection) else {
+            return Ok(Delivery::Sent); // Graceful degradation if not configured
         };
 
         let session_id = self.session_id.unwrap_or(0);
@@ -74,27 +483,488 @@ impl StatusReporter {
             state: state.to_string(),
             message: message.to_string(),
             needs_input_prompt,
+            awaiting_reply,
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
 
+        // An older status is still waiting; sending this one first would
+        // let the older one overwrite it later
+        if self.outbox.has_pending(session_id) {
+            self.queue(payload, connection);
+            self.outbox.wakeup.notify_one();
+            return Ok(Delivery::Queued);
+        }
+
         // Send HTTP POST to Maestro's status endpoint
         eprintln!(
             "[maestro-mcp-server] Sending status to {}: session_id={}, state={}, message={}",
             status_url, payload.session_id, payload.state, payload.message
         );
 
-        let response = self.client
-            .post(status_url)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
+        match connection.post_status(&payload).await {
+            Ok(()) => Ok(Delivery::Sent),
+            Err(e) if e.is_transient() => {
+                eprintln!(
+                    "[maestro-mcp-server] Maestro can't take the status yet, queueing it: {}",
+                    e
+                );
+                self.queue(payload, connection);
+                Ok(Delivery::Queued)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn queue(&self, payload: StatusPayload, connection: &Connection) {
+        if self.outbox.push(payload) {
+            tokio::spawn(self.outbox.clone().flush(connection.clone()));
+        }
+    }
+
+    /// Whether a status URL and session ID were configured, i.e. whether
+    /// there is a Maestro UI to talk to.
+    pub fn is_configured(&self) -> bool {
+        self.status_url.is_some() && self.session_id.is_some()
+    }
+
+    /// Path of one of this session's routes on the status server, e.g.
+    /// `session_path("replies")` -> `/sessions/3/replies`.
+    fn session_path(&self, route: &str) -> Result<String, StatusError> {
+        let (Some(_), Some(session_id)) = (&self.connection, self.session_id) else {
+            return Err(StatusError::NotConfigured);
+        };
+        Ok(format!("/sessions/{}/{}", session_id, route))
+    }
+
+    /// Send one request to the status server. A request getting through
+    /// means Maestro is reachable, so queued statuses are retried now.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<Reply, StatusError> {
+        let connection = self.connection.as_ref().ok_or(StatusError::NotConfigured)?;
+        let reply = connection.send(method, path, body, timeout).await?;
+        if self
+            .session_id
+            .is_some_and(|id| self.outbox.has_pending(id))
+        {
+            self.outbox.wakeup.notify_one();
+        }
+        Ok(reply)
+    }
+
+    /// Fetch and clear the user's queued replies.
+    ///
+    /// If none are queued, Maestro holds the request for up to `wait`
+    /// (it caps this at 60 seconds) and returns as soon as one arrives.
+    pub async fn fetch_replies(&self, wait: Duration) -> Result<Vec<PromptReply>, StatusError> {
+        self.poll("replies", wait).await
+    }
+
+    /// Fetch and clear messages other sessions sent us, waiting up to `wait`
+    /// like `fetch_replies`.
+    pub async fn fetch_messages(&self, wait: Duration) -> Result<Vec<AgentMessage>, StatusError> {
+        self.poll("messages", wait).await
+    }
+
+    /// Long-poll one of the session's queues.
+    async fn poll<T: DeserializeOwned>(
+        &self,
+        route: &str,
+        wait: Duration,
+    ) -> Result<Vec<T>, StatusError> {
+        let path = format!("{}?wait={}", self.session_path(route)?, wait.as_secs());
+        self.send(Method::GET, &path, None, wait + Duration::from_secs(5))
+            .await?
+            .json()
+    }
+
+    /// List the other sessions of this project.
+    pub async fn list_peers(&self) -> Result<Vec<PeerSession>, StatusError> {
+        let path = self.session_path("peers")?;
+        self.send(Method::GET, &path, None, Duration::from_secs(5))
+            .await?
+            .json()
+    }
+
+    /// Send a message to another session of this project. Returns the
+    /// message ID.
+    pub async fn send_message(&self, to_session: u32, text: &str) -> Result<u64, StatusError> {
+        let body = serde_json::json!({
+            "instance_id": self.instance_id.as_deref().unwrap_or("unknown"),
+            "to_session": to_session,
+            "text": text,
+        });
+        let path = self.session_path("messages")?;
+        let response: SendMessageResponse = self
+            .send(
+                Method::POST,
+                &path,
+                Some(serde_json::to_vec(&body)?),
+                Duration::from_secs(5),
+            )
+            .await?
+            .json()?;
+        Ok(response.message_id)
+    }
+
+    /// Report the agent's plan and position in it.
+    pub async fn report_progress(&self, progress: &ProgressUpdate) -> Result<(), StatusError> {
+        let body = serde_json::json!({
+            "instance_id": self.instance_id.as_deref().unwrap_or("unknown"),
+            "steps": progress.steps,
+            "current_step": progress.current_step,
+            "percent": progress.percent,
+            "artifacts": {
+                "files": progress.files,
+                "pr_url": progress.pr_url,
+            },
+        });
+        self.checked_request(Method::POST, "progress", Some(body))
             .await?;
+        Ok(())
+    }
 
-        eprintln!(
-            "[maestro-mcp-server] Status response: {}",
-            response.status()
+    /// List the tasks on the project's task board.
+    pub async fn list_tasks(&self) -> Result<serde_json::Value, StatusError> {
+        self.checked_request(Method::GET, "tasks", None).await
+    }
+
+    /// Add a task to the project's task board.
+    pub async fn create_task(&self, body: &str) -> Result<serde_json::Value, StatusError> {
+        let body = serde_json::json!({ "body": body });
+        self.checked_request(Method::POST, "tasks", Some(body))
+            .await
+    }
+
+    /// Claim `task_id`, or the oldest open task when `None`.
+    pub async fn claim_task(&self, task_id: Option<u64>) -> Result<serde_json::Value, StatusError> {
+        let body = serde_json::json!({ "task_id": task_id });
+        self.checked_request(Method::POST, "tasks/claim", Some(body))
+            .await
+    }
+
+    /// Mark a task we claimed as done (`success`) or failed.
+    pub async fn finish_task(
+        &self,
+        task_id: u64,
+        success: bool,
+        result: Option<&str>,
+    ) -> Result<serde_json::Value, StatusError> {
+        let action = if success { "complete" } else { "fail" };
+        let route = format!("tasks/{}/{}", task_id, action);
+        let body = serde_json::json!({ "result": result });
+        self.checked_request(Method::POST, &route, Some(body)).await
+    }
+
+    /// Send a request to one of this session's routes, turning refusals
+    /// into `Rejected` with Maestro's explanation.
+    async fn checked_request(
+        &self,
+        method: Method,
+        route: &str,
+        body: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, StatusError> {
+        let path = self.session_path(route)?;
+        let body = body.map(|b| serde_json::to_vec(&b)).transpose()?;
+        let reply = self
+            .send(method, &path, body, Duration::from_secs(5))
+            .await?;
+        if reply.status.is_client_error() {
+            let message = String::from_utf8_lossy(&reply.body).into_owned();
+            return Err(StatusError::Rejected(if message.is_empty() {
+                reply.status.to_string()
+            } else {
+                message
+            }));
+        }
+        reply.json()
+    }
+}
+
+/// Send one HTTP/1.1 request over Maestro's Unix domain socket.
+#[cfg(unix)]
+async fn unix_request(
+    socket: &std::path::Path,
+    method: Method,
+    path: &str,
+    token: Option<&str>,
+    body: Option<Vec<u8>>,
+) -> Result<Reply, StatusError> {
+    use http_body_util::{BodyExt, Full};
+    use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST};
+
+    let socket_error = |e: &dyn std::fmt::Display| StatusError::Socket(e.to_string());
+    let stream = tokio::net::UnixStream::connect(socket)
+        .await
+        .map_err(|e| socket_error(&e))?;
+    let (mut sender, connection) =
+        hyper::client::conn::http1::handshake(hyper_util::rt::TokioIo::new(stream))
+            .await
+            .map_err(|e| socket_error(&e))?;
+    tokio::spawn(async move {
+        let _ = connection.await;
+    });
+
+    let mut request = hyper::Request::builder()
+        .method(method)
+        .uri(path)
+        .header(HOST, "localhost");
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    if body.is_some() {
+        request = request.header(CONTENT_TYPE, "application/json");
+    }
+    let request = request
+        .body(Full::new(hyper::body::Bytes::from(
+            body.unwrap_or_default(),
+        )))
+        .map_err(|e| socket_error(&e))?;
+
+    let response = sender
+        .send_request(request)
+        .await
+        .map_err(|e| socket_error(&e))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| socket_error(&e))?
+        .to_bytes()
+        .to_vec();
+    Ok(Reply { status, body })
+}
+
+#[cfg(not(unix))]
+async fn unix_request(
+    _socket: &std::path::Path,
+    _method: Method,
+    _path: &str,
+    _token: Option<&str>,
+    _body: Option<Vec<u8>>,
+) -> Result<Reply, StatusError> {
+    Err(StatusError::Socket(
+        "Unix domain sockets are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_parse() {
+        assert_eq!(
+            Endpoint::parse("http://127.0.0.1:9900/status"),
+            Endpoint::Http("http://127.0.0.1:9900".to_string())
+        );
+        assert_eq!(
+            Endpoint::parse("unix:/tmp/maestro.sock"),
+            Endpoint::Unix("/tmp/maestro.sock".into())
         );
+        assert_eq!(
+            Endpoint::parse("unix:///tmp/maestro.sock"),
+            Endpoint::Unix("/tmp/maestro.sock".into())
+        );
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_statuses_queue_while_maestro_is_down() {
+        use axum::routing::post;
+
+        // Take a free port, then leave it closed so requests are refused
+        let port = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let reporter = StatusReporter::new(
+            Some(format!("http://127.0.0.1:{}/status", port)),
+            Some(7),
+            Some("instance".to_string()),
+            None,
+        );
+        assert_eq!(
+            reporter
+                .report_status("working", "Busy", None)
+                .await
+                .unwrap(),
+            Delivery::Queued
+        );
+        assert_eq!(
+            reporter
+                .report_status("finished", "Finished", None)
+                .await
+                .unwrap(),
+            Delivery::Queued
+        );
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        let app = axum::Router::new().route(
+            "/status",
+            post(
+                move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push(body["state"].as_str().unwrap().to_string());
+                    StatusCode::OK
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+            .await
+            .unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while reporter.outbox.has_pending(7) {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "queue was not flushed"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        // Only the latest status of the session is delivered
+        assert_eq!(*received.lock().unwrap(), vec!["finished".to_string()]);
+
+        assert_eq!(
+            reporter.report_status("idle", "Ready", None).await.unwrap(),
+            Delivery::Sent
+        );
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_statuses_follow_maestro_to_another_port() {
+        use axum::routing::{get, post};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let closed = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let mut listener = None;
+        for port in STATUS_PORTS {
+            if let Ok(l) = tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+                listener = Some(l);
+                break;
+            }
+        }
+        let listener = listener.expect("no free port in the status range");
+        let moved_to = listener.local_addr().unwrap().port();
+
+        // The session is only registered from the second report on
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let app = axum::Router::new()
+            .route(
+                "/instance",
+                get(|| async { axum::Json(serde_json::json!({ "instance_id": "instance" })) }),
+            )
+            .route(
+                "/status",
+                post(move || async move {
+                    if counted.fetch_add(1, Ordering::SeqCst) == 0 {
+                        StatusCode::CONFLICT
+                    } else {
+                        StatusCode::OK
+                    }
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let reporter = StatusReporter::new(
+            Some(format!("http://127.0.0.1:{}/status", closed)),
+            Some(3),
+            Some("instance".to_string()),
+            None,
+        );
+        assert_eq!(
+            reporter
+                .report_status("working", "Busy", None)
+                .await
+                .unwrap(),
+            Delivery::Queued
+        );
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while reporter.outbox.has_pending(3) {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "queue was not flushed"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *reporter.connection.as_ref().unwrap().endpoint(),
+            Endpoint::Http(format!("http://127.0.0.1:{}", moved_to))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket_requests() {
+        use axum::http::HeaderMap;
+        use axum::routing::{get, post};
+
+        let dir = std::env::temp_dir().join(format!("maestro-reporter-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("status.sock");
+        let _ = std::fs::remove_file(&socket);
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let app = axum::Router::new()
+            .route(
+                "/status",
+                post(|headers: HeaderMap, body: String| async move {
+                    let authorized = headers
+                        .get("authorization")
+                        .is_some_and(|v| v == "Bearer token");
+                    if authorized && body.contains("\"state\":\"working\"") {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::UNAUTHORIZED
+                    }
+                }),
+            )
+            .route(
+                "/sessions/4/peers",
+                get(|| async { axum::Json(serde_json::json!([{ "session_id": 5 }])) }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let reporter = StatusReporter::new(
+            Some(format!("unix:{}", socket.display())),
+            Some(4),
+            Some("instance".to_string()),
+            Some("token".to_string()),
+        );
+        reporter
+            .report_status("working", "Busy", None)
+            .await
+            .unwrap();
+        let peers = reporter.list_peers().await.unwrap();
+        assert_eq!(peers[0].session_id, 5);
+
+        let unauthorized = StatusReporter::new(
+            Some(format!("unix:{}", socket.display())),
+            Some(4),
+            None,
+            None,
+        );
+        // Refusals are not retried
+        assert!(matches!(
+            unauthorized.report_status("working", "Busy", None).await,
+            Err(StatusError::Status(401))
+        ));
+        assert!(!unauthorized.outbox.has_pending(4));
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -1,13 +1,17 @@
 //! Tauri build script.
 //!
 //! This script copies the maestro-mcp-server binary to the target directory
-//! so it can be found by the Tauri application during development.
+//! so it can be found by the Tauri application during development, and
+//! stages the maestro-daemon binary where the bundler's `externalBin` expects it.
 
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
 fn main() {
+    // Must exist before tauri_build validates `bundle.externalBin`
+    stage_daemon_binary();
+
     // Standard Tauri build
     tauri_build::build();
 
@@ -15,6 +19,100 @@ fn main() {
     copy_mcp_server_binary();
 }
 
+/// Copies the maestro-daemon binary to `binaries/maestro-daemon-{target}`, the
+/// name `bundle.externalBin` resolves to. The bundler installs it next to the
+/// app executable, where `DaemonClient` starts it from.
+///
+/// The daemon is looked up in this build's output directory, which honors
+/// `CARGO_TARGET_DIR` and `--target <triple>`. It is a bin target of this
+/// crate, so a clean build has nothing to copy yet; an empty placeholder
+/// keeps `tauri_build` happy until then and a warning says so. Release builds
+/// refuse to go on with only a placeholder, since it would be bundled as the
+/// daemon. `npm run build:daemon` (part of `beforeBuildCommand`) builds the
+/// real one first; it sets `PLACEHOLDER_ENV`, being the build that produces it.
+fn stage_daemon_binary() {
+    const PLACEHOLDER_ENV: &str = "MAESTRO_DAEMON_PLACEHOLDER";
+    println!("cargo:rerun-if-env-changed={PLACEHOLDER_ENV}");
+    println!("cargo:rerun-if-env-changed=CARGO_TARGET_DIR");
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let profile = env::var("PROFILE").unwrap_or_else(|_| "debug".to_string());
+    let target = env::var("TARGET").unwrap_or_default();
+    let exe_suffix = if target.contains("windows") { ".exe" } else { "" };
+    let binary_name = format!("maestro-daemon{exe_suffix}");
+
+    let staging_dir = manifest_dir.join("binaries");
+    let dest = staging_dir.join(format!("maestro-daemon-{target}{exe_suffix}"));
+    let Some(project_root) = manifest_dir.parent() else {
+        return;
+    };
+
+    // OUT_DIR is {target_dir}[/{triple}]/{profile}/build/{crate}-{hash}/out,
+    // so its fourth ancestor is where this build puts its binaries
+    let target_dir = match env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => project_root.join(dir),
+        None => project_root.join("target"),
+    };
+    let out_dir = env::var_os("OUT_DIR").map(PathBuf::from);
+    let candidates = [
+        out_dir.as_deref().and_then(|d| d.ancestors().nth(3)).map(|d| d.join(&binary_name)),
+        Some(target_dir.join(&target).join(&profile).join(&binary_name)),
+        Some(target_dir.join(&profile).join(&binary_name)),
+    ];
+    let candidates: Vec<PathBuf> = candidates.into_iter().flatten().collect();
+    for candidate in &candidates {
+        println!("cargo:rerun-if-changed={}", candidate.display());
+    }
+    let source = candidates.into_iter().find(|path| path.is_file());
+
+    if let Err(e) = fs::create_dir_all(&staging_dir) {
+        println!("cargo:warning=Failed to create {:?}: {}", staging_dir, e);
+        return;
+    }
+
+    let Some(source) = source else {
+        if !dest.exists() {
+            if let Err(e) = fs::write(&dest, b"") {
+                println!("cargo:warning=Failed to stage placeholder {:?}: {}", dest, e);
+            }
+        }
+        let is_placeholder = fs::metadata(&dest).map_or(true, |m| m.len() == 0);
+        if !is_placeholder {
+            return;
+        }
+        if profile == "release" && env::var_os(PLACEHOLDER_ENV).is_none() {
+            panic!(
+                "maestro-daemon was not found in {}; only an empty placeholder is staged at \
+                 {:?} and would be bundled. Run `npm run build:daemon` first.",
+                target_dir.display(),
+                dest
+            );
+        }
+        println!(
+            "cargo:warning=maestro-daemon not built yet; staged an empty placeholder at {:?}",
+            dest
+        );
+        return;
+    };
+
+    // A placeholder is always replaced, whatever its timestamp
+    let should_copy = match (fs::metadata(&source), fs::metadata(&dest)) {
+        (Ok(s), Ok(d)) if d.len() > 0 => match (s.modified(), d.modified()) {
+            (Ok(s), Ok(d)) => s > d,
+            _ => true,
+        },
+        _ => true,
+    };
+    if should_copy {
+        if let Err(e) = fs::copy(&source, &dest) {
+            println!(
+                "cargo:warning=Failed to stage maestro-daemon from {:?} to {:?}: {}",
+                source, dest, e
+            );
+        }
+    }
+}
+
 /// Copies the maestro-mcp-server binary from its build location to the Tauri target directory.
 /// This ensures the binary can be found at runtime during development.
 fn copy_mcp_server_binary() {
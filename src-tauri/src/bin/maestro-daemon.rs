@@ -0,0 +1,6 @@
+//! Headless session daemon. Started on demand by the app; see `daemon` in
+//! the library for details.
+
+fn main() {
+    maestro_lib::run_daemon()
+}
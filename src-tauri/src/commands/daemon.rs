@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use tauri::State;
+
+use crate::core::scrollback::ScrollbackChunk;
+use crate::daemon::protocol::{DaemonInfo, DaemonSessionInfo};
+use crate::daemon::{DaemonClient, DaemonError};
+
+/// Connects to the session daemon, starting it if it is not running, and
+/// returns its PID and session count.
+#[tauri::command]
+pub async fn daemon_status(client: State<'_, DaemonClient>) -> Result<DaemonInfo, DaemonError> {
+    client.ping().await
+}
+
+/// Lists sessions owned by the daemon, including ones started by a previous
+/// app run.
+#[tauri::command]
+pub async fn daemon_list_sessions(
+    client: State<'_, DaemonClient>,
+) -> Result<Vec<DaemonSessionInfo>, DaemonError> {
+    client.list().await
+}
+
+/// Spawns a login shell in the daemon and returns its daemon session ID.
+///
+/// Unlike `spawn_shell`, this never falls back to an in-process session.
+/// Output is not delivered until the frontend calls `daemon_attach_session`,
+/// and arrives as `pty-output-{id}` events.
+#[tauri::command]
+pub async fn daemon_spawn_shell(
+    client: State<'_, DaemonClient>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<u32, DaemonError> {
+    let cwd = match cwd {
+        Some(dir) => {
+            let canonical = std::path::Path::new(&dir)
+                .canonicalize()
+                .map_err(|e| DaemonError::InvalidRequest(format!("Invalid cwd '{dir}': {e}")))?;
+            if !canonical.is_dir() {
+                return Err(DaemonError::InvalidRequest(format!(
+                    "cwd '{dir}' is not a directory"
+                )));
+            }
+            Some(canonical.to_string_lossy().into_owned())
+        }
+        None => None,
+    };
+    client.spawn(cwd, env).await
+}
+
+/// Attaches to a daemon session.
+///
+/// Returns scrollback since `since_offset` (default 0). Live output follows
+/// as `pty-output-{id}` events starting exactly at the chunk's
+/// `end_offset`, so nothing is duplicated or lost between the two. The exit
+/// status arrives as `pty-exit-{id}`.
+#[tauri::command]
+pub async fn daemon_attach_session(
+    client: State<'_, DaemonClient>,
+    session_id: u32,
+    since_offset: Option<u64>,
+) -> Result<ScrollbackChunk, DaemonError> {
+    client.attach(session_id, since_offset.unwrap_or(0)).await
+}
+
+/// Stops forwarding a daemon session's output. The session keeps running.
+#[tauri::command]
+pub async fn daemon_detach_session(
+    client: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<(), DaemonError> {
+    client.detach(session_id).await
+}
+
+/// Sends raw text to a daemon session's stdin.
+#[tauri::command]
+pub async fn daemon_write_stdin(
+    client: State<'_, DaemonClient>,
+    session_id: u32,
+    data: String,
+) -> Result<(), DaemonError> {
+    client.write(session_id, data).await
+}
+
+/// Resizes a daemon session's PTY. Applies the same bounds as `resize_pty`.
+#[tauri::command]
+pub async fn daemon_resize_pty(
+    client: State<'_, DaemonClient>,
+    session_id: u32,
+    rows: u16,
+    cols: u16,
+) -> Result<(), DaemonError> {
+    if rows == 0 || cols == 0 || rows > 500 || cols > 500 {
+        return Err(DaemonError::InvalidRequest(
+            "Invalid dimensions".to_string(),
+        ));
+    }
+    client.resize(session_id, rows, cols).await
+}
+
+/// Terminates a daemon session (SIGTERM, then SIGKILL after 3s).
+#[tauri::command]
+pub async fn daemon_kill_session(
+    client: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<(), DaemonError> {
+    client.kill(session_id).await
+}
+
+/// Kills every daemon session and stops the daemon.
+#[tauri::command]
+pub async fn daemon_shutdown(client: State<'_, DaemonClient>) -> Result<(), DaemonError> {
+    client.shutdown().await
+}
@@ -1,6 +1,9 @@
 use std::path::PathBuf;
 
-use crate::git::{BranchInfo, CommitInfo, FileChange, Git, GitError, GitUserConfig, RemoteInfo, WorktreeInfo};
+use crate::git::{
+    BranchInfo, CommitInfo, DiffHunk, FileChange, FileDiff, Git, GitError, GitUserConfig,
+    MergeCheck, RemoteInfo, StatusEntry, WorktreeInfo,
+};
 
 /// Returns `Err(GitError::NotARepo)` if the given path string is empty.
 fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
@@ -228,3 +231,107 @@ pub async fn git_set_default_branch(
     let git = Git::new(&repo_path);
     git.set_default_branch(&branch, global).await
 }
+
+/// Exposes `Git::merge_check` to the frontend.
+/// Dry-runs merging `source_branch` into `target_branch` and lists the
+/// conflicting files, without touching any worktree.
+#[tauri::command]
+pub async fn git_merge_check(
+    repo_path: String,
+    source_branch: String,
+    target_branch: String,
+) -> Result<MergeCheck, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.merge_check(&source_branch, &target_branch).await
+}
+
+/// Exposes `Git::diff_worktree` to the frontend.
+/// Returns per-file hunks for the worktree's uncommitted changes, or for
+/// everything its branch has committed since forking from `against`.
+#[tauri::command]
+pub async fn git_diff_worktree(
+    repo_path: String,
+    against: Option<String>,
+) -> Result<Vec<FileDiff>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.diff_worktree(against.as_deref()).await
+}
+
+/// Exposes `Git::diff_unstaged` to the frontend.
+/// Returns per-file hunks for changes not yet staged, including untracked
+/// files; these are the hunks `git_stage_hunk` accepts.
+#[tauri::command]
+pub async fn git_diff_unstaged(repo_path: String) -> Result<Vec<FileDiff>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.diff_unstaged().await
+}
+
+/// Exposes `Git::status_porcelain_v2` to the frontend.
+/// Returns changed, untracked and conflicted files with their staged and
+/// unstaged state.
+#[tauri::command]
+pub async fn git_status(repo_path: String) -> Result<Vec<StatusEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.status_porcelain_v2().await
+}
+
+/// Exposes `Git::stage_paths` to the frontend.
+/// Stages the given files, including deletions.
+#[tauri::command]
+pub async fn git_stage_paths(repo_path: String, paths: Vec<String>) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    git.stage_paths(&paths).await
+}
+
+/// Exposes `Git::unstage_paths` to the frontend.
+/// Unstages the given files without touching the worktree.
+#[tauri::command]
+pub async fn git_unstage_paths(repo_path: String, paths: Vec<String>) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    git.unstage_paths(&paths).await
+}
+
+/// Exposes `Git::stage_hunk` to the frontend.
+/// Stages one hunk from `git_diff_unstaged` output.
+#[tauri::command]
+pub async fn git_stage_hunk(
+    repo_path: String,
+    path: String,
+    hunk: DiffHunk,
+) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stage_hunk(&path, &hunk).await
+}
+
+/// Exposes `Git::discard_paths` to the frontend.
+/// Reverts unstaged changes to tracked files and deletes untracked ones.
+#[tauri::command]
+pub async fn git_discard_paths(repo_path: String, paths: Vec<String>) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    git.discard_paths(&paths).await
+}
+
+/// Exposes `Git::commit` to the frontend.
+/// Commits the staged changes and returns the new commit hash.
+#[tauri::command]
+pub async fn git_commit(
+    repo_path: String,
+    message: String,
+    amend: bool,
+    signoff: bool,
+) -> Result<String, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.commit(&message, amend, signoff).await
+}
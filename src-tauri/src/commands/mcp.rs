@@ -6,12 +6,14 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tauri_plugin_store::StoreExt;
 
 use crate::core::mcp_config_writer;
+use crate::core::mcp_http_server::McpHttpServer;
 use crate::core::mcp_manager::{McpManager, McpServerConfig};
-use crate::core::status_server::StatusServer;
+use crate::core::status_history::{SessionActivity, SessionHistory};
+use crate::core::status_server::{SessionProgress, StatusServer, StatusServerError};
 
 /// Store filename for custom MCP servers (global, user-level).
 const CUSTOM_MCP_SERVERS_STORE: &str = "mcp-custom-servers.json";
@@ -43,7 +45,8 @@ pub struct McpCustomServer {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatusServerInfo {
-    pub port: u16,
+    /// `None` when the server listens on a Unix domain socket.
+    pub port: Option<u16>,
     pub status_url: String,
     pub instance_id: String,
 }
@@ -174,7 +177,15 @@ pub async fn load_project_mcp_defaults(
         .to_string_lossy()
         .into_owned();
 
-    let store_name = format!("maestro-{}.json", hash_project_path(&canonical));
+    project_mcp_defaults(&app, &canonical)
+}
+
+/// Reads the default enabled MCP servers saved for a canonical project path.
+pub(crate) fn project_mcp_defaults(
+    app: &AppHandle,
+    canonical: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let store_name = format!("maestro-{}.json", hash_project_path(canonical));
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     let result = store
@@ -231,23 +242,97 @@ pub async fn remove_mcp_project(project_path: String) -> Result<(), String> {
 /// the status server so it stops accepting updates for this session.
 #[tauri::command]
 pub async fn remove_session_status(
-    status_server: State<'_, Arc<StatusServer>>,
+    app: AppHandle,
     _project_path: String,
     session_id: u32,
 ) -> Result<(), String> {
-    status_server.unregister_session(session_id).await;
-    log::debug!("Unregistered session {} from status server", session_id);
+    // Nothing to unregister when the status server failed to start
+    if let Some(status_server) = app.try_state::<Arc<StatusServer>>() {
+        status_server.unregister_session(session_id).await;
+        log::debug!("Unregistered session {} from status server", session_id);
+    }
     Ok(())
 }
 
+/// Returns the running status server, or the reason it is unavailable:
+/// the startup error when it could not bind its address.
+fn status_server(app: &AppHandle) -> Result<State<'_, Arc<StatusServer>>, String> {
+    app.try_state::<Arc<StatusServer>>().ok_or_else(|| {
+        app.try_state::<StatusServerError>()
+            .map(|e| format!("Status server unavailable: {}", *e))
+            .unwrap_or_else(|| "Status server unavailable: it is not running".to_string())
+    })
+}
+
+/// Answers a session's `needs_input` prompt from the UI.
+///
+/// The answer is queued on the status server until the session's MCP server
+/// reads it from `GET /sessions/{session_id}/replies`. A `maestro_ask_user`
+/// call long-polling that endpoint returns it to the agent right away.
+#[tauri::command]
+pub async fn answer_session_prompt(
+    app: AppHandle,
+    session_id: u32,
+    answer: String,
+) -> Result<(), String> {
+    let status_server = status_server(&app)?;
+    if status_server.push_reply(session_id, answer).await {
+        Ok(())
+    } else {
+        Err(format!("Session {} is not registered with the status server", session_id))
+    }
+}
+
+/// Returns the plan and position a session last reported with
+/// `maestro_progress`, or `None` if it hasn't reported any.
+///
+/// Later reports arrive as `session-progress-changed` events.
+#[tauri::command]
+pub async fn get_session_progress(
+    app: AppHandle,
+    session_id: u32,
+) -> Result<Option<SessionProgress>, String> {
+    Ok(status_server(&app)?.progress(session_id).await)
+}
+
+/// Returns every status a session moved to, whether reported through its
+/// MCP server, inferred from its output or set when it exited, or `None` if
+/// it has no status yet.
+#[tauri::command]
+pub async fn get_session_status_history(
+    app: AppHandle,
+    session_id: u32,
+) -> Result<Option<SessionHistory>, String> {
+    Ok(status_server(&app)?.status_history(session_id).await)
+}
+
+/// Summarizes what each of a project's sessions did since `since`
+/// (milliseconds since the Unix epoch; all recorded history when absent),
+/// including how long each spent in every status.
+#[tauri::command]
+pub async fn get_project_activity(
+    app: AppHandle,
+    project_path: String,
+    since: Option<u64>,
+) -> Result<Vec<SessionActivity>, String> {
+    let status_server = status_server(&app)?;
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+    Ok(status_server
+        .project_activity(&canonical, since.unwrap_or(0))
+        .await)
+}
+
 /// Gets the status server info (URL, port, instance ID).
 ///
 /// This is needed by the frontend when writing MCP configs so the
-/// MCP server knows where to POST status updates.
+/// MCP server knows where to POST status updates. Fails with the startup
+/// error when the server could not bind its address.
 #[tauri::command]
-pub async fn get_status_server_info(
-    status_server: State<'_, Arc<StatusServer>>,
-) -> Result<StatusServerInfo, String> {
+pub async fn get_status_server_info(app: AppHandle) -> Result<StatusServerInfo, String> {
+    let status_server = status_server(&app)?;
     let registered = status_server.registered_sessions().await;
     log::info!(
         "get_status_server_info: instance_id={}, registered_sessions={:?}",
@@ -277,7 +362,6 @@ pub async fn get_status_server_info(
 pub async fn write_session_mcp_config(
     app: AppHandle,
     mcp_state: State<'_, McpManager>,
-    status_server: State<'_, Arc<StatusServer>>,
     working_dir: String,
     session_id: u32,
     project_path: String,
@@ -288,24 +372,55 @@ pub async fn write_session_mcp_config(
         .to_string_lossy()
         .into_owned();
 
-    // Register this session with the status server
-    status_server
-        .register_session(session_id, &canonical)
+    write_session_mcp_config_inner(
+        &app,
+        &mcp_state,
+        &working_dir,
+        session_id,
+        &canonical,
+        &enabled_server_names,
+    )
+    .await
+}
+
+/// Registers a session with the status server and writes its `.mcp.json`.
+///
+/// Shared by `write_session_mcp_config` and `restore_sessions`, so restored
+/// sessions get the same status reporting as freshly launched ones.
+/// `canonical` must already be canonicalized.
+pub(crate) async fn write_session_mcp_config_inner(
+    app: &AppHandle,
+    mcp_state: &McpManager,
+    working_dir: &str,
+    session_id: u32,
+    canonical: &str,
+    enabled_server_names: &[String],
+) -> Result<(), String> {
+    let status_server = app
+        .try_state::<Arc<StatusServer>>()
+        .ok_or("Status server is not running")?;
+
+    // Register this session with the status server. The token it hands
+    // back is what the session's MCP server authenticates with.
+    let status_token = status_server
+        .register_session(session_id, canonical)
         .await;
 
     // Get the status URL and instance ID from the status server
     let status_url = status_server.status_url();
     let instance_id = status_server.instance_id();
+    let http_server = app.try_state::<Arc<McpHttpServer>>();
+    let maestro_http_url = http_server.as_ref().map(|server| server.url());
 
     // Get full server configs for enabled discovered servers
-    let all_discovered = mcp_state.get_project_servers(&canonical);
+    let all_discovered = mcp_state.get_project_servers(canonical);
     let enabled_discovered: Vec<_> = all_discovered
         .into_iter()
         .filter(|s| enabled_server_names.contains(&s.name))
         .collect();
 
     // Get enabled custom servers
-    let custom_servers = get_custom_mcp_servers_internal(&app)?;
+    let custom_servers = get_custom_mcp_servers_internal(app)?;
     let enabled_custom: Vec<_> = custom_servers
         .into_iter()
         .filter(|s| s.is_enabled)
@@ -321,10 +436,12 @@ pub async fn write_session_mcp_config(
     );
 
     mcp_config_writer::write_session_mcp_config(
-        Path::new(&working_dir),
+        Path::new(working_dir),
         session_id,
         &status_url,
         instance_id,
+        &status_token,
+        maestro_http_url,
         &enabled_discovered,
         &enabled_custom,
     )
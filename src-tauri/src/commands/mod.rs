@@ -1,9 +1,21 @@
 pub mod claudemd;
+pub mod daemon;
 pub mod fonts;
 pub mod git;
 pub mod marketplace;
 pub mod mcp;
+pub mod notifications;
 pub mod plugin;
+pub mod recording;
 pub mod session;
+pub mod task_board;
 pub mod terminal;
+pub mod webhooks;
 pub mod worktree;
+
+/// Canonical form of a project path, which keys its per-project store.
+pub(crate) fn canonical_project(project_path: &str) -> Result<String, String> {
+    std::fs::canonicalize(project_path)
+        .map(|p| p.to_string_lossy().into_owned())
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))
+}
@@ -0,0 +1,42 @@
+use tauri::{AppHandle, State};
+
+use super::canonical_project;
+use crate::core::notifications::{self, NotificationRules, Notifier};
+
+/// Returns when a project's sessions show desktop notifications. Projects
+/// that were never configured get the defaults.
+#[tauri::command]
+pub async fn get_notification_rules(
+    app: AppHandle,
+    project_path: String,
+) -> Result<NotificationRules, String> {
+    let canonical = canonical_project(&project_path)?;
+    Ok(notifications::load_rules(&app, &canonical))
+}
+
+/// Replaces a project's notification rules.
+#[tauri::command]
+pub async fn set_notification_rules(
+    app: AppHandle,
+    project_path: String,
+    rules: NotificationRules,
+) -> Result<(), String> {
+    if let Some(quiet) = &rules.quiet_hours {
+        if quiet.start_minute >= 24 * 60 || quiet.end_minute >= 24 * 60 {
+            return Err("Quiet hours must be minutes within a day (0-1439)".to_string());
+        }
+    }
+    let canonical = canonical_project(&project_path)?;
+    notifications::save_rules(&app, &canonical, &rules)
+}
+
+/// Tells the notifier which session's pane has keyboard focus (`None` when
+/// none does), so it doesn't notify about a session the user is looking at.
+#[tauri::command]
+pub async fn set_focused_session(
+    notifier: State<'_, Notifier>,
+    session_id: Option<u32>,
+) -> Result<(), String> {
+    notifier.set_focused_session(session_id);
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::core::recording::{self, Recording, RecordingInfo, ReplayManager, RECORDINGS_DIR};
+use crate::core::{ProcessManager, PtyError};
+use crate::daemon::DaemonClient;
+
+/// Directory recordings are written to: `<app data dir>/recordings`.
+pub(crate) fn recordings_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECORDINGS_DIR))
+        .map_err(|e| format!("No app data dir: {e}"))
+}
+
+/// Starts recording a session to an asciicast v2 file. Returns the new
+/// recording's ID.
+#[tauri::command]
+pub async fn start_session_recording(
+    app: AppHandle,
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<String, PtyError> {
+    let dir = recordings_dir(&app).map_err(PtyError::recording_failed)?;
+    let path = start_recording_inner(&state, &daemon, session_id, &dir).await?;
+    Ok(recording_id(&path))
+}
+
+/// Starts recording a session wherever it is hosted: in the session daemon
+/// when it is available, otherwise in the in-process `ProcessManager`.
+pub(crate) async fn start_recording_inner(
+    pm: &ProcessManager,
+    daemon: &DaemonClient,
+    session_id: u32,
+    dir: &Path,
+) -> Result<PathBuf, PtyError> {
+    if daemon.enabled().await {
+        return daemon
+            .start_recording(session_id, dir)
+            .await
+            .map_err(|e| PtyError::recording_failed(e.to_string()));
+    }
+    pm.start_recording(session_id, dir)
+}
+
+/// Stops recording a session. Returns the finished recording's ID, or
+/// `None` if the session was not being recorded.
+#[tauri::command]
+pub async fn stop_session_recording(
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<Option<String>, PtyError> {
+    let path = if daemon.enabled().await {
+        daemon
+            .stop_recording(session_id)
+            .await
+            .map_err(|e| PtyError::recording_failed(e.to_string()))?
+    } else {
+        state.stop_recording(session_id)?
+    };
+    Ok(path.as_deref().map(recording_id))
+}
+
+/// Returns the ID of the recording a session is currently writing, if any.
+#[tauri::command]
+pub async fn get_session_recording(
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<Option<String>, String> {
+    let path = if daemon.enabled().await {
+        daemon
+            .recording_path(session_id)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        state.recording_path(session_id)
+    };
+    Ok(path.as_deref().map(recording_id))
+}
+
+fn recording_id(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Lists all recordings, newest first.
+#[tauri::command]
+pub async fn list_recordings(app: AppHandle) -> Result<Vec<RecordingInfo>, String> {
+    let dir = recordings_dir(&app)?;
+    recording::list_recordings(&dir).map_err(|e| e.to_string())
+}
+
+/// Returns a recording with all of its events.
+#[tauri::command]
+pub async fn get_recording(app: AppHandle, recording_id: String) -> Result<Recording, String> {
+    let dir = recordings_dir(&app)?;
+    recording::read_recording(&dir, &recording_id).map_err(|e| e.to_string())
+}
+
+/// Replays a recording at `speed` times real time (default 1.0, clamped to
+/// 0.1–100) into a read-only pane. Events arrive as
+/// `recording-replay-{pane_id}` with a `type` of `output`, `resize` or
+/// `finished`; the frontend picks `pane_id` so it can listen before the
+/// first event. Returns a replay ID for `set_replay_speed`/`stop_replay`.
+#[tauri::command]
+pub async fn replay_recording(
+    app: AppHandle,
+    replays: State<'_, ReplayManager>,
+    recording_id: String,
+    pane_id: String,
+    speed: Option<f64>,
+) -> Result<u32, String> {
+    if pane_id.is_empty()
+        || !pane_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid pane ID '{pane_id}'"));
+    }
+    let dir = recordings_dir(&app)?;
+    let recording = recording::read_recording(&dir, &recording_id).map_err(|e| e.to_string())?;
+
+    let event_name = format!("recording-replay-{pane_id}");
+    Ok(
+        replays.start(recording.events, speed.unwrap_or(1.0), move |event| {
+            let _ = app.emit(&event_name, event);
+        }),
+    )
+}
+
+/// Changes the speed of a running replay. Returns `false` if it already
+/// finished.
+#[tauri::command]
+pub async fn set_replay_speed(
+    replays: State<'_, ReplayManager>,
+    replay_id: u32,
+    speed: f64,
+) -> Result<bool, String> {
+    Ok(replays.set_speed(replay_id, speed))
+}
+
+/// Stops a running replay. Returns `false` if it already finished.
+#[tauri::command]
+pub async fn stop_replay(
+    replays: State<'_, ReplayManager>,
+    replay_id: u32,
+) -> Result<bool, String> {
+    Ok(replays.stop(replay_id))
+}
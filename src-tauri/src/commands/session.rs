@@ -1,14 +1,22 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
-use tauri::State;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_store::StoreExt;
 
+use crate::commands::mcp;
+use crate::commands::terminal::{kill_session_inner, spawn_shell_inner};
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::McpManager;
 use crate::core::plugin_manager::PluginManager;
 use crate::core::process_manager::ProcessManager;
 use crate::core::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus};
+use crate::core::status_detector::{self, StatusInference, StatusRule, StatusRuleConfig};
 use crate::core::status_server::StatusServer;
+use crate::core::task_board::TaskBoards;
+use crate::daemon::DaemonClient;
 
 /// Exposes `SessionManager::all_sessions` to the frontend.
 /// Returns a snapshot of all active sessions in arbitrary order.
@@ -63,14 +71,22 @@ pub async fn assign_session_branch(
         .ok_or_else(|| format!("Session {} not found", session_id))
 }
 
-/// Exposes `SessionManager::remove_session` to the frontend.
+/// Exposes `SessionManager::remove_session` to the frontend, reopening the
+/// tasks the session still held.
 /// Returns the removed session config, or `None` if it was not found.
 #[tauri::command]
 pub async fn remove_session(
+    app_handle: AppHandle,
     state: State<'_, SessionManager>,
     session_id: u32,
 ) -> Result<Option<SessionConfig>, String> {
-    Ok(state.remove_session(session_id))
+    let removed = state.remove_session(session_id);
+    if let (Some(session), Some(boards)) = (&removed, app_handle.try_state::<TaskBoards>()) {
+        boards
+            .release_session(&app_handle, &session.project_path, session_id)
+            .await;
+    }
+    Ok(removed)
 }
 
 /// Gets all sessions for a specific project.
@@ -91,10 +107,11 @@ pub async fn get_sessions_for_project(
 /// Also kills the associated PTY sessions and cleans up MCP/plugin state.
 #[tauri::command]
 pub async fn remove_sessions_for_project(
+    app_handle: AppHandle,
     state: State<'_, SessionManager>,
     process_manager: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     mcp_manager: State<'_, McpManager>,
-    status_server: State<'_, Arc<StatusServer>>,
     plugin_manager: State<'_, PluginManager>,
     project_path: String,
 ) -> Result<Vec<SessionConfig>, String> {
@@ -104,6 +121,8 @@ pub async fn remove_sessions_for_project(
         .into_owned();
 
     let removed = state.remove_sessions_for_project(&canonical);
+    let status_server = app_handle.try_state::<Arc<StatusServer>>();
+    let task_boards = app_handle.try_state::<TaskBoards>();
 
     // Clean up MCP, plugin, and PTY state for each removed session
     for session in &removed {
@@ -111,8 +130,17 @@ pub async fn remove_sessions_for_project(
         mcp_manager.remove_session(&canonical, session.id);
         plugin_manager.remove_session(&canonical, session.id);
 
+        // Reopen any tasks the session still held
+        if let Some(boards) = &task_boards {
+            boards
+                .release_session(&app_handle, &canonical, session.id)
+                .await;
+        }
+
         // Unregister session from status server
-        status_server.unregister_session(session.id).await;
+        if let Some(status_server) = &status_server {
+            status_server.unregister_session(session.id).await;
+        }
 
         // Clean up .mcp.json entry (use worktree_path if set, otherwise project_path)
         let working_dir = session
@@ -130,7 +158,7 @@ pub async fn remove_sessions_for_project(
         }
 
         // Fire-and-forget kill -- log errors but don't fail the removal
-        if let Err(e) = process_manager.kill_session(session.id).await {
+        if let Err(e) = kill_session_inner(&process_manager, &daemon, session.id).await {
             log::warn!("Failed to kill PTY for session {}: {}", session.id, e);
         }
     }
@@ -143,3 +171,231 @@ pub async fn remove_sessions_for_project(
 
     Ok(removed)
 }
+
+/// A session re-created by `restore_sessions`.
+///
+/// `previous_id` is the ID the session had before the restart; `session`
+/// carries the new PTY-backed ID so the frontend can remap its pane state.
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoredSession {
+    pub previous_id: u32,
+    pub session: SessionConfig,
+}
+
+/// Returns the sessions saved by the previous run for a project.
+///
+/// Returns an empty list if no journal is attached or nothing was saved.
+#[tauri::command]
+pub async fn get_restorable_sessions(
+    state: State<'_, SessionManager>,
+    project_path: String,
+) -> Result<Vec<SessionConfig>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    match state.journal() {
+        Some(journal) => journal
+            .restorable_sessions(&canonical)
+            .map_err(|e| format!("Failed to read session journal: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Re-spawns the sessions saved by the previous run for a project.
+///
+/// Each saved session gets a fresh login shell via `ProcessManager::spawn_shell`
+/// in its worktree (or the project path if the worktree no longer exists) and
+/// is re-registered with `SessionManager` under the new PTY ID, keeping its
+/// `AiMode` and branch. A session still running in the session daemon (same
+/// ID and working directory) is adopted under its old ID instead, so the
+/// frontend can reattach to it. `env` is passed to every spawned shell, as with
+/// `spawn_shell`. Claude sessions are registered with the status server and
+/// get their `.mcp.json` rewritten, as on a normal launch. Sessions that fail
+/// to spawn are logged and skipped. The restore file is cleared afterwards so
+/// the same sessions are not restored twice.
+#[tauri::command]
+pub async fn restore_sessions(
+    app_handle: AppHandle,
+    state: State<'_, SessionManager>,
+    process_manager: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    mcp_state: State<'_, McpManager>,
+    project_path: String,
+    env: Option<HashMap<String, String>>,
+) -> Result<Vec<RestoredSession>, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    let journal = match state.journal() {
+        Some(journal) => journal,
+        None => return Ok(Vec::new()),
+    };
+    let saved = journal
+        .restorable_sessions(&canonical)
+        .map_err(|e| format!("Failed to read session journal: {}", e))?;
+
+    let pm = process_manager.inner().clone();
+    let live = if daemon.enabled().await {
+        daemon.list().await.unwrap_or_else(|e| {
+            log::warn!("Failed to list daemon sessions: {}", e);
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+    let mut restored = Vec::with_capacity(saved.len());
+    for previous in saved {
+        // Only reuse the worktree if it survived the restart
+        let worktree_path = previous
+            .worktree_path
+            .clone()
+            .filter(|p| Path::new(p).is_dir());
+        if previous.worktree_path.is_some() && worktree_path.is_none() {
+            log::warn!(
+                "Worktree for session {} is gone, restoring in project path",
+                previous.id
+            );
+        }
+        let cwd = worktree_path.clone().unwrap_or_else(|| canonical.clone());
+
+        let adopted = live
+            .iter()
+            .any(|s| s.id == previous.id && s.cwd.as_deref() == Some(cwd.as_str()));
+        let new_id = if adopted {
+            previous.id
+        } else {
+            let spawned =
+                spawn_shell_inner(&pm, &daemon, app_handle.clone(), Some(cwd), env.clone()).await;
+            match spawned {
+                Ok(id) => id,
+                Err(e) => {
+                    log::warn!("Failed to restore session {}: {}", previous.id, e);
+                    continue;
+                }
+            }
+        };
+
+        let mut session = match state.create_session(new_id, previous.mode.clone(), canonical.clone()) {
+            Ok(session) => session,
+            Err(existing) => {
+                log::warn!("Session {} already exists, skipping restore", existing.id);
+                if adopted {
+                    continue;
+                }
+                if let Err(e) = kill_session_inner(&pm, &daemon, new_id).await {
+                    log::warn!("Failed to kill shell {} of skipped session: {}", new_id, e);
+                }
+                continue;
+            }
+        };
+        if let Some(branch) = previous.branch.clone() {
+            if let Some(updated) = state.assign_branch(new_id, branch, worktree_path.clone()) {
+                session = updated;
+            }
+        }
+
+        // Same setup as a normal Claude launch: MCP servers enabled per the
+        // project defaults, and a status server registration via .mcp.json
+        if matches!(previous.mode, AiMode::Claude) {
+            let enabled = match mcp::project_mcp_defaults(&app_handle, &canonical) {
+                Ok(Some(enabled)) => enabled,
+                _ => mcp_state.get_session_enabled(&canonical, new_id),
+            };
+            mcp_state.set_session_enabled(&canonical, new_id, enabled.clone());
+            let working_dir = worktree_path.unwrap_or_else(|| canonical.clone());
+            if let Err(e) = mcp::write_session_mcp_config_inner(
+                &app_handle,
+                &mcp_state,
+                &working_dir,
+                new_id,
+                &canonical,
+                &enabled,
+            )
+            .await
+            {
+                log::warn!("Failed to write MCP config for restored session {}: {}", new_id, e);
+            }
+        }
+
+        log::info!("Restored session {} as {}", previous.id, new_id);
+        restored.push(RestoredSession {
+            previous_id: previous.id,
+            session,
+        });
+    }
+
+    if let Err(e) = journal.clear_restorable(&canonical) {
+        log::warn!("Failed to clear restored session journal: {}", e);
+    }
+
+    Ok(restored)
+}
+
+/// Forgets the sessions saved by the previous run for a project.
+#[tauri::command]
+pub async fn discard_restorable_sessions(
+    state: State<'_, SessionManager>,
+    project_path: String,
+) -> Result<(), String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    if let Some(journal) = state.journal() {
+        journal
+            .clear_restorable(&canonical)
+            .map_err(|e| format!("Failed to clear session journal: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Returns the built-in status inference rules for a mode, so the settings
+/// UI can show them next to the project's own rules.
+#[tauri::command]
+pub async fn get_builtin_status_rules(mode: AiMode) -> Result<Vec<StatusRule>, String> {
+    Ok(status_detector::builtin_rules(&mode))
+}
+
+/// Loads a project's status inference settings and user-defined rules.
+#[tauri::command]
+pub async fn get_status_rules(
+    app: AppHandle,
+    project_path: String,
+) -> Result<StatusRuleConfig, String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+
+    Ok(status_detector::load_project_rules(&app, &canonical))
+}
+
+/// Saves a project's status inference settings. Every pattern must compile.
+/// Running sessions in the project pick up the new rules on their next output.
+#[tauri::command]
+pub async fn save_status_rules(
+    app: AppHandle,
+    inference: State<'_, StatusInference>,
+    project_path: String,
+    config: StatusRuleConfig,
+) -> Result<(), String> {
+    let canonical = std::fs::canonicalize(&project_path)
+        .map_err(|e| format!("Invalid project path '{}': {}", project_path, e))?
+        .to_string_lossy()
+        .into_owned();
+    config.validate()?;
+
+    let store_name = format!("maestro-{}.json", StatusServer::generate_project_hash(&canonical));
+    let store = app.store(&store_name).map_err(|e| e.to_string())?;
+    let value = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+    store.set(status_detector::STATUS_RULES_KEY, value);
+    store.save().map_err(|e| e.to_string())?;
+
+    inference.set_project_rules(&canonical, config);
+    Ok(())
+}
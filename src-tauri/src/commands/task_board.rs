@@ -0,0 +1,115 @@
+use tauri::{AppHandle, State};
+
+use super::canonical_project;
+use crate::core::task_board::{Task, TaskBoards};
+
+/// Returns a project's tasks, oldest first.
+#[tauri::command]
+pub async fn get_task_board(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+) -> Result<Vec<Task>, String> {
+    let canonical = canonical_project(&project_path)?;
+    let board = boards.load(&app, &canonical).map_err(|e| e.to_string())?;
+    Ok(board.tasks)
+}
+
+/// Adds an open task to a project's board.
+#[tauri::command]
+pub async fn create_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    body: String,
+) -> Result<Task, String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| board.create(body))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Assigns an open task to a session on the user's behalf.
+#[tauri::command]
+pub async fn assign_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    task_id: u64,
+    session_id: u32,
+) -> Result<Task, String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| {
+            board.claim(session_id, Some(task_id))
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Marks a task done, whoever holds it.
+#[tauri::command]
+pub async fn complete_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    task_id: u64,
+    result: Option<String>,
+) -> Result<Task, String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| {
+            board.finish(task_id, None, true, result)
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Marks a task failed, whoever holds it.
+#[tauri::command]
+pub async fn fail_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    task_id: u64,
+    reason: Option<String>,
+) -> Result<Task, String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| {
+            board.finish(task_id, None, false, reason)
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Puts a task back in the queue, unassigned.
+#[tauri::command]
+pub async fn reopen_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    task_id: u64,
+) -> Result<Task, String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| board.reopen(task_id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Removes a task from a project's board.
+#[tauri::command]
+pub async fn delete_task(
+    app: AppHandle,
+    boards: State<'_, TaskBoards>,
+    project_path: String,
+    task_id: u64,
+) -> Result<(), String> {
+    let canonical = canonical_project(&project_path)?;
+    boards
+        .update(&app, &canonical, move |board| board.delete(task_id))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -2,12 +2,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_store::StoreExt;
 
-use crate::core::session_manager::SessionManager;
+use crate::commands::recording;
+use crate::core::scrollback::{
+    ScrollbackChunk, MAX_SCROLLBACK_LIMIT, MIN_SCROLLBACK_LIMIT, SCROLLBACK_LIMIT_KEY,
+    SCROLLBACK_STORE,
+};
+#[cfg(unix)]
+use crate::core::launch_profile::cli_search_paths;
+use crate::core::launch_profile::{resolve_program, LaunchProfile, LAUNCH_PROFILES_STORE};
+use crate::core::session_manager::{AiMode, SessionManager};
+use crate::core::status_detector::StatusInference;
 use crate::core::status_server::StatusServer;
+use crate::core::task_board::TaskBoards;
 use crate::core::windows_process::TokioCommandExt;
 use crate::core::{BackendCapabilities, BackendType, ProcessManager, PtyError, SessionProcessTree};
+use crate::daemon::DaemonClient;
 
 /// Backend information returned to the frontend.
 #[derive(Debug, Clone, Serialize)]
@@ -79,18 +91,64 @@ pub fn get_backend_info() -> BackendInfo {
 /// forwarding to the process manager. Returns the new session ID.
 /// The frontend should listen on `pty-output-{id}` for shell output events.
 ///
+/// When the session daemon is available (see `DaemonClient::enabled`) the
+/// shell is spawned there instead, so it survives the app closing. The
+/// session ID and events are the same either way.
+///
 /// # Environment Variables
 /// The `env` parameter allows passing environment variables to the shell process.
 /// These are inherited by all child processes (including Claude CLI → MCP server).
 /// Common usage: `{ "MAESTRO_PROJECT_HASH": "<hash>" }` for MCP status identification.
 /// Note: `MAESTRO_SESSION_ID` is automatically set by the process manager.
+///
+/// With `record: true`, recording starts as soon as the shell is spawned
+/// (see `start_session_recording`). A recording failure is logged and does
+/// not fail the spawn.
 #[tauri::command]
 pub async fn spawn_shell(
     app_handle: AppHandle,
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     cwd: Option<String>,
     env: Option<HashMap<String, String>>,
+    record: Option<bool>,
 ) -> Result<u32, PtyError> {
+    let canonical_cwd = validate_cwd(cwd)?;
+    let pm = state.inner().clone();
+    let record_dir = match record.unwrap_or(false) {
+        true => Some(recording::recordings_dir(&app_handle).map_err(PtyError::spawn_failed)?),
+        false => None,
+    };
+    let id = spawn_shell_inner(&pm, &daemon, app_handle, canonical_cwd, env).await?;
+    if let Some(dir) = record_dir {
+        if let Err(e) = recording::start_recording_inner(&pm, &daemon, id, &dir).await {
+            log::warn!("Session {id} started without recording: {}", e);
+        }
+    }
+    Ok(id)
+}
+
+/// Spawns a login shell in the daemon when it is available, otherwise in
+/// the in-process `ProcessManager`.
+pub(crate) async fn spawn_shell_inner(
+    pm: &ProcessManager,
+    daemon: &DaemonClient,
+    app_handle: AppHandle,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+) -> Result<u32, PtyError> {
+    if daemon.enabled().await {
+        return daemon
+            .spawn(cwd, env)
+            .await
+            .map_err(|e| PtyError::spawn_failed(e.to_string()));
+    }
+    pm.spawn_shell(app_handle, cwd, env)
+}
+
+/// Canonicalizes a requested working directory, which must exist and be a
+/// directory.
+fn validate_cwd(cwd: Option<String>) -> Result<Option<String>, PtyError> {
     // Validate cwd if provided: must exist and be a directory
     let canonical_cwd = if let Some(ref dir) = cwd {
         let path = std::path::Path::new(dir);
@@ -119,8 +177,118 @@ pub async fn spawn_shell(
     } else {
         None
     };
+    Ok(canonical_cwd)
+}
+
+/// Returns the launch profile for a mode: the user's saved override, or the
+/// built-in default. `Plain` has no profile.
+fn launch_profile(app: &AppHandle, mode: &AiMode) -> Option<LaunchProfile> {
+    let saved = app
+        .store(LAUNCH_PROFILES_STORE)
+        .ok()
+        .and_then(|store| store.get(LaunchProfile::store_key(mode)))
+        .and_then(|value| serde_json::from_value(value).ok());
+    saved.or_else(|| LaunchProfile::default_for(mode))
+}
+
+/// Launches a mode's CLI directly as the PTY's child, using its launch
+/// profile, and returns the new session ID.
+///
+/// `args` are appended after the profile's default flags. `env` is layered
+/// over the profile's env. On Unix, `PATH` defaults to the same directories
+/// `check_cli_available` searches, so CLIs installed via npm/Homebrew find
+/// their interpreters even when the app was started from a GUI launcher.
+/// `Plain` sessions get a login shell, as with `spawn_shell`.
+///
+/// Output arrives as `pty-output-{id}` and the CLI's exit status as
+/// `pty-exit-{id}`.
+#[tauri::command]
+pub async fn spawn_agent(
+    app_handle: AppHandle,
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    mode: AiMode,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    args: Option<Vec<String>>,
+) -> Result<u32, PtyError> {
+    let canonical_cwd = validate_cwd(cwd)?;
     let pm = state.inner().clone();
-    pm.spawn_shell(app_handle, canonical_cwd, env)
+
+    let Some(profile) = launch_profile(&app_handle, &mode) else {
+        return spawn_shell_inner(&pm, &daemon, app_handle, canonical_cwd, env).await;
+    };
+
+    let program = resolve_program(&profile.program).ok_or_else(|| {
+        PtyError::spawn_failed(format!("'{}' was not found in PATH", profile.program))
+    })?;
+
+    let mut full_env = HashMap::new();
+    #[cfg(unix)]
+    full_env.insert("PATH".to_string(), cli_search_paths().join(":"));
+    full_env.extend(profile.env);
+    full_env.extend(env.unwrap_or_default());
+
+    let mut full_args = profile.args;
+    full_args.extend(args.unwrap_or_default());
+
+    if daemon.enabled().await {
+        return daemon
+            .spawn_command(
+                program.to_string_lossy().into_owned(),
+                full_args,
+                canonical_cwd,
+                Some(full_env),
+            )
+            .await
+            .map_err(|e| PtyError::spawn_failed(e.to_string()));
+    }
+    pm.spawn_command(
+        app_handle,
+        &program.to_string_lossy(),
+        &full_args,
+        canonical_cwd,
+        Some(full_env),
+    )
+}
+
+/// Returns the effective launch profile for a mode, or `None` for `Plain`.
+#[tauri::command]
+pub async fn get_launch_profile(
+    app_handle: AppHandle,
+    mode: AiMode,
+) -> Result<Option<LaunchProfile>, String> {
+    Ok(launch_profile(&app_handle, &mode))
+}
+
+/// Saves a launch profile override for a mode. Passing `None` restores the
+/// built-in default.
+#[tauri::command]
+pub async fn save_launch_profile(
+    app_handle: AppHandle,
+    mode: AiMode,
+    profile: Option<LaunchProfile>,
+) -> Result<(), String> {
+    if matches!(mode, AiMode::Plain) {
+        return Err("Plain sessions always run a login shell".to_string());
+    }
+    let store = app_handle
+        .store(LAUNCH_PROFILES_STORE)
+        .map_err(|e| e.to_string())?;
+    let key = LaunchProfile::store_key(&mode);
+    match profile {
+        Some(profile) => {
+            if profile.program.trim().is_empty() {
+                return Err("Launch profile program must not be empty".to_string());
+            }
+            let value = serde_json::to_value(&profile).map_err(|e| e.to_string())?;
+            store.set(key, value);
+        }
+        None => {
+            store.delete(key);
+        }
+    }
+    store.save().map_err(|e| e.to_string())
 }
 
 /// Exposes `ProcessManager::write_stdin` to the frontend.
@@ -128,18 +296,89 @@ pub async fn spawn_shell(
 #[tauri::command]
 pub async fn write_stdin(
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     session_id: u32,
     data: String,
 ) -> Result<(), PtyError> {
+    if daemon.enabled().await {
+        return daemon
+            .write(session_id, data)
+            .await
+            .map_err(|e| PtyError::write_failed(e.to_string()));
+    }
     let pm = state.inner().clone();
     pm.write_stdin(session_id, &data)
 }
 
+/// Exposes `ProcessManager::get_scrollback` to the frontend.
+///
+/// Returns output buffered since `since_offset` (default 0, i.e. everything
+/// still buffered) plus the offset to resume from. Used to repopulate a pane
+/// after a webview reload or remount without gaps or duplicates.
+///
+/// For a daemon-hosted session this attaches to it: live output follows as
+/// `pty-output-{id}` events until `detach_session`.
+#[tauri::command]
+pub async fn get_session_scrollback(
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    session_id: u32,
+    since_offset: Option<u64>,
+) -> Result<ScrollbackChunk, PtyError> {
+    if daemon.enabled().await {
+        return daemon
+            .attach(session_id, since_offset.unwrap_or(0))
+            .await
+            .map_err(|_| PtyError::session_not_found(session_id));
+    }
+    let pm = state.inner().clone();
+    pm.get_scrollback(session_id, since_offset.unwrap_or(0))
+}
+
+/// Returns the per-session scrollback limit in bytes.
+#[tauri::command]
+pub async fn get_scrollback_limit(state: State<'_, ProcessManager>) -> Result<usize, String> {
+    Ok(state.scrollback_limit())
+}
+
+/// Sets the per-session scrollback limit in bytes, applies it to running
+/// sessions (in the session daemon too, when it hosts them) and saves it for
+/// future runs.
+#[tauri::command]
+pub async fn set_scrollback_limit(
+    app_handle: AppHandle,
+    state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
+    limit: usize,
+) -> Result<(), String> {
+    if !(MIN_SCROLLBACK_LIMIT..=MAX_SCROLLBACK_LIMIT).contains(&limit) {
+        return Err(format!(
+            "Scrollback limit must be between {} and {} bytes",
+            MIN_SCROLLBACK_LIMIT, MAX_SCROLLBACK_LIMIT
+        ));
+    }
+    let store = app_handle
+        .store(SCROLLBACK_STORE)
+        .map_err(|e| e.to_string())?;
+    store.set(SCROLLBACK_LIMIT_KEY, serde_json::json!(limit));
+    store.save().map_err(|e| e.to_string())?;
+
+    state.set_scrollback_limit(limit);
+    if daemon.enabled().await {
+        daemon
+            .set_scrollback_limit(limit)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 /// Exposes `ProcessManager::resize_pty` to the frontend.
 /// Rejects dimensions that are zero or exceed 500 to prevent misuse.
 #[tauri::command]
 pub async fn resize_pty(
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     session_id: u32,
     rows: u16,
     cols: u16,
@@ -147,37 +386,66 @@ pub async fn resize_pty(
     if rows == 0 || cols == 0 || rows > 500 || cols > 500 {
         return Err(PtyError::resize_failed("Invalid dimensions"));
     }
+    if daemon.enabled().await {
+        return daemon
+            .resize(session_id, rows, cols)
+            .await
+            .map_err(|e| PtyError::resize_failed(e.to_string()));
+    }
     let pm = state.inner().clone();
     pm.resize_pty(session_id, rows, cols)
 }
 
 /// Exposes `ProcessManager::kill_session` to the frontend.
 /// Gracefully terminates the PTY session (SIGTERM, then SIGKILL after 3s).
-/// Also unregisters the session from the status server.
+/// Also reopens the tasks the session still held and unregisters it from
+/// the status server, if it is running.
 #[tauri::command]
 pub async fn kill_session(
+    app_handle: AppHandle,
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     session_mgr: State<'_, SessionManager>,
-    status_server: State<'_, Arc<StatusServer>>,
+    inference: State<'_, StatusInference>,
     session_id: u32,
 ) -> Result<(), PtyError> {
     // Kill the PTY session
-    let pm = state.inner().clone();
-    let result = pm.kill_session(session_id).await;
+    let result = kill_session_inner(&state, &daemon, session_id).await;
 
-    // Unregister the session from the status server so it stops accepting updates
-    status_server.unregister_session(session_id).await;
+    // Reopen any tasks the session still held
+    if let (Some(session), Some(boards)) = (
+        session_mgr.get_session(session_id),
+        app_handle.try_state::<TaskBoards>(),
+    ) {
+        boards
+            .release_session(&app_handle, &session.project_path, session_id)
+            .await;
+    }
 
-    // Log for debugging
-    let _project_path = session_mgr
-        .all_sessions()
-        .into_iter()
-        .find(|s| s.id == session_id)
-        .map(|s| s.project_path);
+    // Unregister the session from the status server so it stops accepting updates
+    if let Some(status_server) = app_handle.try_state::<Arc<StatusServer>>() {
+        status_server.unregister_session(session_id).await;
+    }
+    inference.forget(session_id);
 
     result
 }
 
+/// Kills a session wherever it is hosted.
+pub(crate) async fn kill_session_inner(
+    pm: &ProcessManager,
+    daemon: &DaemonClient,
+    session_id: u32,
+) -> Result<(), PtyError> {
+    if daemon.enabled().await {
+        return daemon
+            .kill(session_id)
+            .await
+            .map_err(|e| PtyError::kill_failed(e.to_string()));
+    }
+    pm.kill_session(session_id).await
+}
+
 /// Returns the process tree for a specific session.
 ///
 /// The tree includes the root shell process and all its descendants.
@@ -185,10 +453,18 @@ pub async fn kill_session(
 #[tauri::command]
 pub async fn get_session_process_tree(
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     session_id: u32,
 ) -> Result<Option<SessionProcessTree>, String> {
-    let pm = state.inner().clone();
-    let root_pid = match pm.get_session_pid(session_id) {
+    let root_pid = if daemon.enabled().await {
+        daemon
+            .session_pid(session_id)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        state.get_session_pid(session_id)
+    };
+    let root_pid = match root_pid {
         Some(pid) => pid,
         None => return Ok(None),
     };
@@ -203,9 +479,9 @@ pub async fn get_session_process_tree(
 #[tauri::command]
 pub async fn get_all_process_trees(
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
 ) -> Result<Vec<SessionProcessTree>, String> {
-    let pm = state.inner().clone();
-    let sessions = pm.get_all_session_pids();
+    let sessions = session_pids(&state, &daemon).await?;
     Ok(crate::core::process_tree::get_all_process_trees(&sessions))
 }
 
@@ -216,11 +492,11 @@ pub async fn get_all_process_trees(
 #[tauri::command]
 pub async fn kill_process(
     state: State<'_, ProcessManager>,
+    daemon: State<'_, DaemonClient>,
     pid: u32,
 ) -> Result<(), String> {
-    let pm = state.inner().clone();
-    let session_root_pids: Vec<i32> = pm
-        .get_all_session_pids()
+    let session_root_pids: Vec<i32> = session_pids(&state, &daemon)
+        .await?
         .into_iter()
         .map(|(_, root_pid)| root_pid)
         .collect();
@@ -230,10 +506,43 @@ pub async fn kill_process(
         .map_err(|e| e.to_string())
 }
 
-/// Kills all active PTY sessions.
+/// Every session's ID and root PID, from the daemon when it hosts sessions.
+async fn session_pids(
+    pm: &ProcessManager,
+    daemon: &DaemonClient,
+) -> Result<Vec<(u32, i32)>, String> {
+    if daemon.enabled().await {
+        return daemon.session_pids().await.map_err(|e| e.to_string());
+    }
+    Ok(pm.get_all_session_pids())
+}
+
+/// Stops emitting a daemon-hosted session's output to the webview. The
+/// session keeps running and can be picked up again with
+/// `get_session_scrollback`; the app still runs status inference on its
+/// output and finalizes it when it exits. No-op for in-process sessions.
+#[tauri::command]
+pub async fn detach_session(
+    daemon: State<'_, DaemonClient>,
+    session_id: u32,
+) -> Result<(), String> {
+    if !daemon.enabled().await {
+        return Ok(());
+    }
+    daemon.detach(session_id).await.map_err(|e| e.to_string())
+}
+
+/// Whether sessions are hosted by the session daemon and outlive the app.
+#[tauri::command]
+pub async fn sessions_persist(daemon: State<'_, DaemonClient>) -> Result<bool, String> {
+    Ok(daemon.enabled().await)
+}
+
+/// Kills all in-process PTY sessions.
 ///
 /// Used to clean up orphaned sessions when the frontend reloads.
-/// Returns the number of sessions that were killed.
+/// Returns the number of sessions that were killed. Daemon-hosted sessions
+/// are not touched; the frontend reattaches to them instead.
 #[tauri::command]
 pub async fn kill_all_sessions(state: State<'_, ProcessManager>) -> Result<u32, PtyError> {
     let pm = state.inner().clone();
@@ -255,43 +564,9 @@ pub async fn check_cli_available(command: String) -> Result<bool, String> {
         // Search for command in PATH and common installation directories
         // We avoid spawning a shell because shell plugins (oh-my-zsh, powerlevel10k)
         // can hang or abort when run without a TTY
-        let mut paths: Vec<String> = Vec::new();
-
-        // Start with current environment PATH
-        if let Ok(env_path) = std::env::var("PATH") {
-            paths.extend(env_path.split(':').map(String::from));
-        }
-
-        // Add common user installation directories that GUI launchers often miss
-        if let Ok(home) = std::env::var("HOME") {
-            // Homebrew on Apple Silicon
-            paths.push("/opt/homebrew/bin".to_string());
-            paths.push("/opt/homebrew/sbin".to_string());
-            // Homebrew on Intel Mac
-            paths.push("/usr/local/bin".to_string());
-            paths.push("/usr/local/sbin".to_string());
-            // npm global installations
-            paths.push(format!("{}/.npm-global/bin", home));
-            paths.push(format!("{}/node_modules/.bin", home));
-            // Cargo/Rust
-            paths.push(format!("{}/.cargo/bin", home));
-            // Go
-            paths.push(format!("{}/go/bin", home));
-            // Python user installs
-            paths.push(format!("{}/.local/bin", home));
-            // pyenv
-            paths.push(format!("{}/.pyenv/shims", home));
-            // rbenv
-            paths.push(format!("{}/.rbenv/shims", home));
-        }
-
-        // Search for command in all PATH directories
-        for dir in &paths {
-            let cmd_path = format!("{}/{}", dir, command);
-            if std::path::Path::new(&cmd_path).exists() {
-                log::debug!("Found {} at {}", command, cmd_path);
-                return Ok(true);
-            }
+        if let Some(path) = resolve_program(&command) {
+            log::debug!("Found {} at {}", command, path.display());
+            return Ok(true);
         }
 
         log::debug!("Command {} not found in PATH", command);
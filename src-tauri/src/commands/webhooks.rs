@@ -0,0 +1,38 @@
+use tauri::AppHandle;
+
+use crate::core::webhooks::{self, WebhookConfig, WebhookEvent};
+
+/// Returns the configured outgoing webhooks.
+#[tauri::command]
+pub async fn get_webhooks(app: AppHandle) -> Result<Vec<WebhookConfig>, String> {
+    Ok(webhooks::load_webhooks(&app))
+}
+
+/// Replaces the configured webhooks. Fails without saving anything if a
+/// hook has a bad URL or a template that doesn't render to JSON.
+#[tauri::command]
+pub async fn set_webhooks(app: AppHandle, hooks: Vec<WebhookConfig>) -> Result<(), String> {
+    webhooks::save_webhooks(&app, &hooks)
+}
+
+/// Sends a sample `session.status` event to `hook` right away, without
+/// retries, so the user can check the URL, template and signature.
+#[tauri::command]
+pub async fn send_test_webhook(hook: WebhookConfig) -> Result<(), String> {
+    hook.validate()?;
+    let event = WebhookEvent::status(
+        0,
+        "/path/to/project",
+        "Done",
+        "Test event from Maestro",
+        Some("main"),
+    );
+    let retry = webhooks::RetryPolicy {
+        attempts: 1,
+        ..Default::default()
+    };
+    let client = reqwest::Client::new();
+    webhooks::deliver(&client, &hook, &event, "test", retry)
+        .await
+        .map(|_| ())
+}
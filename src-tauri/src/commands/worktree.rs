@@ -1,10 +1,13 @@
 use std::path::PathBuf;
 
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
 
-use crate::core::worktree_manager::WorktreeManager;
-use crate::git::Git;
+use super::canonical_project;
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_bootstrap::{self, BootstrapResult, ProgressSink, WorktreeBootstrap};
+use crate::core::worktree_manager::{WorktreeGcReport, WorktreeGcResult, WorktreeManager};
+use crate::git::{Git, MergeResult, MergeStrategy};
 
 /// Result of preparing a worktree for a session.
 #[derive(Debug, Clone, Serialize)]
@@ -17,6 +20,10 @@ pub struct WorktreePreparationResult {
     pub created: bool,
     /// Warning message if something unexpected happened but we recovered.
     pub warning: Option<String>,
+    /// What applying the project's bootstrap config to a newly created
+    /// worktree did, if it has one. Progress arrives as
+    /// `worktree-bootstrap-progress` events while it runs.
+    pub bootstrap: Option<BootstrapResult>,
 }
 
 /// Prepares a worktree for a session, handling all edge cases gracefully.
@@ -27,18 +34,34 @@ pub struct WorktreePreparationResult {
 /// 3. If the branch is checked out in the main repo, switches main to a fallback first.
 /// 4. If the branch doesn't exist locally, creates it (handling remote branches).
 /// 5. Creates the worktree via WorktreeManager.
+/// 6. Bootstraps a newly created worktree with the project's
+///    `WorktreeBootstrap` config, if any, so the session starts with its
+///    files and dependencies in place.
 ///
 /// On any failure, falls back to the project path so sessions always launch.
 /// The caller is responsible for updating the session with the worktree path.
 #[tauri::command]
 pub async fn prepare_session_worktree(
+    app: AppHandle,
     worktree_manager: State<'_, WorktreeManager>,
     project_path: String,
     branch: Option<String>,
 ) -> Result<WorktreePreparationResult, String> {
-    prepare_worktree_inner(&worktree_manager, project_path, branch).await
+    let config = canonical_project(&project_path)
+        .map(|canonical| worktree_bootstrap::load_config(&app, &canonical))
+        .unwrap_or_default();
+    prepare_worktree_inner(
+        &worktree_manager,
+        project_path,
+        branch,
+        Some((config, worktree_bootstrap::event_sink(app))),
+    )
+    .await
 }
 
+/// What to bootstrap a new worktree with, and where its progress goes.
+pub(crate) type BootstrapRequest = (WorktreeBootstrap, ProgressSink);
+
 /// Inner implementation extracted from the Tauri command for testability.
 ///
 /// All logic lives here so that tests can call this directly without
@@ -47,6 +70,7 @@ pub(crate) async fn prepare_worktree_inner(
     worktree_manager: &WorktreeManager,
     project_path: String,
     branch: Option<String>,
+    bootstrap: Option<BootstrapRequest>,
 ) -> Result<WorktreePreparationResult, String> {
     // No branch specified - just use the project path
     let branch = match branch {
@@ -57,6 +81,7 @@ pub(crate) async fn prepare_worktree_inner(
                 worktree_path: None,
                 created: false,
                 warning: None,
+                bootstrap: None,
             });
         }
     };
@@ -89,6 +114,7 @@ pub(crate) async fn prepare_worktree_inner(
                             worktree_path: Some(wt.path.clone()),
                             created: false,
                             warning: None,
+                            bootstrap: None,
                         });
                     }
                 }
@@ -150,6 +176,7 @@ pub(crate) async fn prepare_worktree_inner(
             worktree_path: None,
             created: false,
             warning: Some(format!("Failed to create branch {}: {}", local_branch, e)),
+            bootstrap: None,
         });
     }
 
@@ -163,11 +190,32 @@ pub(crate) async fn prepare_worktree_inner(
                 local_branch
             );
 
+            let bootstrap = match bootstrap.filter(|(config, _)| !config.is_empty()) {
+                Some((config, progress)) => {
+                    let result =
+                        worktree_bootstrap::bootstrap(&config, &repo_path, &wt_path, &progress)
+                            .await;
+                    if !result.succeeded() {
+                        log::warn!(
+                            "Worktree bootstrap of {} did not complete: {:?}",
+                            wt_path.display(),
+                            result.errors
+                        );
+                        warning.get_or_insert_with(|| {
+                            "Worktree bootstrap did not complete".to_string()
+                        });
+                    }
+                    Some(result)
+                }
+                None => None,
+            };
+
             Ok(WorktreePreparationResult {
                 working_directory: wt_path_str.clone(),
                 worktree_path: Some(wt_path_str),
                 created: true,
                 warning,
+                bootstrap,
             })
         }
         Err(e) => {
@@ -177,6 +225,7 @@ pub(crate) async fn prepare_worktree_inner(
                 worktree_path: None,
                 created: false,
                 warning: Some(format!("Failed to create worktree: {}", e)),
+                bootstrap: None,
             })
         }
     }
@@ -220,6 +269,133 @@ pub(crate) async fn cleanup_worktree_inner(
     }
 }
 
+/// Result of merging a session's branch, plus whether its worktree was
+/// removed afterwards.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMergeResult {
+    #[serde(flatten)]
+    pub merge: MergeResult,
+    pub worktree_cleaned: bool,
+}
+
+/// Merges, squash-merges or rebases a session's branch onto `target_branch`.
+///
+/// A dry run (`git merge-tree`) runs first; if it finds conflicts they are
+/// returned and nothing changes. On success, `cleanup_worktree_path` (the
+/// session's worktree) is removed if given.
+#[tauri::command]
+pub async fn merge_session_branch(
+    worktree_manager: State<'_, WorktreeManager>,
+    project_path: String,
+    source_branch: String,
+    target_branch: String,
+    strategy: MergeStrategy,
+    message: Option<String>,
+    cleanup_worktree_path: Option<String>,
+) -> Result<SessionMergeResult, String> {
+    merge_session_branch_inner(
+        &worktree_manager,
+        project_path,
+        &source_branch,
+        &target_branch,
+        strategy,
+        message.as_deref(),
+        cleanup_worktree_path,
+    )
+    .await
+}
+
+/// Inner implementation for merging, extracted for testability.
+pub(crate) async fn merge_session_branch_inner(
+    worktree_manager: &WorktreeManager,
+    project_path: String,
+    source_branch: &str,
+    target_branch: &str,
+    strategy: MergeStrategy,
+    message: Option<&str>,
+    cleanup_worktree_path: Option<String>,
+) -> Result<SessionMergeResult, String> {
+    let git = Git::new(&project_path);
+    let merge = git
+        .merge_branch(source_branch, target_branch, strategy, message)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let worktree_cleaned = match cleanup_worktree_path {
+        Some(worktree_path) if merge.merged => {
+            cleanup_worktree_inner(worktree_manager, project_path, worktree_path).await?
+        }
+        _ => false,
+    };
+    Ok(SessionMergeResult {
+        merge,
+        worktree_cleaned,
+    })
+}
+
+/// Returns how a project's new worktrees are set up. Projects that were
+/// never configured get an empty config.
+#[tauri::command]
+pub async fn get_worktree_bootstrap(
+    app: AppHandle,
+    project_path: String,
+) -> Result<WorktreeBootstrap, String> {
+    let canonical = canonical_project(&project_path)?;
+    Ok(worktree_bootstrap::load_config(&app, &canonical))
+}
+
+/// Replaces a project's worktree bootstrap config.
+#[tauri::command]
+pub async fn set_worktree_bootstrap(
+    app: AppHandle,
+    project_path: String,
+    config: WorktreeBootstrap,
+) -> Result<(), String> {
+    config.validate()?;
+    let canonical = canonical_project(&project_path)?;
+    worktree_bootstrap::save_config(&app, &canonical, &config)
+}
+
+/// Worktree paths of the live sessions, which GC must never touch.
+fn live_worktrees(session_manager: &SessionManager) -> Vec<PathBuf> {
+    session_manager
+        .all_sessions()
+        .into_iter()
+        .filter_map(|s| s.worktree_path.map(PathBuf::from))
+        .collect()
+}
+
+/// Dry-run worktree garbage collection across all repos.
+///
+/// Reports every Maestro-managed worktree that no live session uses, with
+/// whether it is stale, dirty, orphaned or left behind by a deleted repo,
+/// and its disk usage. Nothing is removed.
+#[tauri::command]
+pub async fn scan_worktree_gc(
+    worktree_manager: State<'_, WorktreeManager>,
+    session_manager: State<'_, SessionManager>,
+) -> Result<WorktreeGcReport, String> {
+    let in_use = live_worktrees(&session_manager);
+    Ok(worktree_manager.gc_scan(&in_use).await)
+}
+
+/// Removes the worktrees the user confirmed from a `scan_worktree_gc`
+/// report. Each is re-checked first; dirty, in-use and unknown paths are
+/// skipped, and so are orphaned ones unless `force_orphaned` is set.
+#[tauri::command]
+pub async fn apply_worktree_gc(
+    worktree_manager: State<'_, WorktreeManager>,
+    session_manager: State<'_, SessionManager>,
+    paths: Vec<String>,
+    force_orphaned: bool,
+) -> Result<WorktreeGcResult, String> {
+    let in_use = live_worktrees(&session_manager);
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+    Ok(worktree_manager
+        .gc_apply(&paths, &in_use, force_orphaned)
+        .await)
+}
+
 /// Gets a fallback branch to switch to when the target branch is checked out.
 ///
 /// Tries init.defaultBranch config, then looks for main/master.
@@ -319,7 +495,9 @@ async fn ensure_local_branch(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::worktree_bootstrap::BootstrapProgress;
     use crate::core::worktree_manager::WorktreeManager;
+    use std::sync::Arc;
     use tempfile::tempdir;
 
     /// Checks if a branch exists locally (test helper).
@@ -374,7 +552,7 @@ mod tests {
     async fn test_prepare_no_branch_returns_project_path() {
         let (_dir, path) = create_test_repo().await;
         let wm = WorktreeManager::new();
-        let result = prepare_worktree_inner(&wm, path.to_string_lossy().to_string(), None)
+        let result = prepare_worktree_inner(&wm, path.to_string_lossy().to_string(), None, None)
             .await
             .unwrap();
 
@@ -392,6 +570,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("".to_string()),
+            None,
         )
         .await
         .unwrap();
@@ -416,6 +595,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some(current.clone()),
+            None,
         )
         .await
         .unwrap();
@@ -456,6 +636,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some(current.clone()),
+            None,
         )
         .await
         .unwrap();
@@ -483,6 +664,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("feature-test".to_string()),
+            None,
         )
         .await
         .unwrap();
@@ -499,6 +681,81 @@ mod tests {
         let _ = wm.remove(&path, &wt_path).await;
     }
 
+    #[tokio::test]
+    async fn test_merge_session_branch_cleans_up_worktree() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        let main = git.current_branch().await.unwrap();
+        create_branch(&git, "merge-test").await;
+
+        let wm = WorktreeManager::new();
+        let wt_path = wm.create("merge-test", &path).await.unwrap();
+        tokio::fs::write(wt_path.join("agent.txt"), "done").await.unwrap();
+        let wt_git = Git::new(&wt_path);
+        wt_git.run(&["add", "."]).await.unwrap();
+        wt_git.run(&["commit", "-m", "agent work"]).await.unwrap();
+
+        let result = merge_session_branch_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            "merge-test",
+            &main,
+            MergeStrategy::Merge,
+            None,
+            Some(wt_path.to_string_lossy().to_string()),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.merge.merged);
+        assert!(result.worktree_cleaned);
+        assert!(!wt_path.exists());
+        assert!(path.join("agent.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_bootstraps_new_worktree() {
+        let (_dir, path) = create_test_repo().await;
+        let git = Git::new(&path);
+        create_branch(&git, "bootstrap-test").await;
+        tokio::fs::write(path.join(".env"), "KEY=1").await.unwrap();
+
+        let config = WorktreeBootstrap {
+            copy: vec![".env*".to_string()],
+            ..Default::default()
+        };
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let progress: ProgressSink = Arc::new(move |_, progress| {
+            let _ = sender.send(progress);
+        });
+        let wm = WorktreeManager::new();
+        let result = prepare_worktree_inner(
+            &wm,
+            path.to_string_lossy().to_string(),
+            Some("bootstrap-test".to_string()),
+            Some((config, progress)),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.created);
+        assert!(result.warning.is_none());
+        let bootstrap = result.bootstrap.expect("worktree was not bootstrapped");
+        assert_eq!(bootstrap.copied, vec![".env"]);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(BootstrapProgress::FilesPlaced { copied: 1, .. })
+        ));
+        let wt_path = PathBuf::from(result.worktree_path.unwrap());
+        assert_eq!(
+            tokio::fs::read_to_string(wt_path.join(".env")).await.unwrap(),
+            "KEY=1"
+        );
+
+        // Cleanup
+        let _ = wm.remove(&path, &wt_path).await;
+    }
+
     #[tokio::test]
     async fn test_prepare_reuses_existing_managed_worktree() {
         let (_dir, path) = create_test_repo().await;
@@ -513,6 +770,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("reuse-test".to_string()),
+            None,
         )
         .await
         .unwrap();
@@ -523,6 +781,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("reuse-test".to_string()),
+            None,
         )
         .await
         .unwrap();
@@ -543,6 +802,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("brand-new-branch".to_string()),
+            None,
         )
         .await
         .unwrap();
@@ -570,6 +830,7 @@ mod tests {
             &wm,
             path.to_string_lossy().to_string(),
             Some("main".to_string()),
+            None,
         )
         .await
         .unwrap();
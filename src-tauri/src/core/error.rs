@@ -11,6 +11,7 @@ pub enum PtyErrorCode {
     ResizeFailed,
     KillFailed,
     IdOverflow,
+    RecordingFailed,
 }
 
 /// Structured PTY error with a machine-readable code and human-readable message.
@@ -80,4 +81,12 @@ impl PtyError {
             message: "Session ID counter overflowed u32::MAX".to_string(),
         }
     }
+
+    /// Starting or stopping a session recording failed.
+    pub fn recording_failed(msg: impl Into<String>) -> Self {
+        Self {
+            code: PtyErrorCode::RecordingFailed,
+            message: msg.into(),
+        }
+    }
 }
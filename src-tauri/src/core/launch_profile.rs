@@ -0,0 +1,147 @@
+//! How each AI mode's CLI is launched when spawned directly in a PTY.
+//!
+//! Typing `claude` into a login shell is fragile: shell plugins can swallow
+//! or delay the input, and the CLI's exit code is lost once the shell takes
+//! over again. A launch profile describes the argv and environment instead,
+//! so `ProcessManager::spawn_command` can exec the CLI as the PTY's child.
+//!
+//! Profiles default to the bare CLI name and can be overridden per mode by
+//! the user; overrides are stored in `launch-profiles.json`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::session_manager::AiMode;
+
+/// Store file holding user overrides, keyed by mode name.
+pub const LAUNCH_PROFILES_STORE: &str = "launch-profiles.json";
+
+/// Binary, default flags and extra environment for one AI mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaunchProfile {
+    /// Executable name or path. Bare names are resolved with
+    /// `resolve_program`.
+    pub program: String,
+    /// Flags passed before any per-launch arguments.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment added on top of the session's own.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl LaunchProfile {
+    /// Built-in profile for a mode. `Plain` has none: it runs a login shell.
+    pub fn default_for(mode: &AiMode) -> Option<Self> {
+        let program = match mode {
+            AiMode::Claude => "claude",
+            AiMode::Gemini => "gemini",
+            AiMode::Codex => "codex",
+            AiMode::Plain => return None,
+        };
+        Some(Self {
+            program: program.to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+        })
+    }
+
+    /// Store key for a mode's override.
+    pub fn store_key(mode: &AiMode) -> &'static str {
+        match mode {
+            AiMode::Claude => "Claude",
+            AiMode::Gemini => "Gemini",
+            AiMode::Codex => "Codex",
+            AiMode::Plain => "Plain",
+        }
+    }
+}
+
+/// Directories searched for CLI binaries: `$PATH` followed by common user
+/// installation directories that GUI launchers (Raycast, Spotlight) leave
+/// out of the app's environment.
+#[cfg(unix)]
+pub fn cli_search_paths() -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+
+    // Start with current environment PATH
+    if let Ok(env_path) = std::env::var("PATH") {
+        paths.extend(env_path.split(':').map(String::from));
+    }
+
+    // Add common user installation directories that GUI launchers often miss
+    if let Ok(home) = std::env::var("HOME") {
+        // Homebrew on Apple Silicon
+        paths.push("/opt/homebrew/bin".to_string());
+        paths.push("/opt/homebrew/sbin".to_string());
+        // Homebrew on Intel Mac
+        paths.push("/usr/local/bin".to_string());
+        paths.push("/usr/local/sbin".to_string());
+        // npm global installations
+        paths.push(format!("{}/.npm-global/bin", home));
+        paths.push(format!("{}/node_modules/.bin", home));
+        // Cargo/Rust
+        paths.push(format!("{}/.cargo/bin", home));
+        // Go
+        paths.push(format!("{}/go/bin", home));
+        // Python user installs
+        paths.push(format!("{}/.local/bin", home));
+        // pyenv
+        paths.push(format!("{}/.pyenv/shims", home));
+        // rbenv
+        paths.push(format!("{}/.rbenv/shims", home));
+    }
+
+    paths
+}
+
+/// Finds an executable by name in `cli_search_paths`. Names containing a
+/// path separator are returned unchanged if they exist.
+#[cfg(unix)]
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return path.exists().then_some(path);
+    }
+    cli_search_paths()
+        .iter()
+        .map(|dir| PathBuf::from(dir).join(program))
+        .find(|path| path.exists())
+}
+
+/// On Windows the PTY's `CreateProcess` searches `PATH` itself.
+#[cfg(windows)]
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    Some(PathBuf::from(program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profiles() {
+        assert_eq!(
+            LaunchProfile::default_for(&AiMode::Gemini).unwrap().program,
+            "gemini"
+        );
+        assert!(LaunchProfile::default_for(&AiMode::Plain).is_none());
+    }
+
+    #[test]
+    fn test_profile_fields_default_when_missing() {
+        let profile: LaunchProfile = serde_json::from_str(r#"{"program":"claude"}"#).unwrap();
+        assert!(profile.args.is_empty());
+        assert!(profile.env.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_program() {
+        assert_eq!(resolve_program("/bin/sh"), Some(PathBuf::from("/bin/sh")));
+        assert!(resolve_program("sh").is_some());
+        assert!(resolve_program("definitely-not-a-real-binary-xyz").is_none());
+    }
+}
@@ -20,7 +20,7 @@ use crate::commands::mcp::McpCustomServer;
 /// 3. Development: relative to src-tauri/target/debug or release
 /// 4. macOS Application Support (~Library/Application Support/Claude Maestro/)
 /// 5. Linux local share (~/.local/share/maestro/)
-fn find_maestro_mcp_path() -> Option<PathBuf> {
+pub(crate) fn find_maestro_mcp_path() -> Option<PathBuf> {
     // Determine the binary name based on platform
     #[cfg(target_os = "windows")]
     let binary_name = "maestro-mcp-server.exe";
@@ -257,13 +257,19 @@ fn merge_with_existing(
 /// * `session_id` - Session identifier for the Maestro MCP server
 /// * `status_url` - HTTP URL for the status server endpoint
 /// * `instance_id` - UUID for this Maestro instance (prevents cross-instance pollution)
+/// * `status_token` - Per-session secret the MCP server must present to the status server
+/// * `maestro_http_url` - Endpoint of a shared Streamable HTTP MCP server; `None` to
+///   have the agent spawn its own stdio server
 /// * `enabled_servers` - List of discovered MCP server configs enabled for this session
 /// * `custom_servers` - List of custom MCP servers that are enabled
+#[allow(clippy::too_many_arguments)]
 pub async fn write_session_mcp_config(
     working_dir: &Path,
     session_id: u32,
     status_url: &str,
     instance_id: &str,
+    status_token: &str,
+    maestro_http_url: Option<&str>,
     enabled_servers: &[McpServerConfig],
     custom_servers: &[McpCustomServer],
 ) -> Result<(), String> {
@@ -273,7 +279,26 @@ pub async fn write_session_mcp_config(
     // Uses a SINGLE "maestro-status" entry with session ID in env vars (Swift pattern).
     // Each Claude instance spawns its own MCP server process with the env vars from when
     // it read the config. This avoids memory bloat from loading N servers per project.
-    if let Some(mcp_path) = find_maestro_mcp_path() {
+    // With a shared HTTP server, the session is named in request headers instead.
+    if let Some(url) = maestro_http_url {
+        log::info!(
+            "Adding maestro-status HTTP entry for session {} at {}",
+            session_id,
+            url
+        );
+        mcp_servers.insert(
+            "maestro-status".to_string(),
+            json!({
+                "type": "http",
+                "url": url,
+                "headers": {
+                    "X-Maestro-Session-Id": session_id.to_string(),
+                    "X-Maestro-Status-Token": status_token,
+                    "X-Maestro-Working-Dir": working_dir.to_string_lossy()
+                }
+            }),
+        );
+    } else if let Some(mcp_path) = find_maestro_mcp_path() {
         log::info!(
             "Found maestro-mcp-server at {:?}, adding single maestro-status entry for session {} with status_url={}",
             mcp_path,
@@ -291,7 +316,8 @@ pub async fn write_session_mcp_config(
                 "env": {
                     "MAESTRO_SESSION_ID": session_id.to_string(),
                     "MAESTRO_STATUS_URL": status_url,
-                    "MAESTRO_INSTANCE_ID": instance_id
+                    "MAESTRO_INSTANCE_ID": instance_id,
+                    "MAESTRO_STATUS_TOKEN": status_token
                 }
             }),
         );
@@ -438,6 +464,8 @@ mod tests {
             1,
             "http://127.0.0.1:9900/status",
             "test-instance-id",
+            "test-token",
+            None,
             &[],
             &[],
         )
@@ -447,6 +475,31 @@ mod tests {
         assert!(dir.path().join(".mcp.json").exists());
     }
 
+    #[tokio::test]
+    async fn test_write_session_mcp_config_http_entry() {
+        let dir = tempdir().unwrap();
+        write_session_mcp_config(
+            dir.path(),
+            3,
+            "http://127.0.0.1:9900/status",
+            "test-instance-id",
+            "test-token",
+            Some("http://127.0.0.1:9901/mcp"),
+            &[],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        let config: Value = serde_json::from_str(&content).unwrap();
+        let entry = &config["mcpServers"]["maestro-status"];
+        assert_eq!(entry["type"], "http");
+        assert_eq!(entry["url"], "http://127.0.0.1:9901/mcp");
+        assert_eq!(entry["headers"]["X-Maestro-Session-Id"], "3");
+        assert_eq!(entry["headers"]["X-Maestro-Status-Token"], "test-token");
+    }
+
     #[test]
     fn test_merge_preserves_user_servers_removes_all_maestro() {
         let dir = tempdir().unwrap();
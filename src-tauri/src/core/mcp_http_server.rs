@@ -0,0 +1,75 @@
+//! Shared `maestro-mcp-server` speaking MCP Streamable HTTP.
+//!
+//! By default every agent spawns its own stdio MCP server from the
+//! `.mcp.json` Maestro writes. With `MAESTRO_MCP_TRANSPORT=http`, Maestro
+//! instead starts one server process at launch and points each session's
+//! `"maestro-status"` entry at it, naming the session in request headers.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+
+use super::mcp_config_writer::find_maestro_mcp_path;
+use super::windows_process::TokioCommandExt;
+
+/// Environment variable that opts into the shared HTTP server.
+pub const TRANSPORT_ENV: &str = "MAESTRO_MCP_TRANSPORT";
+
+/// How long the server gets to print its endpoint URL.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A running shared MCP server. The process is killed when this is dropped.
+pub struct McpHttpServer {
+    url: String,
+    _child: Child,
+}
+
+impl McpHttpServer {
+    /// Whether the user asked for the shared HTTP server.
+    pub fn enabled() -> bool {
+        std::env::var(TRANSPORT_ENV).is_ok_and(|t| t.eq_ignore_ascii_case("http"))
+    }
+
+    /// Spawns `maestro-mcp-server --http` on a free loopback port and waits
+    /// for it to report its endpoint.
+    pub async fn start(status_url: &str, instance_id: &str) -> Result<Self, String> {
+        let binary = find_maestro_mcp_path()
+            .ok_or_else(|| "maestro-mcp-server binary not found".to_string())?;
+
+        let mut child = Command::new(&binary)
+            .args(["--http", "127.0.0.1:0"])
+            .env("MAESTRO_STATUS_URL", status_url)
+            .env("MAESTRO_INSTANCE_ID", instance_id)
+            .env_remove(TRANSPORT_ENV)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .hide_console_window()
+            .spawn()
+            .map_err(|e| format!("Failed to spawn {:?}: {}", binary, e))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| "maestro-mcp-server stdout not captured".to_string())?;
+        let mut lines = BufReader::new(stdout).lines();
+        let url = match tokio::time::timeout(STARTUP_TIMEOUT, lines.next_line()).await {
+            Ok(Ok(Some(line))) if line.starts_with("http://") => line.trim().to_string(),
+            Ok(Ok(other)) => {
+                return Err(format!("Unexpected maestro-mcp-server output: {:?}", other))
+            }
+            Ok(Err(e)) => return Err(format!("Failed to read maestro-mcp-server output: {}", e)),
+            Err(_) => return Err("Timed out waiting for maestro-mcp-server to start".to_string()),
+        };
+
+        Ok(Self { url, _child: child })
+    }
+
+    /// The `/mcp` endpoint sessions connect to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
@@ -1,32 +1,52 @@
 pub mod error;
 pub mod font_detector;
+pub mod launch_profile;
 pub mod marketplace_error;
 pub mod marketplace_manager;
 pub mod marketplace_models;
 pub mod mcp_config_writer;
+pub mod mcp_http_server;
 pub mod mcp_manager;
+pub mod notifications;
 pub mod plugin_config_writer;
 pub mod plugin_manager;
 pub mod process_manager;
 pub mod process_tree;
+pub mod pty_sink;
+pub mod recording;
+pub mod scrollback;
+pub mod session_journal;
+pub mod session_lifecycle;
 pub mod session_manager;
+pub mod status_detector;
+pub mod status_events;
+pub mod status_history;
 pub mod status_server;
+pub mod task_board;
 pub mod terminal_backend;
+pub mod webhooks;
 pub mod windows_process;
+pub mod worktree_bootstrap;
 pub mod worktree_manager;
 pub mod xterm_backend;
 
+#[cfg(feature = "vte-backend")]
+pub mod screen_grid;
 #[cfg(feature = "vte-backend")]
 pub mod vte_backend;
 
 pub use error::PtyError;
 pub use font_detector::{detect_available_fonts, is_font_available, AvailableFont};
 pub use marketplace_manager::MarketplaceManager;
+pub use mcp_http_server::McpHttpServer;
 pub use mcp_manager::McpManager;
 pub use plugin_manager::PluginManager;
 pub use process_manager::ProcessManager;
+pub use pty_sink::{PtyEventSink, PtyExit};
 pub use session_manager::SessionManager;
+pub use status_detector::StatusInference;
 pub use status_server::StatusServer;
+pub use task_board::TaskBoards;
 pub use terminal_backend::{
     BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
     TerminalError, TerminalState,
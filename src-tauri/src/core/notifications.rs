@@ -0,0 +1,413 @@
+//! Desktop notifications for agents that need attention.
+//!
+//! Every status transition, reported or inferred, reaches the `Notifier`
+//! through `status_events::publish`. It notifies when a session moves into
+//! one of the states its project cares about (by default `NeedsInput`,
+//! `Done` and `Error`), unless the user is already looking at that
+//! session's pane. Each project keeps its `NotificationRules` under the
+//! `notification_rules` key of its `maestro-<hash>.json` store.
+//!
+//! Delivery goes through a `NotificationSink`, so tests can record what
+//! would have been shown instead of popping up real notifications.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_store::StoreExt;
+
+use super::status_server::{SessionStatusPayload, StatusServer};
+
+/// Store key for a project's rules.
+const NOTIFICATION_RULES_KEY: &str = "notification_rules";
+
+/// A notification ready to be shown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Notification {
+    pub session_id: u32,
+    pub project_path: String,
+    pub title: String,
+    pub body: String,
+    pub sound: bool,
+}
+
+/// Shows notifications.
+pub trait NotificationSink: Send + Sync {
+    fn show(&self, notification: &Notification) -> Result<(), String>;
+}
+
+/// Shows notifications through the Tauri notification plugin.
+impl NotificationSink for AppHandle {
+    fn show(&self, notification: &Notification) -> Result<(), String> {
+        let mut builder = self
+            .notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.body);
+        if notification.sound {
+            builder = builder.sound("default");
+        }
+        builder.show().map_err(|e| e.to_string())
+    }
+}
+
+/// A daily window, in local time, during which nothing is shown. It may
+/// span midnight, e.g. 22:00 to 07:00.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Minutes after local midnight the window opens.
+    pub start_minute: u32,
+    /// Minutes after local midnight the window closes.
+    pub end_minute: u32,
+}
+
+impl QuietHours {
+    fn contains(&self, minute_of_day: u32) -> bool {
+        if self.start_minute <= self.end_minute {
+            (self.start_minute..self.end_minute).contains(&minute_of_day)
+        } else {
+            minute_of_day >= self.start_minute || minute_of_day < self.end_minute
+        }
+    }
+}
+
+/// When a project's sessions notify.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationRules {
+    pub enabled: bool,
+    /// Session statuses (`NeedsInput`, `Done`, `Error`, ...) that notify
+    /// when a session enters them.
+    pub states: Vec<String>,
+    pub quiet_hours: Option<QuietHours>,
+    /// A session entering the same status again within this many seconds
+    /// is not notified twice.
+    pub dedupe_window_secs: u64,
+    /// Play the system sound along with the notification.
+    pub sound: bool,
+}
+
+impl Default for NotificationRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            states: vec!["NeedsInput".into(), "Done".into(), "Error".into()],
+            quiet_hours: None,
+            dedupe_window_secs: 60,
+            sound: true,
+        }
+    }
+}
+
+/// Turns status transitions into notifications. Held in managed state.
+pub struct Notifier {
+    sink: Box<dyn NotificationSink>,
+    /// When each session last notified of each status, in milliseconds
+    /// since the epoch.
+    last_notified: Mutex<HashMap<u32, HashMap<String, u64>>>,
+    /// Session whose pane has keyboard focus in the UI.
+    focused_session: Mutex<Option<u32>>,
+}
+
+impl Notifier {
+    pub fn new(sink: Box<dyn NotificationSink>) -> Self {
+        Self {
+            sink,
+            last_notified: Mutex::new(HashMap::new()),
+            focused_session: Mutex::new(None),
+        }
+    }
+
+    /// Notifies about a session moving from `previous` to the status in
+    /// `payload`, under its project's rules, at the current time.
+    pub fn observe(&self, app: &AppHandle, payload: &SessionStatusPayload, previous: Option<&str>) {
+        let app_focused = app
+            .webview_windows()
+            .values()
+            .any(|window| window.is_focused().unwrap_or(false));
+        if self.is_watched(payload.session_id, app_focused) {
+            return;
+        }
+        let rules = load_rules(app, &payload.project_path);
+        let now = chrono::Local::now();
+        let minute_of_day = now.hour() * 60 + now.minute();
+        let now_millis = now.timestamp_millis().max(0) as u64;
+        self.handle(payload, previous, &rules, now_millis, minute_of_day);
+    }
+
+    /// Records which session's pane has focus, or `None` if none does.
+    pub fn set_focused_session(&self, session_id: Option<u32>) {
+        *self
+            .focused_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = session_id;
+    }
+
+    /// Whether the user is looking at the session's pane right now, so a
+    /// notification would only repeat what is on screen.
+    pub fn is_watched(&self, session_id: u32, app_focused: bool) -> bool {
+        app_focused
+            && *self
+                .focused_session
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                == Some(session_id)
+    }
+
+    /// Decides whether a session moving from `previous` to the status in
+    /// `payload` notifies, and shows it if so. Returns the notification
+    /// that was shown.
+    pub fn handle(
+        &self,
+        payload: &SessionStatusPayload,
+        previous: Option<&str>,
+        rules: &NotificationRules,
+        now_millis: u64,
+        minute_of_day: u32,
+    ) -> Option<Notification> {
+        let notification = {
+            // Only transitions notify, not repeated reports of the same status
+            if previous == Some(payload.status.as_str())
+                || !rules.enabled
+                || !rules.states.contains(&payload.status)
+                || rules
+                    .quiet_hours
+                    .as_ref()
+                    .is_some_and(|q| q.contains(minute_of_day))
+            {
+                return None;
+            }
+            let mut last_notified = self.last_notified.lock().unwrap_or_else(|e| e.into_inner());
+            let session = last_notified.entry(payload.session_id).or_default();
+            let window = rules.dedupe_window_secs.saturating_mul(1000);
+            if let Some(last) = session.get(&payload.status) {
+                if now_millis.saturating_sub(*last) < window {
+                    return None;
+                }
+            }
+            session.insert(payload.status.clone(), now_millis);
+            describe(payload, rules.sound)
+        };
+
+        if let Err(e) = self.sink.show(&notification) {
+            log::warn!(
+                "Failed to show notification for session {}: {}",
+                payload.session_id,
+                e
+            );
+        }
+        Some(notification)
+    }
+
+    /// Drops what is remembered about a session once it ends.
+    pub fn forget(&self, session_id: u32) {
+        self.last_notified
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&session_id);
+    }
+}
+
+fn describe(payload: &SessionStatusPayload, sound: bool) -> Notification {
+    let project = std::path::Path::new(&payload.project_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| payload.project_path.clone());
+    let title = match payload.status.as_str() {
+        "NeedsInput" => format!("Session {} needs input", payload.session_id),
+        "Done" => format!("Session {} finished", payload.session_id),
+        "Error" => format!("Session {} hit an error", payload.session_id),
+        other => format!("Session {} is {}", payload.session_id, other),
+    };
+    let detail = payload
+        .needs_input_prompt
+        .as_deref()
+        .filter(|prompt| !prompt.is_empty())
+        .unwrap_or(&payload.message);
+    let body = if detail.is_empty() {
+        project
+    } else {
+        format!("{}: {}", project, detail)
+    };
+    Notification {
+        session_id: payload.session_id,
+        project_path: payload.project_path.clone(),
+        title,
+        body,
+        sound,
+    }
+}
+
+/// A project's rules, or the defaults if it has none.
+pub fn load_rules(app: &AppHandle, project_path: &str) -> NotificationRules {
+    app.store(StatusServer::project_store_name(project_path))
+        .ok()
+        .and_then(|store| store.get(NOTIFICATION_RULES_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Saves a project's rules.
+pub fn save_rules(
+    app: &AppHandle,
+    project_path: &str,
+    rules: &NotificationRules,
+) -> Result<(), String> {
+    let store = app
+        .store(StatusServer::project_store_name(project_path))
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_value(rules).map_err(|e| e.to_string())?;
+    store.set(NOTIFICATION_RULES_KEY, json);
+    store.save().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Records notifications instead of showing them.
+    #[derive(Default)]
+    struct RecordingSink {
+        shown: Arc<Mutex<Vec<Notification>>>,
+    }
+
+    impl NotificationSink for RecordingSink {
+        fn show(&self, notification: &Notification) -> Result<(), String> {
+            self.shown.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    fn notifier() -> (Notifier, Arc<Mutex<Vec<Notification>>>) {
+        let sink = RecordingSink::default();
+        let shown = sink.shown.clone();
+        (Notifier::new(Box::new(sink)), shown)
+    }
+
+    fn payload(session_id: u32, status: &str) -> SessionStatusPayload {
+        SessionStatusPayload {
+            session_id,
+            project_path: "/work/maestro".to_string(),
+            status: status.to_string(),
+            message: "Waiting on you".to_string(),
+            needs_input_prompt: None,
+            awaiting_reply: false,
+        }
+    }
+
+    #[test]
+    fn test_notifies_on_transitions_into_watched_states() {
+        let (notifier, shown) = notifier();
+        let rules = NotificationRules::default();
+
+        assert!(notifier
+            .handle(&payload(1, "Working"), Some("Starting"), &rules, 0, 0)
+            .is_none());
+        let sent = notifier
+            .handle(&payload(1, "NeedsInput"), Some("Working"), &rules, 1_000, 0)
+            .unwrap();
+        assert_eq!(sent.title, "Session 1 needs input");
+        assert_eq!(sent.body, "maestro: Waiting on you");
+        // Repeated reports of the same status don't notify again
+        assert!(notifier
+            .handle(
+                &payload(1, "NeedsInput"),
+                Some("NeedsInput"),
+                &rules,
+                200_000,
+                0
+            )
+            .is_none());
+        assert!(notifier
+            .handle(&payload(2, "Done"), None, &rules, 2_000, 0)
+            .is_some());
+
+        assert_eq!(shown.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dedupe_window() {
+        let (notifier, shown) = notifier();
+        let rules = NotificationRules {
+            dedupe_window_secs: 60,
+            ..Default::default()
+        };
+
+        notifier.handle(&payload(1, "NeedsInput"), Some("Working"), &rules, 0, 0);
+        assert!(notifier
+            .handle(
+                &payload(1, "NeedsInput"),
+                Some("Working"),
+                &rules,
+                20_000,
+                0
+            )
+            .is_none());
+        assert!(notifier
+            .handle(
+                &payload(1, "NeedsInput"),
+                Some("Working"),
+                &rules,
+                61_000,
+                0
+            )
+            .is_some());
+
+        assert_eq!(shown.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rules_filter_states_and_quiet_hours() {
+        let (notifier, shown) = notifier();
+        let rules = NotificationRules {
+            states: vec!["Error".into()],
+            quiet_hours: Some(QuietHours {
+                start_minute: 22 * 60,
+                end_minute: 7 * 60,
+            }),
+            ..Default::default()
+        };
+
+        assert!(notifier
+            .handle(&payload(1, "Done"), None, &rules, 0, 12 * 60)
+            .is_none());
+        assert!(notifier
+            .handle(&payload(2, "Error"), None, &rules, 0, 23 * 60)
+            .is_none());
+        assert!(notifier
+            .handle(&payload(3, "Error"), None, &rules, 0, 6 * 60)
+            .is_none());
+        assert!(notifier
+            .handle(&payload(4, "Error"), None, &rules, 0, 12 * 60)
+            .is_some());
+
+        let disabled = NotificationRules {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(notifier
+            .handle(&payload(5, "Error"), None, &disabled, 0, 12 * 60)
+            .is_none());
+        assert_eq!(shown.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_forget_resets_session() {
+        let (notifier, _) = notifier();
+        let rules = NotificationRules {
+            dedupe_window_secs: 0,
+            ..Default::default()
+        };
+        assert!(notifier
+            .handle(&payload(1, "Done"), None, &rules, 0, 0)
+            .is_some());
+        notifier.forget(1);
+        assert!(notifier
+            .handle(&payload(1, "Done"), None, &rules, 1, 0)
+            .is_some());
+    }
+}
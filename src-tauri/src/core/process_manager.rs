@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 
 use dashmap::DashMap;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
-use tauri::{AppHandle, Emitter};
 use tokio::sync::Notify;
 use tokio::time::{self, Duration, MissedTickBehavior};
 
@@ -14,6 +14,9 @@ use tokio::time::{self, Duration, MissedTickBehavior};
 use libc;
 
 use super::error::PtyError;
+use super::pty_sink::{PtyEventSink, PtyExit};
+use super::recording::{self, Recorder};
+use super::scrollback::{ScrollbackBuffer, ScrollbackChunk, DEFAULT_SCROLLBACK_LIMIT};
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
 ///
@@ -77,6 +80,33 @@ impl Utf8Decoder {
     }
 }
 
+/// How long an exit status waits for the child's remaining output before it
+/// is reported anyway (a background process may hold the PTY open).
+const EXIT_OUTPUT_GRACE: Duration = Duration::from_millis(250);
+
+/// Reports a child's exit and releases its session.
+///
+/// `kill_session` removes the session before signaling, so a session that is
+/// still registered here exited on its own; it is removed, which closes the
+/// PTY and lets the reader thread finish. Exits caused by `kill_session` are
+/// reported with `killed` set.
+fn report_exit(inner: &Inner, sink: &dyn PtyEventSink, id: u32, mut status: PtyExit) {
+    let session = inner.sessions.remove(&id).map(|(_, session)| session);
+    status.killed = session.is_none();
+    if let Some(session) = &session {
+        log::info!(
+            "PTY session {id} (pid={}) exited: code={} signal={:?}",
+            session.child_pid,
+            status.exit_code,
+            status.signal
+        );
+    }
+    sink.exit(id, status);
+    // Dropping the session closes the master and writer; the reader thread
+    // sees EOF and exits on its own
+    drop(session);
+}
+
 /// A single PTY session with its associated resources.
 struct PtySession {
     /// Writer half of the PTY master — used for stdin.
@@ -94,11 +124,36 @@ struct PtySession {
     shutdown: Arc<Notify>,
     /// Handle to the dedicated reader OS thread.
     reader_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Recent output, shared with the event emitter task.
+    scrollback: Arc<Mutex<ScrollbackBuffer>>,
+    /// Active asciicast recording, if any, shared with the event emitter task.
+    recorder: Arc<Mutex<Option<Recorder>>>,
+}
+
+/// Runs `f` against a session's recorder, if it has one. A write failure
+/// stops the recording rather than failing the PTY operation.
+fn record(
+    recorder: &Mutex<Option<Recorder>>,
+    f: impl FnOnce(&mut Recorder) -> std::io::Result<()>,
+) {
+    let mut slot = recorder.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(active) = slot.as_mut() {
+        if let Err(e) = f(active) {
+            log::warn!(
+                "Stopping recording {}: write failed: {}",
+                active.path().display(),
+                e
+            );
+            *slot = None;
+        }
+    }
 }
 
 struct Inner {
     sessions: DashMap<u32, PtySession>,
     next_id: AtomicU32,
+    /// Byte limit for each new session's scrollback buffer.
+    scrollback_limit: AtomicUsize,
     /// Tracks last spawn time on Windows to prevent rapid consecutive spawns
     /// that may cause terminal spawning loops (Bug #76).
     #[cfg(windows)]
@@ -125,36 +180,111 @@ impl ProcessManager {
     /// Creates a new manager with no active sessions.
     /// Session IDs start at 1 and increment atomically.
     pub fn new() -> Self {
+        Self::with_scrollback_limit(DEFAULT_SCROLLBACK_LIMIT)
+    }
+
+    /// Creates a new manager whose sessions buffer up to `limit` bytes of
+    /// output each for `get_scrollback`.
+    pub fn with_scrollback_limit(limit: usize) -> Self {
         Self {
             inner: Arc::new(Inner {
                 sessions: DashMap::new(),
                 next_id: AtomicU32::new(1),
+                scrollback_limit: AtomicUsize::new(limit),
                 #[cfg(windows)]
                 last_spawn_time: Mutex::new(std::time::Instant::now()),
             }),
         }
     }
 
+    /// Byte limit applied to each session's scrollback buffer.
+    pub fn scrollback_limit(&self) -> usize {
+        self.inner.scrollback_limit.load(Ordering::Relaxed)
+    }
+
+    /// Changes the scrollback limit for new and running sessions. Running
+    /// sessions whose buffer is over the new limit drop their oldest output.
+    pub fn set_scrollback_limit(&self, limit: usize) {
+        self.inner.scrollback_limit.store(limit, Ordering::Relaxed);
+        for session in self.inner.sessions.iter() {
+            session
+                .scrollback
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .set_limit(limit);
+        }
+    }
+
     /// Spawns a login shell in a new PTY and returns its session ID.
     ///
     /// Uses `$SHELL` (falling back to `/bin/sh`) with `-l` for a login environment.
     /// The child process calls `setsid()` via portable-pty, making it a session
     /// leader so `kill_session` can signal the entire process group.
     /// A dedicated OS thread reads PTY output into a bounded 256-slot channel
-    /// (~1 MB of 4 KB chunks), and a tokio task drains it into Tauri events
-    /// named `pty-output-{id}`. If the channel fills, output is dropped and a
-    /// log message is emitted to make the loss visible.
+    /// (~1 MB of 4 KB chunks), and a tokio task drains it into `sink` in
+    /// batches (an `AppHandle` sink emits `pty-output-{id}` events). If the
+    /// channel fills, output is dropped and a log message is emitted to make
+    /// the loss visible. Every decoded chunk is also appended to the session's
+    /// scrollback buffer before it is emitted.
     ///
     /// # Environment Variables
     /// - `MAESTRO_SESSION_ID` is automatically set to the session ID
     /// - Additional env vars can be passed via the `env` parameter (e.g., `MAESTRO_PROJECT_HASH`)
     ///
+    /// # Exit Status
+    /// A dedicated waiter thread reaps the child and reports its exit status
+    /// through `sink.exit`, after the child's remaining output.
+    ///
     /// # Windows Debouncing
     /// On Windows, rapid consecutive spawn calls (within 500ms) are rejected to prevent
     /// terminal spawning loops (Bug #76).
     pub fn spawn_shell(
         &self,
-        app_handle: AppHandle,
+        sink: impl PtyEventSink + 'static,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+    ) -> Result<u32, PtyError> {
+        // Determine the user's shell (platform-specific)
+        #[cfg(unix)]
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        #[cfg(windows)]
+        let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+
+        let mut cmd = CommandBuilder::new(&shell);
+        #[cfg(unix)]
+        cmd.arg("-l"); // Login shell for proper env on Unix
+
+        self.spawn_in_pty(sink, cmd, &shell, cwd, env)
+    }
+
+    /// Spawns `program` with `args` directly as the PTY's child, without a
+    /// shell in between, and returns its session ID.
+    ///
+    /// Behaves like `spawn_shell` otherwise: same environment handling,
+    /// output delivery and exit reporting. The exit status reported through
+    /// `sink.exit` is the program's own, which a shell would have hidden.
+    /// `program` should be an absolute path (see `launch_profile::resolve_program`).
+    pub fn spawn_command(
+        &self,
+        sink: impl PtyEventSink + 'static,
+        program: &str,
+        args: &[String],
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+    ) -> Result<u32, PtyError> {
+        let mut cmd = CommandBuilder::new(program);
+        cmd.args(args);
+        self.spawn_in_pty(sink, cmd, program, cwd, env)
+    }
+
+    /// Shared spawn path: opens the PTY, starts `cmd` in it and wires up the
+    /// reader thread, emitter task and child waiter. `label` names the
+    /// program in log messages.
+    fn spawn_in_pty(
+        &self,
+        sink: impl PtyEventSink + 'static,
+        mut cmd: CommandBuilder,
+        label: &str,
         cwd: Option<String>,
         env: Option<HashMap<String, String>>,
     ) -> Result<u32, PtyError> {
@@ -198,16 +328,6 @@ impl ProcessManager {
             })
             .map_err(|e| PtyError::spawn_failed(format!("Failed to open PTY: {e}")))?;
 
-        // Determine the user's shell (platform-specific)
-        #[cfg(unix)]
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
-        #[cfg(windows)]
-        let shell = std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
-
-        let mut cmd = CommandBuilder::new(&shell);
-        #[cfg(unix)]
-        cmd.arg("-l"); // Login shell for proper env on Unix
-
         // Set TERM for proper terminal emulation on Unix.
         // xterm-256color is the standard for modern terminal emulators and enables:
         // - Proper cursor positioning and line editing
@@ -235,10 +355,10 @@ impl ProcessManager {
             cmd.cwd(dir);
         }
 
-        let child = pair
+        let mut child = pair
             .slave
             .spawn_command(cmd)
-            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn shell: {e}")))?;
+            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn {label}: {e}")))?;
 
         let child_pid = child
             .process_id()
@@ -268,7 +388,7 @@ impl ProcessManager {
 
         // Dedicated OS thread for reading PTY output.
         // Sends data through a bounded mpsc channel (~1 MB of 4 KB chunks) to a
-        // tokio task that emits Tauri events.
+        // tokio task that forwards it to the event sink.
         let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
 
         // Shutdown mechanism: dropping the master/writer FDs closes the PTY
@@ -311,15 +431,34 @@ impl ProcessManager {
             })
             .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn reader thread: {e}")))?;
 
-        // Tokio task: drain the channel and emit Tauri events
-        let event_name = format!("pty-output-{id}");
-        let app = app_handle.clone();
+        let scrollback = Arc::new(Mutex::new(ScrollbackBuffer::new(
+            self.inner.scrollback_limit.load(Ordering::Relaxed),
+        )));
+        let scrollback_clone = scrollback.clone();
+        let recorder: Arc<Mutex<Option<Recorder>>> = Arc::new(Mutex::new(None));
+        let recorder_clone = recorder.clone();
+
+        // The waiter thread hands the exit status to the emitter task so it is
+        // reported after any output still pending
+        let (exit_tx, mut exit_rx) = tokio::sync::oneshot::channel::<PtyExit>();
+
+        // Tokio task: drain the channel and forward batches to the sink
+        let sink: Arc<dyn PtyEventSink> = Arc::new(sink);
+        let emitter_sink = sink.clone();
         let inner_ref = self.inner.clone();
         tokio::spawn(async move {
+            let sink = emitter_sink;
+            // Exit status received from the waiter but not yet reported, and
+            // when to give up waiting for the rest of the output
+            let mut exited = false;
+            let mut pending_exit: Option<(PtyExit, time::Instant)> = None;
+            let mut killed = false;
             let mut decoder = Utf8Decoder::new();
             // Batch PTY output to reduce the number of cross-process IPC events.
             // This helps Tauri's WebKit webview stay responsive when output is bursty.
             let mut pending = String::new();
+            // Scrollback offset just past the last byte in `pending`
+            let mut pending_end = 0u64;
             let mut ticker = time::interval(Duration::from_millis(16));
             ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
             // First tick is immediate; advance once so the next tick is delayed.
@@ -364,11 +503,16 @@ impl ProcessManager {
 
                                 let text = decoder.decode(&bytes);
                                 if !text.is_empty() {
+                                    if let Ok(mut buf) = scrollback_clone.lock() {
+                                        buf.push(&text);
+                                        pending_end = buf.end_offset();
+                                    }
+                                    record(&recorder_clone, |r| r.output(&text));
                                     pending.push_str(&text);
                                     // Flush early if we have a lot buffered.
                                     if pending.len() >= 16 * 1024 {
                                         let chunk = std::mem::take(&mut pending);
-                                        let _ = app.emit(&event_name, chunk);
+                                        sink.output(id, chunk, pending_end);
                                     }
                                 }
                             }
@@ -378,21 +522,65 @@ impl ProcessManager {
                     _ = ticker.tick() => {
                         if !pending.is_empty() {
                             let chunk = std::mem::take(&mut pending);
-                            let _ = app.emit(&event_name, chunk);
+                            sink.output(id, chunk, pending_end);
+                        }
+                        if pending_exit.as_ref().is_some_and(|(_, at)| time::Instant::now() >= *at) {
+                            if let Some((status, _)) = pending_exit.take() {
+                                report_exit(&inner_ref, sink.as_ref(), id, status);
+                            }
+                        }
+                    }
+                    status = &mut exit_rx, if !exited => {
+                        // The child's last output may still be in flight; hold
+                        // the status until EOF or a short grace period
+                        exited = true;
+                        if let Ok(status) = status {
+                            pending_exit = Some((status, time::Instant::now() + EXIT_OUTPUT_GRACE));
                         }
                     }
                     _ = shutdown_clone.notified() => {
+                        killed = true;
                         break;
                     }
                 }
             }
             if !pending.is_empty() {
                 let chunk = std::mem::take(&mut pending);
-                let _ = app.emit(&event_name, chunk);
+                sink.output(id, chunk, pending_end);
+            }
+            // EOF usually beats the waiter thread; give it a moment to report
+            if !exited && !killed {
+                if let Ok(Ok(status)) = time::timeout(EXIT_OUTPUT_GRACE, &mut exit_rx).await {
+                    pending_exit = Some((status, time::Instant::now()));
+                }
+            }
+            if let Some((status, _)) = pending_exit {
+                report_exit(&inner_ref, sink.as_ref(), id, status);
             }
             log::debug!("PTY event emitter {id} exited");
         });
 
+        // Dedicated OS thread that blocks until the child exits. Reaping it here
+        // also keeps exited children from lingering as zombies.
+        let waiter_inner = self.inner.clone();
+        std::thread::Builder::new()
+            .name(format!("pty-wait-{id}"))
+            .spawn(move || {
+                let status = match child.wait() {
+                    Ok(status) => PtyExit::from(status),
+                    Err(e) => {
+                        log::warn!("Failed to wait for PTY session {id}: {e}");
+                        return;
+                    }
+                };
+                log::debug!("PTY session {id} child exited: {:?}", status);
+                // If the emitter task is already gone, report directly
+                if let Err(status) = exit_tx.send(status) {
+                    report_exit(&waiter_inner, sink.as_ref(), id, status);
+                }
+            })
+            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn waiter thread: {e}")))?;
+
         // Drop the slave — the master keeps the PTY alive
         drop(pair.slave);
 
@@ -404,13 +592,15 @@ impl ProcessManager {
             pgid,
             shutdown,
             reader_handle: Mutex::new(Some(reader_handle)),
+            scrollback,
+            recorder,
         };
 
         self.inner.sessions.insert(id, session);
         #[cfg(unix)]
-        log::info!("Spawned PTY session {id} (pid={child_pid}, pgid={pgid}, shell={shell})");
+        log::info!("Spawned PTY session {id} (pid={child_pid}, pgid={pgid}, program={label})");
         #[cfg(windows)]
-        log::info!("Spawned PTY session {id} (pid={child_pid}, shell={shell})");
+        log::info!("Spawned PTY session {id} (pid={child_pid}, program={label})");
 
         Ok(id)
     }
@@ -438,10 +628,37 @@ impl ProcessManager {
         writer
             .flush()
             .map_err(|e| PtyError::write_failed(format!("Flush failed: {e}")))?;
+        drop(writer);
 
+        record(&session.recorder, |r| r.input(data));
         Ok(())
     }
 
+    /// Returns buffered output for a session starting at `since_offset`.
+    ///
+    /// Offsets are monotonic byte positions in the session's output stream;
+    /// pass the returned `end_offset` back to read only newer output. If the
+    /// requested range was already evicted, the chunk is marked `truncated`.
+    pub fn get_scrollback(
+        &self,
+        session_id: u32,
+        since_offset: u64,
+    ) -> Result<ScrollbackChunk, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        // The buffer is plain data, so a poisoned lock is still safe to read
+        let buf = session
+            .scrollback
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        Ok(buf.read_since(since_offset))
+    }
+
     /// Resizes the PTY to the given dimensions, propagating SIGWINCH to the child.
     ///
     /// Pixel dimensions are always set to 0 (unused by terminal emulators).
@@ -466,10 +683,76 @@ impl ProcessManager {
                 pixel_height: 0,
             })
             .map_err(|e| PtyError::resize_failed(format!("Resize failed: {e}")))?;
+        drop(master);
 
+        record(&session.recorder, |r| r.resize(cols, rows));
         Ok(())
     }
 
+    /// Starts recording a session to a new asciicast v2 file in `dir`.
+    ///
+    /// Output, `write_stdin` input and resizes are appended until
+    /// `stop_recording` is called or the session ends. Returns the path of
+    /// the new file. Fails if the session is already being recorded.
+    pub fn start_recording(&self, session_id: u32, dir: &Path) -> Result<PathBuf, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let size = session
+            .master
+            .lock()
+            .map_err(|e| PtyError::recording_failed(format!("Master lock poisoned: {e}")))?
+            .get_size()
+            .map_err(|e| PtyError::recording_failed(format!("Failed to read PTY size: {e}")))?;
+
+        let mut slot = session.recorder.lock().unwrap_or_else(|e| e.into_inner());
+        if slot.is_some() {
+            return Err(PtyError::recording_failed(format!(
+                "Session {session_id} is already being recorded"
+            )));
+        }
+
+        std::fs::create_dir_all(dir).map_err(|e| {
+            PtyError::recording_failed(format!("Failed to create {}: {e}", dir.display()))
+        })?;
+        let path = recording::new_recording_path(dir, session_id);
+        let header = recording::header_for(session_id, size.cols, size.rows);
+        let recorder = Recorder::create(&path, &header).map_err(|e| {
+            PtyError::recording_failed(format!("Failed to create {}: {e}", path.display()))
+        })?;
+
+        *slot = Some(recorder);
+        log::info!("Recording session {session_id} to {}", path.display());
+        Ok(path)
+    }
+
+    /// Stops recording a session. Returns the finished file's path, or `None`
+    /// if the session was not being recorded.
+    pub fn stop_recording(&self, session_id: u32) -> Result<Option<PathBuf>, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let recorder = session
+            .recorder
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        Ok(recorder.map(|r| r.path().to_path_buf()))
+    }
+
+    /// Returns the file a session is currently being recorded to, if any.
+    pub fn recording_path(&self, session_id: u32) -> Option<PathBuf> {
+        let session = self.inner.sessions.get(&session_id)?;
+        let slot = session.recorder.lock().unwrap_or_else(|e| e.into_inner());
+        slot.as_ref().map(|r| r.path().to_path_buf())
+    }
+
     /// Terminates a PTY session with graceful escalation.
     ///
     /// On Unix: Sends SIGTERM to the entire process group (via negative PGID),
@@ -615,3 +898,67 @@ impl ProcessManager {
         Ok(count)
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Collects output and exit statuses in arrival order.
+    #[derive(Clone, Default)]
+    struct EventLog(Arc<Mutex<Vec<String>>>);
+
+    impl PtyEventSink for EventLog {
+        fn output(&self, _session_id: u32, data: String, _end_offset: u64) {
+            self.0.lock().unwrap().push(format!("output:{}", data.trim()));
+        }
+
+        fn exit(&self, _session_id: u32, status: PtyExit) {
+            let killed = if status.killed { ":killed" } else { "" };
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("exit:{}{}", status.exit_code, killed));
+        }
+    }
+
+    impl EventLog {
+        async fn wait_for_exit(&self) -> Vec<String> {
+            let deadline = std::time::Instant::now() + Duration::from_secs(10);
+            while !self.0.lock().unwrap().iter().any(|e| e.starts_with("exit:")) {
+                assert!(std::time::Instant::now() < deadline, "no exit event");
+                time::sleep(Duration::from_millis(20)).await;
+            }
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spawn_command_reports_exit_after_output() {
+        let pm = ProcessManager::new();
+        let log = EventLog::default();
+        let args = vec!["-c".to_string(), "echo done; exit 3".to_string()];
+        let id = pm
+            .spawn_command(log.clone(), "/bin/sh", &args, None, None)
+            .unwrap();
+
+        let events = log.wait_for_exit().await;
+        assert_eq!(events.last().map(String::as_str), Some("exit:3"));
+        assert!(events.iter().any(|e| e.contains("done")));
+        // The exited session is released without a kill_session call
+        assert!(pm.get_session_pid(id).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_killed_session_exit_is_marked() {
+        let pm = ProcessManager::new();
+        let log = EventLog::default();
+        let args = vec!["-c".to_string(), "sleep 30".to_string()];
+        let id = pm
+            .spawn_command(log.clone(), "/bin/sh", &args, None, None)
+            .unwrap();
+
+        pm.kill_session(id).await.unwrap();
+        let events = log.wait_for_exit().await;
+        assert!(events.last().unwrap().ends_with(":killed"));
+    }
+}
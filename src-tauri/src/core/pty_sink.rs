@@ -0,0 +1,92 @@
+//! Destination for PTY output produced by `ProcessManager`.
+//!
+//! Inside the app, output goes straight to the webview as Tauri events. The
+//! headless daemon has no webview, so it forwards output to whichever socket
+//! clients are attached instead. Abstracting the destination behind a trait
+//! keeps `ProcessManager` free of any Tauri dependency.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use super::{session_lifecycle, status_detector};
+
+/// Receives batched output from a PTY session's emitter task.
+pub trait PtyEventSink: Send + Sync {
+    /// Called with each batch of decoded output.
+    ///
+    /// `end_offset` is the scrollback stream offset just past `data`, so the
+    /// batch covers `end_offset - data.len()..end_offset`. Consumers that
+    /// combine live output with a `get_scrollback` read use it to drop
+    /// overlapping bytes.
+    fn output(&self, session_id: u32, data: String, end_offset: u64);
+
+    /// Called instead of `output` for a batch of a session that no pane is
+    /// showing, e.g. a daemon session whose pane is unmounted. Consumers
+    /// keep tracking it but need not display it.
+    fn hidden_output(&self, _session_id: u32, _data: &str) {}
+
+    /// Called once when the session's child process exits, after the output
+    /// that was pending at that moment.
+    fn exit(&self, _session_id: u32, _status: PtyExit) {}
+}
+
+/// Exit status of a session's child process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyExit {
+    /// Exit code; 1 when the process was killed by a signal.
+    pub exit_code: u32,
+    /// Name of the terminating signal, if any (Unix only).
+    pub signal: Option<String>,
+    pub success: bool,
+    /// Whether the exit was caused by `kill_session` rather than the child
+    /// exiting on its own.
+    pub killed: bool,
+}
+
+impl From<portable_pty::ExitStatus> for PtyExit {
+    fn from(status: portable_pty::ExitStatus) -> Self {
+        Self {
+            exit_code: status.exit_code(),
+            signal: status.signal().map(str::to_string),
+            success: status.success(),
+            killed: false,
+        }
+    }
+}
+
+/// Payload of a `pty-output-{id}` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyOutput {
+    pub data: String,
+    /// Scrollback stream offset just past `data`; see `PtyEventSink::output`.
+    pub end_offset: u64,
+}
+
+/// Emits output to the webview as `pty-output-{id}` events and runs status
+/// inference on it, shown or not. Exit statuses go out as `pty-exit-{id}`; a
+/// child that exited on its own also has its session finalized.
+impl PtyEventSink for AppHandle {
+    fn output(&self, session_id: u32, data: String, end_offset: u64) {
+        status_detector::observe_output(self, session_id, &data);
+        let _ = self.emit(
+            &format!("pty-output-{session_id}"),
+            PtyOutput { data, end_offset },
+        );
+    }
+
+    fn hidden_output(&self, session_id: u32, data: &str) {
+        status_detector::observe_output(self, session_id, data);
+    }
+
+    fn exit(&self, session_id: u32, status: PtyExit) {
+        let _ = self.emit(&format!("pty-exit-{session_id}"), &status);
+        if !status.killed {
+            let app = self.clone();
+            tauri::async_runtime::spawn(async move {
+                session_lifecycle::on_child_exit(&app, session_id, &status).await;
+            });
+        }
+    }
+}
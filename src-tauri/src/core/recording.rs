@@ -0,0 +1,550 @@
+//! Session recordings in asciicast v2 format.
+//!
+//! A recording is a `.cast` file: one JSON header line followed by one
+//! `[time, code, data]` line per event, where `time` is seconds since the
+//! recording started and `code` is `"o"` (output), `"i"` (input written via
+//! `write_stdin`) or `"r"` (resize, data `"{cols}x{rows}"`). The format is
+//! the one asciinema uses, so recordings also play in standard tools.
+//!
+//! Files are named `session-{id}-{unix_ms}-{suffix}.cast`, with a random
+//! suffix so recordings started in the same millisecond (e.g. by the app
+//! and the session daemon) never share a file; the file stem is the
+//! recording ID used by the commands.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+/// Subdirectory of the app data dir that holds recordings.
+pub const RECORDINGS_DIR: &str = "recordings";
+
+/// Longest pause between events during replay, in recorded seconds. Keeps a
+/// session that sat idle for an hour from replaying as an hour of nothing.
+const MAX_REPLAY_IDLE: f64 = 3.0;
+
+/// Bounds for replay speed multipliers.
+const MIN_REPLAY_SPEED: f64 = 0.1;
+const MAX_REPLAY_SPEED: f64 = 100.0;
+
+/// First line of a `.cast` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastHeader {
+    pub version: u32,
+    pub width: u16,
+    pub height: u16,
+    /// Unix time in seconds when recording started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// A single recorded event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastEvent {
+    /// Seconds since the recording started.
+    pub time: f64,
+    /// `"o"`, `"i"` or `"r"`.
+    pub code: String,
+    pub data: String,
+}
+
+/// Summary of a recording file, as listed to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    pub id: String,
+    pub path: String,
+    /// Session the recording was made from, parsed from the file name.
+    pub session_id: Option<u32>,
+    /// Unix time in seconds when recording started.
+    pub started_at: Option<u64>,
+    pub width: u16,
+    pub height: u16,
+    pub title: Option<String>,
+    pub size_bytes: u64,
+}
+
+/// A full recording: its summary plus every event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    pub info: RecordingInfo,
+    /// Time of the last event, in seconds.
+    pub duration: f64,
+    pub events: Vec<CastEvent>,
+}
+
+/// Appends events for one session to a `.cast` file.
+///
+/// Each event is one line and the writer is line-buffered, so a crash loses
+/// at most the event being written.
+pub struct Recorder {
+    writer: LineWriter<File>,
+    started: Instant,
+    path: PathBuf,
+}
+
+impl Recorder {
+    /// Creates `path`, which must not exist yet, and writes the header.
+    pub fn create(path: &Path, header: &CastHeader) -> io::Result<Self> {
+        let mut writer = LineWriter::new(File::create_new(path)?);
+        let line = serde_json::to_string(header)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(writer, "{line}")?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records PTY output.
+    pub fn output(&mut self, data: &str) -> io::Result<()> {
+        self.event("o", data)
+    }
+
+    /// Records text written to the PTY's stdin.
+    pub fn input(&mut self, data: &str) -> io::Result<()> {
+        self.event("i", data)
+    }
+
+    /// Records a terminal resize.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.event("r", &format!("{cols}x{rows}"))
+    }
+
+    fn event(&mut self, code: &str, data: &str) -> io::Result<()> {
+        // Microsecond precision, like asciinema
+        let time = (self.started.elapsed().as_secs_f64() * 1e6).round() / 1e6;
+        let line = serde_json::to_string(&(time, code, data))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        writeln!(self.writer, "{line}")
+    }
+}
+
+/// Returns the path for a new recording of `session_id` in `dir`.
+pub fn new_recording_path(dir: &Path, session_id: u32) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let suffix = &uuid::Uuid::new_v4().simple().to_string()[..8];
+    dir.join(format!("session-{session_id}-{millis}-{suffix}.cast"))
+}
+
+/// Builds the header for a recording that starts now.
+pub fn header_for(session_id: u32, cols: u16, rows: u16) -> CastHeader {
+    let mut env = HashMap::new();
+    for key in ["SHELL", "TERM"] {
+        if let Ok(value) = std::env::var(key) {
+            env.insert(key.to_string(), value);
+        }
+    }
+    // Sessions always run with this TERM, whatever the app's own is
+    #[cfg(unix)]
+    env.insert("TERM".to_string(), "xterm-256color".to_string());
+
+    CastHeader {
+        version: 2,
+        width: cols,
+        height: rows,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs()),
+        title: Some(format!("Session {session_id}")),
+        env: Some(env),
+    }
+}
+
+/// Rejects IDs that could escape the recordings directory.
+fn recording_path(dir: &Path, id: &str) -> io::Result<PathBuf> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid recording ID '{id}'"),
+        ));
+    }
+    Ok(dir.join(format!("{id}.cast")))
+}
+
+/// Parses the session ID out of a `session-{id}-{unix_ms}[-{suffix}]`
+/// file stem.
+fn session_id_from_stem(stem: &str) -> Option<u32> {
+    stem.strip_prefix("session-")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn read_info(path: &Path) -> io::Result<RecordingInfo> {
+    let id = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut first_line = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut first_line)?;
+    let header: CastHeader = serde_json::from_str(&first_line)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    Ok(RecordingInfo {
+        session_id: session_id_from_stem(&id),
+        id,
+        path: path.to_string_lossy().into_owned(),
+        started_at: header.timestamp,
+        width: header.width,
+        height: header.height,
+        title: header.title,
+        size_bytes: std::fs::metadata(path)?.len(),
+    })
+}
+
+/// Lists recordings in `dir`, newest first. A missing directory means no
+/// recordings; files with an unreadable header are skipped.
+pub fn list_recordings(dir: &Path) -> io::Result<Vec<RecordingInfo>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut recordings: Vec<RecordingInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cast"))
+        .filter_map(|path| match read_info(&path) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                log::warn!("Skipping recording {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    recordings.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    Ok(recordings)
+}
+
+/// Reads a recording and all of its events.
+///
+/// A truncated final line (from a crash mid-write) is ignored.
+pub fn read_recording(dir: &Path, id: &str) -> io::Result<Recording> {
+    let path = recording_path(dir, id)?;
+    let info = read_info(&path)?;
+
+    let mut events = Vec::new();
+    for line in BufReader::new(File::open(&path)?).lines().skip(1) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<(f64, String, String)>(&line) {
+            Ok((time, code, data)) => events.push(CastEvent { time, code, data }),
+            Err(e) => log::debug!("Skipping malformed event in {}: {}", id, e),
+        }
+    }
+
+    Ok(Recording {
+        info,
+        duration: events.last().map_or(0.0, |e| e.time),
+        events,
+    })
+}
+
+/// An event delivered to a replay pane.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReplayEvent {
+    Output { data: String },
+    Resize { cols: u16, rows: u16 },
+    Finished,
+}
+
+struct ReplayControl {
+    /// Current speed multiplier, stored as `f64` bits.
+    speed: Arc<AtomicU64>,
+    cancel: Arc<Notify>,
+}
+
+/// Plays recordings back in real time (scaled by a speed multiplier).
+///
+/// Each replay runs as a tokio task that hands events to a callback, so the
+/// caller decides where they go (the app emits them to a read-only pane).
+/// Input events are skipped: the shell's echo is already in the output.
+#[derive(Default)]
+pub struct ReplayManager {
+    replays: Arc<DashMap<u32, ReplayControl>>,
+    next_id: AtomicU32,
+}
+
+fn clamp_speed(speed: f64) -> f64 {
+    if speed.is_finite() {
+        speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED)
+    } else {
+        1.0
+    }
+}
+
+/// Parses resize data of the form `"{cols}x{rows}"`.
+fn parse_resize(data: &str) -> Option<(u16, u16)> {
+    let (cols, rows) = data.split_once('x')?;
+    Some((cols.parse().ok()?, rows.parse().ok()?))
+}
+
+impl ReplayManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts replaying `events` and returns the replay ID. `emit` is called
+    /// for each output and resize event, then once with `Finished` (also
+    /// when stopped early).
+    pub fn start(
+        &self,
+        events: Vec<CastEvent>,
+        speed: f64,
+        emit: impl Fn(ReplayEvent) + Send + 'static,
+    ) -> u32 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let speed = Arc::new(AtomicU64::new(clamp_speed(speed).to_bits()));
+        let cancel = Arc::new(Notify::new());
+        self.replays.insert(
+            id,
+            ReplayControl {
+                speed: speed.clone(),
+                cancel: cancel.clone(),
+            },
+        );
+
+        let replays = self.replays.clone();
+        tokio::spawn(async move {
+            let mut previous = 0.0;
+            for event in events {
+                let replay_event = match event.code.as_str() {
+                    "o" => ReplayEvent::Output { data: event.data },
+                    "r" => match parse_resize(&event.data) {
+                        Some((cols, rows)) => ReplayEvent::Resize { cols, rows },
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+
+                let gap = (event.time - previous).clamp(0.0, MAX_REPLAY_IDLE);
+                previous = event.time;
+                let delay = gap / f64::from_bits(speed.load(Ordering::Relaxed));
+                if delay > 0.0 {
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs_f64(delay)) => {}
+                        _ = cancel.notified() => break,
+                    }
+                }
+                emit(replay_event);
+            }
+            replays.remove(&id);
+            emit(ReplayEvent::Finished);
+        });
+        id
+    }
+
+    /// Changes a running replay's speed, effective from the next event.
+    /// Returns `false` if the replay already finished.
+    pub fn set_speed(&self, id: u32, speed: f64) -> bool {
+        match self.replays.get(&id) {
+            Some(control) => {
+                control
+                    .speed
+                    .store(clamp_speed(speed).to_bits(), Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops a running replay. Returns `false` if it already finished.
+    pub fn stop(&self, id: u32) -> bool {
+        match self.replays.get(&id) {
+            Some(control) => {
+                control.cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_sample(dir: &Path, session_id: u32) -> String {
+        let path = new_recording_path(dir, session_id);
+        let mut recorder = Recorder::create(&path, &header_for(session_id, 80, 24)).unwrap();
+        recorder.output("$ ").unwrap();
+        recorder.input("ls\r").unwrap();
+        recorder.output("ls\r\nfile.txt \"quoted\"\r\n").unwrap();
+        recorder.resize(120, 40).unwrap();
+        path.file_stem().unwrap().to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_recording_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = write_sample(dir.path(), 7);
+
+        let recording = read_recording(dir.path(), &id).unwrap();
+        assert_eq!(recording.info.session_id, Some(7));
+        assert_eq!(recording.info.width, 80);
+        assert_eq!(recording.info.height, 24);
+        let codes: Vec<&str> = recording.events.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(codes, ["o", "i", "o", "r"]);
+        assert_eq!(recording.events[2].data, "ls\r\nfile.txt \"quoted\"\r\n");
+        assert_eq!(recording.events[3].data, "120x40");
+        assert!(recording.events.windows(2).all(|w| w[0].time <= w[1].time));
+    }
+
+    #[test]
+    fn test_file_is_asciicast_v2() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = write_sample(dir.path(), 1);
+        let text = std::fs::read_to_string(dir.path().join(format!("{id}.cast"))).unwrap();
+        let mut lines = text.lines();
+
+        let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 80);
+        let event: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert!(event[0].is_f64());
+        assert_eq!(event[1], "o");
+        assert_eq!(event[2], "$ ");
+    }
+
+    #[test]
+    fn test_list_recordings() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_recordings(&dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        write_sample(dir.path(), 1);
+        write_sample(dir.path(), 2);
+        std::fs::write(dir.path().join("broken.cast"), "not json\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let recordings = list_recordings(dir.path()).unwrap();
+        assert_eq!(recordings.len(), 2);
+        assert!(recordings.iter().all(|r| r.size_bytes > 0));
+    }
+
+    #[test]
+    fn test_recordings_of_one_session_get_distinct_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = write_sample(dir.path(), 3);
+        let second = write_sample(dir.path(), 3);
+        assert_ne!(first, second);
+        assert_eq!(list_recordings(dir.path()).unwrap().len(), 2);
+
+        // An existing recording is never overwritten
+        let existing = dir.path().join(format!("{first}.cast"));
+        let err = Recorder::create(&existing, &header_for(3, 80, 24)).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(read_recording(dir.path(), &first).unwrap().events.len(), 4);
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = read_recording(dir.path(), "../secrets").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn test_replay_delivers_events_in_order() {
+        let events = vec![
+            CastEvent {
+                time: 0.0,
+                code: "o".into(),
+                data: "a".into(),
+            },
+            CastEvent {
+                time: 0.01,
+                code: "i".into(),
+                data: "x".into(),
+            },
+            CastEvent {
+                time: 0.02,
+                code: "r".into(),
+                data: "100x30".into(),
+            },
+            CastEvent {
+                time: 0.03,
+                code: "o".into(),
+                data: "b".into(),
+            },
+        ];
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = ReplayManager::new();
+        manager.start(events, 10.0, move |event| {
+            let _ = tx.send(event);
+        });
+
+        let mut received = Vec::new();
+        while let Some(event) = rx.recv().await {
+            received.push(format!("{event:?}"));
+        }
+        assert_eq!(
+            received,
+            [
+                "Output { data: \"a\" }",
+                "Resize { cols: 100, rows: 30 }",
+                "Output { data: \"b\" }",
+                "Finished",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_can_be_stopped() {
+        let events = vec![
+            CastEvent {
+                time: 0.0,
+                code: "o".into(),
+                data: "a".into(),
+            },
+            CastEvent {
+                time: 2.0,
+                code: "o".into(),
+                data: "b".into(),
+            },
+        ];
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = ReplayManager::new();
+        let id = manager.start(events, 1.0, move |event| {
+            let _ = tx.send(event);
+        });
+
+        assert!(matches!(rx.recv().await, Some(ReplayEvent::Output { .. })));
+        assert!(manager.set_speed(id, 0.5));
+        assert!(manager.stop(id));
+        assert!(matches!(rx.recv().await, Some(ReplayEvent::Finished)));
+        assert!(!manager.stop(id));
+    }
+}
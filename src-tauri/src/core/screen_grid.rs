@@ -0,0 +1,869 @@
+//! Screen grid emulation for the VTE backend.
+//!
+//! `ScreenGrid` implements `vte::Perform` and models what a VT100/xterm-style
+//! terminal displays: a grid of cells with SGR attributes, the alternate
+//! screen, scroll regions, erase/insert/delete operations and wide (CJK,
+//! emoji) characters. It lets Rust code read an agent's screen without going
+//! through xterm.js.
+//!
+//! Rendering still happens in xterm.js, so the model only needs to be faithful
+//! enough to answer "what text is on screen and how is it styled". Text is
+//! not reflowed on resize, combining characters are dropped, and charset
+//! designations (`ESC ( 0`) are ignored.
+
+use std::collections::VecDeque;
+
+use unicode_width::UnicodeWidthChar;
+use vte::{Params, Perform};
+
+use super::terminal_backend::{
+    Cell, CellAttributes, CellColor, CursorShape, ScreenSnapshot, TerminalState,
+};
+
+/// Maximum lines kept after scrolling off the top of the primary screen.
+const MAX_SCROLLBACK_LINES: usize = 10_000;
+
+type Row = Vec<Cell>;
+
+/// Cursor state saved by DECSC (`ESC 7`) and restored by DECRC (`ESC 8`).
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    row: u16,
+    col: u16,
+    attrs: CellAttributes,
+    pending_wrap: bool,
+}
+
+/// In-memory model of a terminal screen.
+pub struct ScreenGrid {
+    rows: u16,
+    cols: u16,
+    /// Rows of the active screen (primary or alternate).
+    grid: Vec<Row>,
+    /// The primary screen, stashed while the alternate screen is active.
+    primary: Option<Vec<Row>>,
+    /// Lines scrolled off the top of the primary screen, oldest first.
+    scrollback: VecDeque<Row>,
+    cursor_row: u16,
+    cursor_col: u16,
+    /// Set after printing into the last column. The next printable character
+    /// wraps to a new line first (the DEC "last column flag").
+    pending_wrap: bool,
+    /// Attributes applied to newly printed characters.
+    attrs: CellAttributes,
+    /// Scroll region, inclusive, set by DECSTBM.
+    scroll_top: u16,
+    scroll_bottom: u16,
+    saved_cursor: Option<SavedCursor>,
+    autowrap: bool,
+    cursor_visible: bool,
+    cursor_shape: CursorShape,
+    title: Option<String>,
+}
+
+fn blank_row(cols: u16) -> Row {
+    vec![Cell::default(); cols as usize]
+}
+
+/// Returns parameter `idx` (its first sub-parameter), treating 0 and missing
+/// values as `default` like most CSI sequences do.
+fn param(params: &Params, idx: usize, default: u16) -> u16 {
+    params
+        .iter()
+        .nth(idx)
+        .and_then(|p| p.first().copied())
+        .filter(|&v| v != 0)
+        .unwrap_or(default)
+}
+
+/// Parses an extended color (`38;5;n`, `38;2;r;g;b` or the colon forms).
+///
+/// `params[0]` is the 38/48 parameter itself. Returns the color and how many
+/// additional semicolon-separated parameters it consumed.
+fn extended_color(params: &[&[u16]]) -> (Option<CellColor>, usize) {
+    let first = params[0];
+    let byte = |v: u16| v.min(255) as u8;
+
+    // Colon form: everything is in sub-parameters of one parameter
+    if first.len() > 1 {
+        let color = match first[1] {
+            5 => first.get(2).map(|&n| CellColor::Indexed(byte(n))),
+            // `38:2:r:g:b` or `38:2:<colorspace>:r:g:b`
+            2 if first.len() >= 6 => Some(CellColor::Rgb(
+                byte(first[3]),
+                byte(first[4]),
+                byte(first[5]),
+            )),
+            2 if first.len() == 5 => Some(CellColor::Rgb(
+                byte(first[2]),
+                byte(first[3]),
+                byte(first[4]),
+            )),
+            _ => None,
+        };
+        return (color, 0);
+    }
+
+    let value = |i: usize| params.get(i).and_then(|p| p.first().copied());
+    match value(1) {
+        Some(5) => (value(2).map(|n| CellColor::Indexed(byte(n))), 2),
+        Some(2) => match (value(2), value(3), value(4)) {
+            (Some(r), Some(g), Some(b)) => (Some(CellColor::Rgb(byte(r), byte(g), byte(b))), 4),
+            _ => (None, params.len() - 1),
+        },
+        _ => (None, 0),
+    }
+}
+
+impl ScreenGrid {
+    /// Creates a blank screen. Dimensions are clamped to at least 1x1.
+    pub fn new(rows: u16, cols: u16) -> Self {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        Self {
+            rows,
+            cols,
+            grid: (0..rows).map(|_| blank_row(cols)).collect(),
+            primary: None,
+            scrollback: VecDeque::new(),
+            cursor_row: 0,
+            cursor_col: 0,
+            pending_wrap: false,
+            attrs: CellAttributes::default(),
+            scroll_top: 0,
+            scroll_bottom: rows - 1,
+            saved_cursor: None,
+            autowrap: true,
+            cursor_visible: true,
+            cursor_shape: CursorShape::Block,
+            title: None,
+        }
+    }
+
+    /// Returns a copy of the visible screen.
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        ScreenSnapshot {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.grid.clone(),
+            alternate_screen: self.primary.is_some(),
+        }
+    }
+
+    /// Returns cursor, title and screen state for `TerminalBackend::get_state`.
+    pub fn state(&self) -> TerminalState {
+        TerminalState {
+            cursor_row: self.cursor_row,
+            cursor_col: self.cursor_col,
+            cursor_shape: self.cursor_shape,
+            cursor_visible: self.cursor_visible,
+            scrollback_position: 0,
+            scrollback_total: self.scrollback.len() as u32,
+            title: self.title.clone(),
+            screen: Some(self.snapshot()),
+        }
+    }
+
+    /// Resizes the screen without reflowing text.
+    ///
+    /// When shrinking vertically, rows above the cursor are scrolled off the
+    /// top (into scrollback on the primary screen) so the cursor line stays
+    /// visible. The scroll region resets to the full screen.
+    pub fn resize(&mut self, rows: u16, cols: u16) {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+
+        if rows < self.rows {
+            let overflow = (self.cursor_row + 1).saturating_sub(rows);
+            for _ in 0..overflow {
+                let line = self.grid.remove(0);
+                self.push_scrollback(line);
+            }
+            self.cursor_row -= overflow;
+            self.grid.truncate(rows as usize);
+        }
+        self.grid.resize_with(rows as usize, || blank_row(cols));
+        for row in &mut self.grid {
+            Self::resize_row(row, cols);
+        }
+
+        if let Some(primary) = self.primary.as_mut() {
+            primary.truncate(rows as usize);
+            primary.resize_with(rows as usize, || blank_row(cols));
+            for row in primary.iter_mut() {
+                Self::resize_row(row, cols);
+            }
+        }
+
+        self.rows = rows;
+        self.cols = cols;
+        self.scroll_top = 0;
+        self.scroll_bottom = rows - 1;
+        self.cursor_row = self.cursor_row.min(rows - 1);
+        self.cursor_col = self.cursor_col.min(cols - 1);
+        self.pending_wrap = false;
+    }
+
+    fn resize_row(row: &mut Row, cols: u16) {
+        row.resize(cols as usize, Cell::default());
+        // Don't leave the first half of a wide character without its spacer
+        if let Some(last) = row.last_mut() {
+            if last.width == 2 {
+                *last = Cell::default();
+            }
+        }
+    }
+
+    fn push_scrollback(&mut self, row: Row) {
+        // Only the primary screen has scrollback
+        if self.primary.is_some() {
+            return;
+        }
+        if self.scrollback.len() == MAX_SCROLLBACK_LINES {
+            self.scrollback.pop_front();
+        }
+        self.scrollback.push_back(row);
+    }
+
+    /// A blank cell carrying the current background (erase uses BCE).
+    fn blank(&self) -> Cell {
+        Cell {
+            attrs: CellAttributes {
+                bg: self.attrs.bg,
+                ..CellAttributes::default()
+            },
+            ..Cell::default()
+        }
+    }
+
+    /// Blanks `start..end` of a row, also clearing the other half of any wide
+    /// character cut by either edge.
+    fn erase_cells(&mut self, row: u16, start: u16, end: u16) {
+        let blank = self.blank();
+        let cols = self.cols;
+        let line = &mut self.grid[row as usize];
+        let start = start.min(cols) as usize;
+        let end = end.min(cols) as usize;
+        if start >= end {
+            return;
+        }
+        if start > 0 && line[start].width == 0 {
+            line[start - 1] = blank;
+        }
+        if end < line.len() && line[end].width == 0 {
+            line[end] = blank;
+        }
+        for cell in &mut line[start..end] {
+            *cell = blank;
+        }
+    }
+
+    fn print_char(&mut self, c: char) {
+        // Zero-width and combining characters are dropped
+        let width = match c.width() {
+            Some(w) if w > 0 => w.min(2) as u16,
+            _ => return,
+        };
+        if width > self.cols {
+            return;
+        }
+
+        if self.pending_wrap {
+            self.cursor_col = 0;
+            self.linefeed();
+            self.pending_wrap = false;
+        }
+
+        // A wide character that doesn't fit in the last column wraps early
+        if self.cursor_col + width > self.cols {
+            if self.autowrap {
+                self.erase_cells(self.cursor_row, self.cursor_col, self.cols);
+                self.cursor_col = 0;
+                self.linefeed();
+            } else {
+                self.cursor_col = self.cols - width;
+            }
+        }
+
+        let (row, col) = (self.cursor_row, self.cursor_col);
+        self.erase_cells(row, col, col + width);
+        let line = &mut self.grid[row as usize];
+        line[col as usize] = Cell {
+            c,
+            width: width as u8,
+            attrs: self.attrs,
+        };
+        if width == 2 {
+            line[col as usize + 1] = Cell {
+                c: ' ',
+                width: 0,
+                attrs: self.attrs,
+            };
+        }
+
+        let next = col + width;
+        if next >= self.cols {
+            self.cursor_col = self.cols - 1;
+            self.pending_wrap = self.autowrap;
+        } else {
+            self.cursor_col = next;
+        }
+    }
+
+    /// Moves down one line, scrolling the region if the cursor is on its
+    /// bottom margin.
+    fn linefeed(&mut self) {
+        self.pending_wrap = false;
+        if self.cursor_row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor_row < self.rows - 1 {
+            self.cursor_row += 1;
+        }
+    }
+
+    /// Moves up one line, scrolling the region down if the cursor is on its
+    /// top margin.
+    fn reverse_index(&mut self) {
+        self.pending_wrap = false;
+        if self.cursor_row == self.scroll_top {
+            self.scroll_down(1);
+        } else if self.cursor_row > 0 {
+            self.cursor_row -= 1;
+        }
+    }
+
+    /// Shifts rows `top..=scroll_bottom` up by `n`, filling the bottom with
+    /// blank rows. Returns the rows that fell off the top.
+    fn rotate_up(&mut self, top: u16, n: u16) -> Vec<Row> {
+        let top = top as usize;
+        let bottom = self.scroll_bottom as usize;
+        let n = (n as usize).min(bottom + 1 - top);
+        let blank = vec![self.blank(); self.cols as usize];
+        let removed: Vec<Row> = self.grid.drain(top..top + n).collect();
+        for _ in 0..n {
+            self.grid.insert(bottom + 1 - n, blank.clone());
+        }
+        removed
+    }
+
+    /// Shifts rows `top..=scroll_bottom` down by `n`, filling the top with
+    /// blank rows.
+    fn rotate_down(&mut self, top: u16, n: u16) {
+        let top = top as usize;
+        let bottom = self.scroll_bottom as usize;
+        let n = (n as usize).min(bottom + 1 - top);
+        let blank = vec![self.blank(); self.cols as usize];
+        self.grid.drain(bottom + 1 - n..=bottom);
+        for _ in 0..n {
+            self.grid.insert(top, blank.clone());
+        }
+    }
+
+    /// Scrolls the scroll region up by `n` lines. Lines leaving the top of
+    /// the screen go to scrollback.
+    fn scroll_up(&mut self, n: u16) {
+        let removed = self.rotate_up(self.scroll_top, n);
+        if self.scroll_top == 0 {
+            for row in removed {
+                self.push_scrollback(row);
+            }
+        }
+    }
+
+    /// Scrolls the scroll region down by `n` lines.
+    fn scroll_down(&mut self, n: u16) {
+        self.rotate_down(self.scroll_top, n);
+    }
+
+    /// IL / DL: inserts or deletes lines at the cursor within the scroll
+    /// region. No-op when the cursor is outside the region.
+    fn shift_lines(&mut self, n: u16, insert: bool) {
+        if self.cursor_row < self.scroll_top || self.cursor_row > self.scroll_bottom {
+            return;
+        }
+        if insert {
+            self.rotate_down(self.cursor_row, n);
+        } else {
+            self.rotate_up(self.cursor_row, n);
+        }
+        self.cursor_col = 0;
+        self.pending_wrap = false;
+    }
+
+    /// ICH / DCH: inserts or deletes blank cells at the cursor, shifting the
+    /// rest of the line.
+    fn shift_cells(&mut self, n: u16, insert: bool) {
+        let row = self.cursor_row as usize;
+        let col = self.cursor_col as usize;
+        let n = (n as usize).min(self.cols as usize - col);
+        // Don't split a wide character straddling the cursor
+        if col > 0 && self.grid[row][col].width == 0 {
+            self.erase_cells(self.cursor_row, self.cursor_col - 1, self.cursor_col + 1);
+        }
+
+        let blank = self.blank();
+        let line = &mut self.grid[row];
+        if insert {
+            line.truncate(line.len() - n);
+            line.splice(col..col, std::iter::repeat_n(blank, n));
+        } else {
+            line.drain(col..col + n);
+            line.extend(std::iter::repeat_n(blank, n));
+        }
+        Self::resize_row(line, self.cols);
+        if line[col].width == 0 {
+            line[col] = blank;
+        }
+        self.pending_wrap = false;
+    }
+
+    fn erase_in_display(&mut self, mode: u16) {
+        let (row, col) = (self.cursor_row, self.cursor_col);
+        match mode {
+            // Cursor to end of screen
+            0 => {
+                self.erase_cells(row, col, self.cols);
+                for r in row + 1..self.rows {
+                    self.erase_cells(r, 0, self.cols);
+                }
+            }
+            // Start of screen to cursor
+            1 => {
+                for r in 0..row {
+                    self.erase_cells(r, 0, self.cols);
+                }
+                self.erase_cells(row, 0, col + 1);
+            }
+            // Whole screen
+            2 => {
+                for r in 0..self.rows {
+                    self.erase_cells(r, 0, self.cols);
+                }
+            }
+            // Scrollback only
+            3 => self.scrollback.clear(),
+            _ => {}
+        }
+    }
+
+    fn erase_in_line(&mut self, mode: u16) {
+        let (row, col) = (self.cursor_row, self.cursor_col);
+        match mode {
+            0 => self.erase_cells(row, col, self.cols),
+            1 => self.erase_cells(row, 0, col + 1),
+            2 => self.erase_cells(row, 0, self.cols),
+            _ => {}
+        }
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some(SavedCursor {
+            row: self.cursor_row,
+            col: self.cursor_col,
+            attrs: self.attrs,
+            pending_wrap: self.pending_wrap,
+        });
+    }
+
+    fn restore_cursor(&mut self) {
+        let saved = self.saved_cursor.unwrap_or(SavedCursor {
+            row: 0,
+            col: 0,
+            attrs: CellAttributes::default(),
+            pending_wrap: false,
+        });
+        self.cursor_row = saved.row.min(self.rows - 1);
+        self.cursor_col = saved.col.min(self.cols - 1);
+        self.attrs = saved.attrs;
+        self.pending_wrap = saved.pending_wrap;
+    }
+
+    fn enter_alternate_screen(&mut self) {
+        if self.primary.is_none() {
+            let alternate = (0..self.rows).map(|_| blank_row(self.cols)).collect();
+            self.primary = Some(std::mem::replace(&mut self.grid, alternate));
+        }
+    }
+
+    fn leave_alternate_screen(&mut self) {
+        if let Some(primary) = self.primary.take() {
+            self.grid = primary;
+        }
+    }
+
+    fn set_private_mode(&mut self, mode: u16, enabled: bool) {
+        match mode {
+            7 => self.autowrap = enabled,
+            25 => self.cursor_visible = enabled,
+            47 | 1047 => {
+                if enabled {
+                    self.enter_alternate_screen();
+                } else {
+                    self.leave_alternate_screen();
+                }
+            }
+            1049 => {
+                if enabled {
+                    self.save_cursor();
+                    self.enter_alternate_screen();
+                } else {
+                    self.leave_alternate_screen();
+                    self.restore_cursor();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select_graphic_rendition(&mut self, params: &Params) {
+        let params: Vec<&[u16]> = params.iter().collect();
+        if params.is_empty() {
+            self.attrs = CellAttributes::default();
+            return;
+        }
+
+        let mut i = 0;
+        while i < params.len() {
+            let p = params[i];
+            match p[0] {
+                0 => self.attrs = CellAttributes::default(),
+                1 => self.attrs.bold = true,
+                2 => self.attrs.dim = true,
+                3 => self.attrs.italic = true,
+                // `4:0` turns underline off; other styles (`4:3` curly) are still underline
+                4 => self.attrs.underline = p.get(1).copied() != Some(0),
+                5 | 6 => self.attrs.blink = true,
+                7 => self.attrs.inverse = true,
+                8 => self.attrs.hidden = true,
+                9 => self.attrs.strikethrough = true,
+                21 => self.attrs.underline = true,
+                22 => {
+                    self.attrs.bold = false;
+                    self.attrs.dim = false;
+                }
+                23 => self.attrs.italic = false,
+                24 => self.attrs.underline = false,
+                25 => self.attrs.blink = false,
+                27 => self.attrs.inverse = false,
+                28 => self.attrs.hidden = false,
+                29 => self.attrs.strikethrough = false,
+                n @ 30..=37 => self.attrs.fg = CellColor::Indexed((n - 30) as u8),
+                n @ 40..=47 => self.attrs.bg = CellColor::Indexed((n - 40) as u8),
+                n @ 90..=97 => self.attrs.fg = CellColor::Indexed((n - 90 + 8) as u8),
+                n @ 100..=107 => self.attrs.bg = CellColor::Indexed((n - 100 + 8) as u8),
+                39 => self.attrs.fg = CellColor::Default,
+                49 => self.attrs.bg = CellColor::Default,
+                n @ (38 | 48) => {
+                    let (color, consumed) = extended_color(&params[i..]);
+                    if let Some(color) = color {
+                        if n == 38 {
+                            self.attrs.fg = color;
+                        } else {
+                            self.attrs.bg = color;
+                        }
+                    }
+                    i += consumed;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+impl Perform for ScreenGrid {
+    fn print(&mut self, c: char) {
+        self.print_char(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            // Backspace
+            0x08 => {
+                self.cursor_col = self.cursor_col.saturating_sub(1);
+                self.pending_wrap = false;
+            }
+            // Horizontal tab: next multiple of 8
+            0x09 => {
+                self.cursor_col = ((self.cursor_col / 8 + 1) * 8).min(self.cols - 1);
+                self.pending_wrap = false;
+            }
+            // Line feed, vertical tab, form feed
+            0x0A..=0x0C => self.linefeed(),
+            // Carriage return
+            0x0D => {
+                self.cursor_col = 0;
+                self.pending_wrap = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        // OSC 0, 1, 2 - Set window/icon title
+        if let Some(&[b'0' | b'1' | b'2']) = params.first() {
+            if let Some(title) = params.get(1).and_then(|t| std::str::from_utf8(t).ok()) {
+                self.title = Some(title.to_string());
+            }
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if ignore {
+            return;
+        }
+        let private = intermediates.first() == Some(&b'?');
+
+        match (action, intermediates) {
+            // CUU / CUD stop at the scroll margins when starting inside them
+            ('A', _) => {
+                let top = if self.cursor_row >= self.scroll_top {
+                    self.scroll_top
+                } else {
+                    0
+                };
+                self.cursor_row = self.cursor_row.saturating_sub(param(params, 0, 1)).max(top);
+                self.pending_wrap = false;
+            }
+            ('B', _) => {
+                let bottom = if self.cursor_row <= self.scroll_bottom {
+                    self.scroll_bottom
+                } else {
+                    self.rows - 1
+                };
+                self.cursor_row = self
+                    .cursor_row
+                    .saturating_add(param(params, 0, 1))
+                    .min(bottom);
+                self.pending_wrap = false;
+            }
+            // CUF / CUB
+            ('C', _) => {
+                self.cursor_col = self
+                    .cursor_col
+                    .saturating_add(param(params, 0, 1))
+                    .min(self.cols - 1);
+                self.pending_wrap = false;
+            }
+            ('D', _) => {
+                self.cursor_col = self.cursor_col.saturating_sub(param(params, 0, 1));
+                self.pending_wrap = false;
+            }
+            // CNL / CPL
+            ('E', _) => {
+                self.cursor_row = self
+                    .cursor_row
+                    .saturating_add(param(params, 0, 1))
+                    .min(self.rows - 1);
+                self.cursor_col = 0;
+                self.pending_wrap = false;
+            }
+            ('F', _) => {
+                self.cursor_row = self.cursor_row.saturating_sub(param(params, 0, 1));
+                self.cursor_col = 0;
+                self.pending_wrap = false;
+            }
+            // CHA / HPA
+            ('G' | '`', _) => {
+                self.cursor_col = (param(params, 0, 1) - 1).min(self.cols - 1);
+                self.pending_wrap = false;
+            }
+            // VPA
+            ('d', _) => {
+                self.cursor_row = (param(params, 0, 1) - 1).min(self.rows - 1);
+                self.pending_wrap = false;
+            }
+            // CUP / HVP
+            ('H' | 'f', _) => {
+                self.cursor_row = (param(params, 0, 1) - 1).min(self.rows - 1);
+                self.cursor_col = (param(params, 1, 1) - 1).min(self.cols - 1);
+                self.pending_wrap = false;
+            }
+            // ED / EL (and their selective `?` variants)
+            ('J', _) => self.erase_in_display(params.iter().next().map_or(0, |p| p[0])),
+            ('K', _) => self.erase_in_line(params.iter().next().map_or(0, |p| p[0])),
+            // IL / DL
+            ('L', []) => self.shift_lines(param(params, 0, 1), true),
+            ('M', []) => self.shift_lines(param(params, 0, 1), false),
+            // ICH / DCH
+            ('@', []) => self.shift_cells(param(params, 0, 1), true),
+            ('P', []) => self.shift_cells(param(params, 0, 1), false),
+            // ECH
+            ('X', []) => {
+                let n = param(params, 0, 1);
+                self.erase_cells(
+                    self.cursor_row,
+                    self.cursor_col,
+                    self.cursor_col.saturating_add(n),
+                );
+            }
+            // SU / SD
+            ('S', []) => self.scroll_up(param(params, 0, 1)),
+            ('T', []) => self.scroll_down(param(params, 0, 1)),
+            // DECSTBM - set scroll region, then home the cursor
+            ('r', []) => {
+                let top = param(params, 0, 1) - 1;
+                let bottom = param(params, 1, self.rows).min(self.rows) - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.cursor_row = 0;
+                    self.cursor_col = 0;
+                    self.pending_wrap = false;
+                }
+            }
+            // SGR
+            ('m', []) => self.select_graphic_rendition(params),
+            // DECSET / DECRST
+            ('h' | 'l', _) if private => {
+                for p in params.iter() {
+                    self.set_private_mode(p[0], action == 'h');
+                }
+            }
+            // SCOSC / SCORC
+            ('s', []) => self.save_cursor(),
+            ('u', []) => self.restore_cursor(),
+            // DECSCUSR - Set Cursor Shape
+            ('q', [b' ']) => {
+                self.cursor_shape = match param(params, 0, 0) {
+                    3 | 4 => CursorShape::Underline,
+                    5 | 6 => CursorShape::Bar,
+                    _ => CursorShape::Block,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        if ignore || !intermediates.is_empty() {
+            return;
+        }
+        match byte {
+            // DECSC / DECRC
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            // IND
+            b'D' => self.linefeed(),
+            // NEL
+            b'E' => {
+                self.cursor_col = 0;
+                self.linefeed();
+            }
+            // RI
+            b'M' => self.reverse_index(),
+            // RIS - full reset, keeping scrollback
+            b'c' => {
+                let scrollback = std::mem::take(&mut self.scrollback);
+                *self = Self::new(self.rows, self.cols);
+                self.scrollback = scrollback;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vte::Parser;
+
+    fn grid_with(rows: u16, cols: u16, input: &str) -> ScreenGrid {
+        let mut grid = ScreenGrid::new(rows, cols);
+        Parser::new().advance(&mut grid, input.as_bytes());
+        grid
+    }
+
+    #[test]
+    fn test_print_wraps_at_last_column() {
+        let grid = grid_with(3, 5, "abcdefg");
+        assert_eq!(grid.snapshot().lines(), vec!["abcde", "fg", ""]);
+        assert_eq!((grid.cursor_row, grid.cursor_col), (1, 2));
+    }
+
+    #[test]
+    fn test_linefeed_at_bottom_scrolls_into_scrollback() {
+        let grid = grid_with(2, 10, "one\r\ntwo\r\nthree");
+        assert_eq!(grid.snapshot().lines(), vec!["two", "three"]);
+        assert_eq!(grid.state().scrollback_total, 1);
+    }
+
+    #[test]
+    fn test_erase_and_cursor_positioning() {
+        let grid = grid_with(3, 10, "hello\r\nworld\x1b[1;3H\x1b[K\x1b[2;1H\x1b[1P");
+        assert_eq!(grid.snapshot().lines(), vec!["he", "orld", ""]);
+
+        let grid = grid_with(3, 10, "abc\r\ndef\x1b[2J");
+        assert_eq!(grid.snapshot().text(), "");
+    }
+
+    #[test]
+    fn test_sgr_attributes() {
+        let grid = grid_with(1, 10, "\x1b[1;31mA\x1b[38;5;200;48;2;1;2;3mB\x1b[0mC");
+        let row = &grid.snapshot().cells[0];
+        assert!(row[0].attrs.bold);
+        assert_eq!(row[0].attrs.fg, CellColor::Indexed(1));
+        assert_eq!(row[1].attrs.fg, CellColor::Indexed(200));
+        assert_eq!(row[1].attrs.bg, CellColor::Rgb(1, 2, 3));
+        assert_eq!(row[2].attrs, CellAttributes::default());
+    }
+
+    #[test]
+    fn test_alternate_screen_restores_primary() {
+        let mut grid = grid_with(3, 10, "shell$ ");
+        Parser::new().advance(&mut grid, b"\x1b[?1049h\x1b[Hfullscreen");
+        let snapshot = grid.snapshot();
+        assert!(snapshot.alternate_screen);
+        assert_eq!(snapshot.lines()[0], "fullscreen");
+
+        Parser::new().advance(&mut grid, b"\x1b[?1049l");
+        let snapshot = grid.snapshot();
+        assert!(!snapshot.alternate_screen);
+        assert_eq!(snapshot.lines()[0], "shell$");
+        assert_eq!((grid.cursor_row, grid.cursor_col), (0, 7));
+    }
+
+    #[test]
+    fn test_scroll_region_keeps_lines_outside_it() {
+        // Region is rows 2-3; a linefeed on row 3 scrolls only that region
+        let grid = grid_with(4, 10, "header\x1b[4;1Hfooter\x1b[2;3r\x1b[2;1Ha\r\nb\r\nc");
+        assert_eq!(grid.snapshot().lines(), vec!["header", "b", "c", "footer"]);
+        // Lines scrolled out of a partial region never reach scrollback
+        assert_eq!(grid.state().scrollback_total, 0);
+    }
+
+    #[test]
+    fn test_wide_characters_take_two_cells() {
+        let grid = grid_with(2, 5, "a漢b");
+        let row = &grid.snapshot().cells[0];
+        assert_eq!((row[1].c, row[1].width), ('漢', 2));
+        assert_eq!(row[2].width, 0);
+        assert_eq!(grid.snapshot().lines()[0], "a漢b");
+
+        // A wide char that doesn't fit in the last column wraps
+        let grid = grid_with(2, 3, "ab漢");
+        assert_eq!(grid.snapshot().lines(), vec!["ab", "漢"]);
+
+        // Overwriting half of a wide char clears the other half
+        let grid = grid_with(1, 5, "漢\x1b[1;2Hx");
+        assert_eq!(grid.snapshot().lines()[0], " x");
+    }
+
+    #[test]
+    fn test_resize_keeps_cursor_line_visible() {
+        let mut grid = grid_with(4, 10, "1\r\n2\r\n3\r\n4");
+        grid.resize(2, 4);
+        assert_eq!(grid.snapshot().lines(), vec!["3", "4"]);
+        assert_eq!(grid.cursor_row, 1);
+        assert_eq!(grid.state().scrollback_total, 2);
+    }
+
+    #[test]
+    fn test_title_and_cursor_modes() {
+        let grid = grid_with(1, 10, "\x1b]0;agent\x07\x1b[?25l\x1b[5 q");
+        let state = grid.state();
+        assert_eq!(state.title.as_deref(), Some("agent"));
+        assert!(!state.cursor_visible);
+        assert_eq!(state.cursor_shape, CursorShape::Bar);
+    }
+}
@@ -0,0 +1,196 @@
+//! Bounded per-session output buffer for reattaching to a PTY.
+//!
+//! `pty-output-{id}` events are fire-and-forget: if the webview reloads or a
+//! pane is remounted, earlier output is gone. The process manager appends
+//! every decoded chunk to a `ScrollbackBuffer` before emitting it, so the
+//! frontend can fetch what it missed.
+//!
+//! Offsets are monotonic byte positions in the session's UTF-8 output stream,
+//! starting at 0 when the session spawns. They never reset when old data is
+//! evicted, so a client that remembers the `end_offset` of its last read can
+//! resume with no gaps or duplicates.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Default scrollback limit per session (1 MiB).
+pub const DEFAULT_SCROLLBACK_LIMIT: usize = 1024 * 1024;
+
+/// Smallest and largest limits accepted from the user.
+pub const MIN_SCROLLBACK_LIMIT: usize = 64 * 1024;
+pub const MAX_SCROLLBACK_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Store file and key holding the user's scrollback limit.
+pub const SCROLLBACK_STORE: &str = "terminal-settings.json";
+pub const SCROLLBACK_LIMIT_KEY: &str = "scrollback_limit";
+
+/// A slice of buffered output returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollbackChunk {
+    /// Output between `start_offset` and `end_offset`.
+    pub data: String,
+    /// Stream offset of the first byte in `data`.
+    pub start_offset: u64,
+    /// Stream offset just past the last byte in `data`. Pass this as
+    /// `since_offset` on the next read to continue where this one stopped.
+    pub end_offset: u64,
+    /// `true` if output before `start_offset` that the caller asked for has
+    /// already been evicted from the buffer.
+    pub truncated: bool,
+}
+
+/// Ring buffer of UTF-8 output capped at a byte limit.
+///
+/// Eviction always drops whole characters from the front, so the buffer
+/// contents remain valid UTF-8.
+#[derive(Debug)]
+pub struct ScrollbackBuffer {
+    data: VecDeque<u8>,
+    /// Stream offset of `data[0]`.
+    start_offset: u64,
+    limit: usize,
+}
+
+/// Returns true for UTF-8 continuation bytes (`10xxxxxx`).
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+impl ScrollbackBuffer {
+    /// Creates an empty buffer holding at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            start_offset: 0,
+            limit,
+        }
+    }
+
+    /// Stream offset just past the newest buffered byte.
+    pub fn end_offset(&self) -> u64 {
+        self.start_offset + self.data.len() as u64
+    }
+
+    /// Appends output, evicting the oldest characters if over the limit.
+    pub fn push(&mut self, text: &str) {
+        self.data.extend(text.as_bytes());
+        self.evict();
+    }
+
+    /// Changes the byte limit, evicting the oldest output if it shrank.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        if self.data.len() <= self.limit {
+            return;
+        }
+
+        let mut excess = self.data.len() - self.limit;
+        // Never split a character: extend the cut to the next char boundary
+        while excess < self.data.len() && is_continuation(self.data[excess]) {
+            excess += 1;
+        }
+        self.data.drain(..excess);
+        self.start_offset += excess as u64;
+    }
+
+    /// Returns everything buffered from `since_offset` onward.
+    ///
+    /// If `since_offset` is older than the buffer, the read starts at the
+    /// oldest buffered byte and `truncated` is set. If it is past the end
+    /// (e.g. a stale offset from another session), an empty chunk at the
+    /// current end is returned.
+    pub fn read_since(&self, since_offset: u64) -> ScrollbackChunk {
+        let end_offset = self.end_offset();
+        if since_offset >= end_offset {
+            return ScrollbackChunk {
+                data: String::new(),
+                start_offset: end_offset,
+                end_offset,
+                truncated: false,
+            };
+        }
+
+        let truncated = since_offset < self.start_offset;
+        let mut skip = since_offset.saturating_sub(self.start_offset) as usize;
+        // Offsets handed out are always char boundaries, but be defensive
+        while skip < self.data.len() && is_continuation(self.data[skip]) {
+            skip += 1;
+        }
+
+        let bytes: Vec<u8> = self.data.range(skip..).copied().collect();
+        ScrollbackChunk {
+            data: String::from_utf8_lossy(&bytes).into_owned(),
+            start_offset: self.start_offset + skip as u64,
+            end_offset,
+            truncated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_since_returns_only_new_output() {
+        let mut buf = ScrollbackBuffer::new(1024);
+        buf.push("hello ");
+        let first = buf.read_since(0);
+        assert_eq!(first.data, "hello ");
+        assert_eq!(first.end_offset, 6);
+
+        buf.push("world");
+        let second = buf.read_since(first.end_offset);
+        assert_eq!(second.data, "world");
+        assert_eq!(second.start_offset, 6);
+        assert_eq!(second.end_offset, 11);
+        assert!(!second.truncated);
+    }
+
+    #[test]
+    fn test_eviction_keeps_offsets_monotonic() {
+        let mut buf = ScrollbackBuffer::new(4);
+        buf.push("abcdef");
+        let chunk = buf.read_since(0);
+        assert_eq!(chunk.data, "cdef");
+        assert_eq!(chunk.start_offset, 2);
+        assert_eq!(chunk.end_offset, 6);
+        assert!(chunk.truncated);
+    }
+
+    #[test]
+    fn test_eviction_never_splits_characters() {
+        let mut buf = ScrollbackBuffer::new(5);
+        // "é" is two bytes; trimming one byte would split it
+        buf.push("éaaaa");
+        let chunk = buf.read_since(0);
+        assert_eq!(chunk.data, "aaaa");
+        assert_eq!(chunk.start_offset, 2);
+    }
+
+    #[test]
+    fn test_shrinking_limit_evicts_oldest() {
+        let mut buf = ScrollbackBuffer::new(16);
+        buf.push("abcdef");
+        buf.set_limit(2);
+        let chunk = buf.read_since(0);
+        assert_eq!(chunk.data, "ef");
+        assert_eq!(chunk.start_offset, 4);
+    }
+
+    #[test]
+    fn test_read_past_end_is_empty() {
+        let mut buf = ScrollbackBuffer::new(16);
+        buf.push("abc");
+        let chunk = buf.read_since(100);
+        assert!(chunk.data.is_empty());
+        assert_eq!(chunk.start_offset, 3);
+        assert_eq!(chunk.end_offset, 3);
+    }
+}
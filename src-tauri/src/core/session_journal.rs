@@ -0,0 +1,361 @@
+//! Durable per-project session journal.
+//!
+//! `SessionManager` keeps sessions in memory only, so a crash or restart
+//! loses which sessions existed and where they were running. The journal
+//! appends every create / assign / remove event as a JSON line to
+//! `maestro-sessions-<hash>.jsonl` in the app data directory (next to the
+//! `maestro-<hash>.json` project stores), and replays it on demand.
+//!
+//! On startup, `SessionJournal::open` rotates each non-empty live journal to
+//! `maestro-sessions-<hash>.restore.jsonl`. The rotated file is the set of
+//! sessions that can be restored; the live file starts fresh for this run.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::session_manager::{SessionConfig, SessionStatus};
+use super::status_server::StatusServer;
+
+/// Filename prefix shared by live and rotated journals.
+const JOURNAL_PREFIX: &str = "maestro-sessions-";
+/// Suffix of the journal written during the current run.
+const LIVE_SUFFIX: &str = ".jsonl";
+/// Suffix of the journal rotated from the previous run.
+const RESTORE_SUFFIX: &str = ".restore.jsonl";
+
+/// A single session lifecycle event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// A session was registered with `SessionManager::create_session`.
+    Created { session: SessionConfig },
+    /// A branch (and optional worktree) was assigned to a session.
+    BranchAssigned {
+        id: u32,
+        branch: String,
+        worktree_path: Option<String>,
+    },
+    /// A session was removed and should not be restored.
+    Removed { id: u32 },
+    /// A session's child exited on its own; like `Removed`, it should not
+    /// be restored.
+    Exited { id: u32 },
+}
+
+/// One line of the journal file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Unix epoch milliseconds when the event was recorded.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Folds journal events into the set of sessions alive at the end of the log.
+///
+/// Sessions are returned ordered by ID. Events for unknown IDs (e.g. an
+/// assignment whose `Created` line was lost) are ignored. Restored sessions
+/// come back with `Starting` status since their PTYs no longer exist.
+pub fn replay(entries: impl IntoIterator<Item = JournalEntry>) -> Vec<SessionConfig> {
+    let mut sessions: BTreeMap<u32, SessionConfig> = BTreeMap::new();
+    for entry in entries {
+        match entry.event {
+            JournalEvent::Created { session } => {
+                sessions.insert(session.id, session);
+            }
+            JournalEvent::BranchAssigned {
+                id,
+                branch,
+                worktree_path,
+            } => {
+                if let Some(session) = sessions.get_mut(&id) {
+                    session.branch = Some(branch);
+                    session.worktree_path = worktree_path;
+                }
+            }
+            JournalEvent::Removed { id } | JournalEvent::Exited { id } => {
+                sessions.remove(&id);
+            }
+        }
+    }
+
+    sessions
+        .into_values()
+        .map(|mut s| {
+            s.status = SessionStatus::Starting;
+            s
+        })
+        .collect()
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Reads a journal file, skipping lines that fail to parse.
+///
+/// A missing file yields an empty list. Corrupt lines (e.g. a partial write
+/// from a crash mid-append) are logged and skipped rather than failing the
+/// whole load.
+fn read_entries(path: &Path) -> io::Result<Vec<JournalEntry>> {
+    let file = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JournalEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::warn!(
+                "Skipping corrupt session journal line {} in {}: {}",
+                index + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+/// Append-only session journal rooted at a directory.
+///
+/// Writes are serialized through an internal mutex so concurrent command
+/// handlers never interleave partial lines.
+pub struct SessionJournal {
+    dir: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl SessionJournal {
+    /// Opens the journal directory and rotates last run's journals.
+    ///
+    /// Each live journal that still contains sessions replaces the project's
+    /// restore file. Journals that replay to nothing (every session was
+    /// removed) are deleted, leaving any older restore file untouched.
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        let journal = Self {
+            dir: dir.into(),
+            write_lock: Mutex::new(()),
+        };
+        if let Err(e) = journal.rotate() {
+            log::warn!(
+                "Failed to rotate session journals in {}: {}",
+                journal.dir.display(),
+                e
+            );
+        }
+        journal
+    }
+
+    fn live_path(&self, project_path: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}{}",
+            JOURNAL_PREFIX,
+            StatusServer::generate_project_hash(project_path),
+            LIVE_SUFFIX
+        ))
+    }
+
+    fn restore_path(&self, project_path: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}{}",
+            JOURNAL_PREFIX,
+            StatusServer::generate_project_hash(project_path),
+            RESTORE_SUFFIX
+        ))
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !name.starts_with(JOURNAL_PREFIX)
+                || !name.ends_with(LIVE_SUFFIX)
+                || name.ends_with(RESTORE_SUFFIX)
+            {
+                continue;
+            }
+
+            if replay(read_entries(&path)?).is_empty() {
+                fs::remove_file(&path)?;
+            } else {
+                let stem = &name[..name.len() - LIVE_SUFFIX.len()];
+                let target = self.dir.join(format!("{}{}", stem, RESTORE_SUFFIX));
+                fs::rename(&path, &target)?;
+                log::info!("Rotated session journal to {}", target.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends an event to the project's live journal.
+    pub fn record(&self, project_path: &str, event: JournalEvent) -> io::Result<()> {
+        let entry = JournalEntry {
+            timestamp: now_millis(),
+            event,
+        };
+        let mut line = serde_json::to_string(&entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.live_path(project_path))?;
+        file.write_all(line.as_bytes())
+    }
+
+    /// Returns the sessions saved by the previous run for a project.
+    pub fn restorable_sessions(&self, project_path: &str) -> io::Result<Vec<SessionConfig>> {
+        Ok(replay(read_entries(&self.restore_path(project_path))?))
+    }
+
+    /// Deletes the project's restore file once its sessions were restored
+    /// or the user chose to discard them. No-op if it does not exist.
+    pub fn clear_restorable(&self, project_path: &str) -> io::Result<()> {
+        match fs::remove_file(self.restore_path(project_path)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::session_manager::AiMode;
+    use tempfile::tempdir;
+
+    fn session(id: u32) -> SessionConfig {
+        SessionConfig {
+            id,
+            mode: AiMode::Claude,
+            branch: None,
+            status: SessionStatus::Idle,
+            worktree_path: None,
+            project_path: "/tmp/project".to_string(),
+        }
+    }
+
+    fn entry(event: JournalEvent) -> JournalEntry {
+        JournalEntry {
+            timestamp: 0,
+            event,
+        }
+    }
+
+    #[test]
+    fn test_replay_applies_assign_and_remove() {
+        let sessions = replay(vec![
+            entry(JournalEvent::Created { session: session(2) }),
+            entry(JournalEvent::Created { session: session(1) }),
+            entry(JournalEvent::BranchAssigned {
+                id: 2,
+                branch: "feature".to_string(),
+                worktree_path: Some("/tmp/wt".to_string()),
+            }),
+            entry(JournalEvent::Removed { id: 1 }),
+            entry(JournalEvent::Created { session: session(3) }),
+            entry(JournalEvent::Exited { id: 3 }),
+        ]);
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, 2);
+        assert_eq!(sessions[0].branch.as_deref(), Some("feature"));
+        assert_eq!(sessions[0].worktree_path.as_deref(), Some("/tmp/wt"));
+        assert!(matches!(sessions[0].status, SessionStatus::Starting));
+    }
+
+    #[test]
+    fn test_replay_ignores_unknown_session() {
+        let sessions = replay(vec![entry(JournalEvent::BranchAssigned {
+            id: 9,
+            branch: "x".to_string(),
+            worktree_path: None,
+        })]);
+        assert!(sessions.is_empty());
+    }
+
+    #[test]
+    fn test_open_rotates_live_journal_into_restore() {
+        let dir = tempdir().unwrap();
+        let journal = SessionJournal::open(dir.path());
+        journal
+            .record("/tmp/project", JournalEvent::Created { session: session(1) })
+            .unwrap();
+        assert!(journal.restorable_sessions("/tmp/project").unwrap().is_empty());
+
+        // Simulate a restart
+        let journal = SessionJournal::open(dir.path());
+        let restorable = journal.restorable_sessions("/tmp/project").unwrap();
+        assert_eq!(restorable.len(), 1);
+        assert_eq!(restorable[0].id, 1);
+        assert!(!journal.live_path("/tmp/project").exists());
+
+        journal.clear_restorable("/tmp/project").unwrap();
+        assert!(journal.restorable_sessions("/tmp/project").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_open_keeps_restore_file_when_live_journal_is_empty() {
+        let dir = tempdir().unwrap();
+        let journal = SessionJournal::open(dir.path());
+        journal
+            .record("/tmp/project", JournalEvent::Created { session: session(1) })
+            .unwrap();
+
+        // Second run creates and removes a session without restoring
+        let journal = SessionJournal::open(dir.path());
+        journal
+            .record("/tmp/project", JournalEvent::Created { session: session(1) })
+            .unwrap();
+        journal
+            .record("/tmp/project", JournalEvent::Removed { id: 1 })
+            .unwrap();
+
+        let journal = SessionJournal::open(dir.path());
+        assert_eq!(journal.restorable_sessions("/tmp/project").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_lines_are_skipped() {
+        let dir = tempdir().unwrap();
+        let journal = SessionJournal::open(dir.path());
+        journal
+            .record("/tmp/project", JournalEvent::Created { session: session(3) })
+            .unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal.live_path("/tmp/project"))
+            .unwrap();
+        file.write_all(b"{\"timestamp\":1,\"event\":\"crea").unwrap();
+
+        let entries = read_entries(&journal.live_path("/tmp/project")).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+}
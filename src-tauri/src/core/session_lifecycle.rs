@@ -0,0 +1,154 @@
+//! What happens to a session when its child process exits on its own.
+//!
+//! Sessions ended through `kill_session` are cleaned up by whoever killed
+//! them. When the shell or agent CLI exits by itself (the user typed `exit`,
+//! the agent crashed), nobody is around to do that, so the app sink calls
+//! `on_child_exit` instead.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager};
+
+use super::pty_sink::PtyExit;
+use super::session_manager::{SessionManager, SessionStatus};
+use super::status_detector::StatusInference;
+use super::status_events;
+use super::status_server::{SessionStatusPayload, StatusServer};
+use super::task_board::TaskBoards;
+use super::{mcp_config_writer, plugin_config_writer};
+
+/// Status a session moves to when its child exits with `status`.
+pub fn status_for_exit(status: &PtyExit) -> SessionStatus {
+    if status.success {
+        SessionStatus::Done
+    } else {
+        SessionStatus::Error
+    }
+}
+
+/// Human-readable description of an exit, used as the status message.
+pub fn describe_exit(status: &PtyExit) -> String {
+    match &status.signal {
+        Some(signal) => format!("Process terminated by {}", signal),
+        None if status.success => "Process exited".to_string(),
+        None => format!("Process exited with code {}", status.exit_code),
+    }
+}
+
+/// Finalizes a session whose child exited on its own.
+///
+/// Moves the session to `Done` or `Error` (journaled, so it is not restored
+/// after a restart), emits `session-status-changed`, reopens the tasks it
+/// still held, stops status reporting and inference for it, and removes the
+/// session-specific MCP and plugin config from its working directory. The
+/// config files are shared by every session in that directory, so they are
+/// left alone while another session there is still running.
+pub async fn on_child_exit(app: &AppHandle, session_id: u32, status: &PtyExit) {
+    let session_mgr = app.state::<SessionManager>();
+    let Some(session) = session_mgr.get_session(session_id) else {
+        // A PTY that was never registered as a session (e.g. a plain pane)
+        return;
+    };
+
+    let new_status = status_for_exit(status);
+    let payload = SessionStatusPayload {
+        session_id,
+        project_path: session.project_path.clone(),
+        status: new_status.as_str().to_string(),
+        message: describe_exit(status),
+        needs_input_prompt: None,
+        awaiting_reply: false,
+    };
+    status_events::publish(app, &payload);
+    session_mgr.record_exit(session_id);
+
+    if let Some(boards) = app.try_state::<TaskBoards>() {
+        boards
+            .release_session(app, &session.project_path, session_id)
+            .await;
+    }
+
+    if let Some(server) = app.try_state::<Arc<StatusServer>>() {
+        server.unregister_session(session_id).await;
+    }
+    if let Some(inference) = app.try_state::<StatusInference>() {
+        inference.forget(session_id);
+    }
+
+    let working_dir = session
+        .worktree_path
+        .as_deref()
+        .unwrap_or(&session.project_path);
+    let shared = session_mgr.all_sessions().iter().any(|other| {
+        other.id != session_id
+            && other
+                .worktree_path
+                .as_deref()
+                .unwrap_or(&other.project_path)
+                == working_dir
+            && !matches!(other.status, SessionStatus::Done | SessionStatus::Error)
+    });
+    if shared {
+        log::debug!(
+            "Keeping MCP/plugin config in {} for other running sessions",
+            working_dir
+        );
+        return;
+    }
+
+    if let Err(e) =
+        mcp_config_writer::remove_session_mcp_config(Path::new(working_dir), session_id).await
+    {
+        log::warn!(
+            "Failed to remove MCP config for session {}: {}",
+            session_id,
+            e
+        );
+    }
+    if let Err(e) = plugin_config_writer::remove_session_plugin_config(Path::new(working_dir)).await
+    {
+        log::warn!(
+            "Failed to remove plugin config for session {}: {}",
+            session_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(exit_code: u32, signal: Option<&str>) -> PtyExit {
+        PtyExit {
+            exit_code,
+            signal: signal.map(str::to_string),
+            success: exit_code == 0 && signal.is_none(),
+            killed: false,
+        }
+    }
+
+    #[test]
+    fn test_status_for_exit() {
+        assert_eq!(status_for_exit(&exit(0, None)), SessionStatus::Done);
+        assert_eq!(status_for_exit(&exit(2, None)), SessionStatus::Error);
+        assert_eq!(
+            status_for_exit(&exit(1, Some("Hangup"))),
+            SessionStatus::Error
+        );
+    }
+
+    #[test]
+    fn test_describe_exit() {
+        assert_eq!(describe_exit(&exit(0, None)), "Process exited");
+        assert_eq!(
+            describe_exit(&exit(127, None)),
+            "Process exited with code 127"
+        );
+        assert_eq!(
+            describe_exit(&exit(1, Some("Segmentation fault"))),
+            "Process terminated by Segmentation fault"
+        );
+    }
+}
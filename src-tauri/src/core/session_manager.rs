@@ -1,7 +1,11 @@
+use std::sync::{Arc, OnceLock};
+
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use serde::{Deserialize, Serialize};
 
+use super::session_journal::{JournalEvent, SessionJournal};
+
 /// Which AI backend a session is configured to use.
 ///
 /// `Plain` is a raw terminal with no AI agent attached, useful for
@@ -19,7 +23,7 @@ pub enum AiMode {
 /// Transitions are driven by the frontend; the backend does not enforce
 /// a state machine. Invalid transitions (e.g., `Done` -> `Working`) are
 /// allowed and the caller is responsible for correctness.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SessionStatus {
     Starting,
     Idle,
@@ -29,6 +33,33 @@ pub enum SessionStatus {
     Error,
 }
 
+impl SessionStatus {
+    /// Name used in `session-status-changed` events (same as the serialized form).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Starting => "Starting",
+            SessionStatus::Idle => "Idle",
+            SessionStatus::Working => "Working",
+            SessionStatus::NeedsInput => "NeedsInput",
+            SessionStatus::Done => "Done",
+            SessionStatus::Error => "Error",
+        }
+    }
+
+    /// Parses a name returned by `as_str`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Starting" => Some(SessionStatus::Starting),
+            "Idle" => Some(SessionStatus::Idle),
+            "Working" => Some(SessionStatus::Working),
+            "NeedsInput" => Some(SessionStatus::NeedsInput),
+            "Done" => Some(SessionStatus::Done),
+            "Error" => Some(SessionStatus::Error),
+            _ => None,
+        }
+    }
+}
+
 /// Frontend-visible configuration and state for a single session.
 ///
 /// `branch` and `worktree_path` are `None` until `assign_branch` is called,
@@ -45,13 +76,29 @@ pub struct SessionConfig {
     pub project_path: String,
 }
 
+/// Notified when sessions are added to or removed from a `SessionManager`.
+///
+/// Called synchronously from the mutating method, so implementations should
+/// hand any slow work (network, disk) off to a task.
+pub trait SessionObserver: Send + Sync {
+    fn session_created(&self, session: &SessionConfig);
+    fn session_removed(&self, session: &SessionConfig);
+}
+
 /// Thread-safe session registry backed by `DashMap` for lock-free concurrent reads.
 ///
 /// Designed to be placed in Tauri managed state. All methods take `&self` so
 /// no exclusive access is needed, enabling safe concurrent access from
 /// multiple async command handlers.
+///
+/// When a `SessionJournal` is attached, create/assign/remove events are also
+/// appended to disk so sessions can be restored after a restart. Journal
+/// failures are logged and never fail the in-memory operation. An attached
+/// `SessionObserver` hears about the same creates and removes.
 pub struct SessionManager {
     sessions: DashMap<u32, SessionConfig>,
+    journal: OnceLock<SessionJournal>,
+    observer: OnceLock<Arc<dyn SessionObserver>>,
 }
 
 impl Default for SessionManager {
@@ -65,6 +112,38 @@ impl SessionManager {
     pub fn new() -> Self {
         Self {
             sessions: DashMap::new(),
+            journal: OnceLock::new(),
+            observer: OnceLock::new(),
+        }
+    }
+
+    /// Attaches the on-disk journal. Only the first call has an effect;
+    /// the journal cannot be swapped once sessions are being recorded.
+    pub fn attach_journal(&self, journal: SessionJournal) {
+        if self.journal.set(journal).is_err() {
+            log::warn!("Session journal already attached; ignoring");
+        }
+    }
+
+    /// Attaches the observer. Like the journal, only the first call has an
+    /// effect.
+    pub fn attach_observer(&self, observer: Arc<dyn SessionObserver>) {
+        if self.observer.set(observer).is_err() {
+            log::warn!("Session observer already attached; ignoring");
+        }
+    }
+
+    /// Returns the attached journal, if any.
+    pub fn journal(&self) -> Option<&SessionJournal> {
+        self.journal.get()
+    }
+
+    /// Appends an event to the journal, logging (not propagating) failures.
+    fn record(&self, project_path: &str, event: JournalEvent) {
+        if let Some(journal) = self.journal.get() {
+            if let Err(e) = journal.record(project_path, event) {
+                log::warn!("Failed to write session journal for '{}': {}", project_path, e);
+            }
         }
     }
 
@@ -83,6 +162,15 @@ impl SessionManager {
             Entry::Occupied(e) => Err(e.get().clone()),
             Entry::Vacant(e) => {
                 e.insert(config.clone());
+                self.record(
+                    &config.project_path,
+                    JournalEvent::Created {
+                        session: config.clone(),
+                    },
+                );
+                if let Some(observer) = self.observer.get() {
+                    observer.session_created(&config);
+                }
                 Ok(config)
             }
         }
@@ -104,16 +192,32 @@ impl SessionManager {
         }
     }
 
+    /// Journals that a session's child exited, so it is not restored after a
+    /// restart. The session itself stays registered with its final status.
+    pub fn record_exit(&self, id: u32) {
+        if let Some(project_path) = self.sessions.get(&id).map(|s| s.project_path.clone()) {
+            self.record(&project_path, JournalEvent::Exited { id });
+        }
+    }
+
     /// Associates a branch (and optional worktree path) with an existing session.
     /// Returns the updated config, or `None` if the session does not exist.
     pub fn assign_branch(&self, id: u32, branch: String, worktree_path: Option<String>) -> Option<SessionConfig> {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
+        let updated = {
+            let mut session = self.sessions.get_mut(&id)?;
             session.branch = Some(branch);
             session.worktree_path = worktree_path;
-            Some(session.clone())
-        } else {
-            None
-        }
+            session.clone()
+        };
+        self.record(
+            &updated.project_path,
+            JournalEvent::BranchAssigned {
+                id,
+                branch: updated.branch.clone().unwrap_or_default(),
+                worktree_path: updated.worktree_path.clone(),
+            },
+        );
+        Some(updated)
     }
 
     /// Returns a snapshot of all active sessions. Order is not guaranteed.
@@ -123,7 +227,12 @@ impl SessionManager {
 
     /// Removes and returns a session. Returns `None` if not found.
     pub fn remove_session(&self, id: u32) -> Option<SessionConfig> {
-        self.sessions.remove(&id).map(|(_, v)| v)
+        let (_, removed) = self.sessions.remove(&id)?;
+        self.record(&removed.project_path, JournalEvent::Removed { id });
+        if let Some(observer) = self.observer.get() {
+            observer.session_removed(&removed);
+        }
+        Some(removed)
     }
 
     /// Returns all sessions for a specific project path.
@@ -147,7 +256,7 @@ impl SessionManager {
 
         ids_to_remove
             .into_iter()
-            .filter_map(|id| self.sessions.remove(&id).map(|(_, v)| v))
+            .filter_map(|id| self.remove_session(id))
             .collect()
     }
 }
@@ -0,0 +1,702 @@
+//! Status inference from terminal output.
+//!
+//! Session status normally changes only when an agent calls the
+//! `maestro_status` MCP tool, which Gemini, Codex and plain shells never do
+//! reliably. This module watches each session's PTY output instead and
+//! matches it against per-`AiMode` rules: permission prompts, spinners, idle
+//! input boxes and shell prompts. A match that changes the session's status
+//! updates `SessionManager` and emits the same `session-status-changed` event
+//! as the status server.
+//!
+//! Rules are plain regexes evaluated against the most recent output with ANSI
+//! escapes stripped. When several rules match, the one whose match ends
+//! latest wins, since that is what is currently on screen. Users can add
+//! their own rules per project. They compete with the built-ins on the same
+//! terms and only take precedence when two matches end at the same place, so
+//! a user rule cannot pin a status that later output has moved past.
+//!
+//! Rules marked `quiet` are only checked once output has stopped for
+//! `QUIET_PERIOD`, and only against the last unterminated line. Shell prompts
+//! are recognized this way, so a line that merely ends in `>` or `%` while a
+//! command is still printing does not mark the session idle.
+
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use super::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus};
+use super::status_events;
+use super::status_server::{SessionStatusPayload, StatusServer};
+
+/// Store key holding a project's `StatusRuleConfig`.
+pub const STATUS_RULES_KEY: &str = "status_rules";
+
+/// How long output-based inference stays quiet after the agent reported its
+/// own status through MCP. Explicit reports are more reliable than scraping.
+const REPORT_GRACE: Duration = Duration::from_secs(30);
+
+/// How long output must stop before `quiet` rules are checked.
+const QUIET_PERIOD: Duration = Duration::from_millis(750);
+
+/// Longest partial line carried over between output batches.
+const MAX_CARRY: usize = 512;
+
+/// Longest matched line included in a `NeedsInput` prompt.
+const MAX_PROMPT_LEN: usize = 200;
+
+/// A pattern that maps terminal output to a session status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusRule {
+    /// Short label shown in the status message, e.g. "permission prompt".
+    pub name: String,
+    /// Regex matched against ANSI-stripped output.
+    pub pattern: String,
+    /// Status to report when the pattern matches.
+    pub status: SessionStatus,
+    /// Match only once output has gone quiet, against the last unterminated
+    /// line instead of the whole batch.
+    #[serde(default)]
+    pub quiet: bool,
+}
+
+impl StatusRule {
+    fn new(name: &str, pattern: &str, status: SessionStatus) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            status,
+            quiet: false,
+        }
+    }
+
+    fn quiet(name: &str, pattern: &str, status: SessionStatus) -> Self {
+        Self {
+            quiet: true,
+            ..Self::new(name, pattern, status)
+        }
+    }
+}
+
+/// Per-project inference settings, persisted in the project store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusRuleConfig {
+    /// Whether output-based inference runs for this project's sessions.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// User-defined rules, listed ahead of the built-in ones so they win ties.
+    #[serde(default)]
+    pub rules: Vec<StatusRule>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for StatusRuleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl StatusRuleConfig {
+    /// Checks that every user rule compiles.
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.rules {
+            Regex::new(&rule.pattern)
+                .map_err(|e| format!("Invalid pattern for rule '{}': {}", rule.name, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the built-in rules for a mode, in priority order.
+pub fn builtin_rules(mode: &AiMode) -> Vec<StatusRule> {
+    use SessionStatus::{Idle, NeedsInput, Working};
+
+    let mut rules = match mode {
+        AiMode::Claude => vec![
+            StatusRule::new(
+                "permission prompt",
+                r"Do you want to (proceed|make this edit|create|allow|run)[^?\n]*\?",
+                NeedsInput,
+            ),
+            StatusRule::new("permission menu", r"❯\s*1\.\s*Yes", NeedsInput),
+            StatusRule::new("spinner", r"(?i)esc to interrupt", Working),
+            StatusRule::new("input box", r"\? for shortcuts", Idle),
+        ],
+        AiMode::Gemini => vec![
+            StatusRule::new(
+                "confirmation prompt",
+                r"(?i)(allow execution|apply this change|do you want to proceed)[^?\n]*\?|waiting for user confirmation",
+                NeedsInput,
+            ),
+            StatusRule::new("spinner", r"(?i)esc to cancel", Working),
+            StatusRule::new("input box", r"(?i)type your message", Idle),
+        ],
+        AiMode::Codex => vec![
+            StatusRule::new(
+                "approval prompt",
+                r"(?i)(allow command|would you like to (run the following command|make the following edits))[^?\n]*\?",
+                NeedsInput,
+            ),
+            StatusRule::new("spinner", r"(?i)esc to interrupt", Working),
+            StatusRule::new("input box", r"⏎ send", Idle),
+        ],
+        AiMode::Plain => vec![StatusRule::new(
+            "password prompt",
+            r"(?i)password[^:\n]*:\s*\z",
+            NeedsInput,
+        )],
+    };
+
+    rules.push(StatusRule::new(
+        "yes/no prompt",
+        r"(?i)(\[y/n\]|\(y/n\)|\[yes/no\]|\(yes/no\))\s*\??\s*\z",
+        NeedsInput,
+    ));
+    if matches!(mode, AiMode::Plain) {
+        // A whole line that looks like a prompt, e.g. `user@host:~/src$ `,
+        // `(venv) ~ ❯ ` or `PS C:\src> `, still there once output stops
+        rules.push(StatusRule::quiet(
+            "shell prompt",
+            r"^(\([^)]*\) )?(\S+ )?\S*[$#%❯›>] ?\z",
+            Idle,
+        ));
+    }
+    rules
+}
+
+/// A status change recognized in the output.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub status: SessionStatus,
+    /// Name of the rule that matched.
+    pub rule: String,
+    /// The output line containing the match, trimmed.
+    pub line: String,
+}
+
+struct CompiledRule {
+    name: String,
+    regex: Regex,
+    status: SessionStatus,
+    quiet: bool,
+}
+
+/// Infers one session's status from its output stream.
+pub struct StatusDetector {
+    rules: Vec<CompiledRule>,
+    /// Unterminated last line of the previous batch, so patterns split
+    /// across batches still match.
+    carry: String,
+    current: Option<SessionStatus>,
+}
+
+impl StatusDetector {
+    /// Builds a detector from rules in priority order. Rules that fail to
+    /// compile are skipped with a warning.
+    pub fn new(rules: &[StatusRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some(CompiledRule {
+                    name: rule.name.clone(),
+                    regex,
+                    status: rule.status.clone(),
+                    quiet: rule.quiet,
+                }),
+                Err(e) => {
+                    log::warn!("Skipping status rule '{}': {}", rule.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            rules,
+            carry: String::new(),
+            current: None,
+        }
+    }
+
+    /// Feeds a batch of raw output. Returns a detection only when the
+    /// inferred status differs from the last one reported.
+    pub fn feed(&mut self, output: &str) -> Option<Detection> {
+        let mut text = std::mem::take(&mut self.carry);
+        text.push_str(&strip_ansi(output));
+
+        let detection = self.detect(&text, false);
+        let last_line = text.rfind('\n').map_or(0, |i| i + 1);
+        self.carry = tail(&text[last_line..], MAX_CARRY).to_string();
+        detection
+    }
+
+    /// Whether any rule waits for output to go quiet.
+    pub fn has_quiet_rules(&self) -> bool {
+        self.rules.iter().any(|rule| rule.quiet)
+    }
+
+    /// Checks the `quiet` rules against the last unterminated line. Call once
+    /// no output has arrived for `QUIET_PERIOD`.
+    pub fn settle(&mut self) -> Option<Detection> {
+        let line = std::mem::take(&mut self.carry);
+        let detection = self.detect(&line, true);
+        self.carry = line;
+        detection
+    }
+
+    /// Runs the rules with the given `quiet` flag over `text`. Returns a
+    /// detection only when the inferred status changed.
+    fn detect(&mut self, text: &str, quiet: bool) -> Option<Detection> {
+        // Latest match end wins; earlier rules win ties
+        let mut best: Option<(usize, usize, &CompiledRule)> = None;
+        for rule in self.rules.iter().filter(|rule| rule.quiet == quiet) {
+            if let Some(m) = rule.regex.find_iter(text).last() {
+                if best.is_none_or(|(_, end, _)| m.end() > end) {
+                    best = Some((m.start(), m.end(), rule));
+                }
+            }
+        }
+
+        let (start, end, rule) = best?;
+        if self.current.as_ref() == Some(&rule.status) {
+            return None;
+        }
+        self.current = Some(rule.status.clone());
+        Some(Detection {
+            status: rule.status.clone(),
+            rule: rule.name.clone(),
+            line: line_around(text, start, end),
+        })
+    }
+}
+
+/// Returns the trimmed line containing `start..end`, capped in length.
+fn line_around(text: &str, start: usize, end: usize) -> String {
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i);
+    let line = text[line_start..line_end].trim();
+    line.chars().take(MAX_PROMPT_LEN).collect()
+}
+
+/// Returns at most the last `max` bytes of `s`, on a character boundary.
+fn tail(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut start = s.len() - max;
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    &s[start..]
+}
+
+/// Removes escape sequences and control characters from terminal output.
+///
+/// Carriage returns and cursor movements that jump to another row become
+/// newlines, so text redrawn in place by TUIs still ends up on its own line.
+pub fn strip_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates, then a final byte
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            if matches!(c, 'A' | 'B' | 'E' | 'F' | 'H' | 'd' | 'f') {
+                                out.push('\n');
+                            }
+                            break;
+                        }
+                    }
+                }
+                // OSC, DCS, SOS, PM, APC: terminated by BEL or ST
+                Some(']' | 'P' | 'X' | '^' | '_') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Charset designation takes one more character
+                Some('(' | ')' | '*' | '+') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\r' => {
+                if chars.peek() != Some(&'\n') {
+                    out.push('\n');
+                }
+            }
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+struct SessionDetector {
+    project_path: String,
+    detector: StatusDetector,
+    last_output: Instant,
+    /// Whether a task is waiting to run the detector's `quiet` rules.
+    settle_pending: bool,
+}
+
+/// Outcome of `StatusInference::settle`.
+pub enum Settle {
+    /// Output arrived in the meantime; check again after this long.
+    Wait(Duration),
+    /// Output stayed quiet and the `quiet` rules ran.
+    Done(Option<Detection>),
+}
+
+/// Runs a `StatusDetector` for every session that produces output.
+///
+/// Detectors are created lazily from the session's mode and its project's
+/// rules on the first output after `create_session`; sessions that are not
+/// registered with `SessionManager` are ignored.
+#[derive(Default)]
+pub struct StatusInference {
+    detectors: DashMap<u32, SessionDetector>,
+    /// Project path -> rule config, loaded from the project store on demand
+    project_rules: DashMap<String, StatusRuleConfig>,
+    /// Session ID -> time of the last `maestro_status` report
+    reported_at: DashMap<u32, Instant>,
+}
+
+impl StatusInference {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether rules for `project_path` are already loaded.
+    pub fn has_project_rules(&self, project_path: &str) -> bool {
+        self.project_rules.contains_key(project_path)
+    }
+
+    /// Replaces a project's rules. Its sessions' detectors are rebuilt on
+    /// their next output.
+    pub fn set_project_rules(&self, project_path: &str, config: StatusRuleConfig) {
+        self.project_rules.insert(project_path.to_string(), config);
+        self.detectors
+            .retain(|_, entry| entry.project_path != project_path);
+    }
+
+    /// Records that the agent reported its own status, pausing inference for
+    /// that session for a while.
+    pub fn note_reported(&self, session_id: u32) {
+        self.reported_at.insert(session_id, Instant::now());
+    }
+
+    /// Drops all state for a session that has ended.
+    pub fn forget(&self, session_id: u32) {
+        self.detectors.remove(&session_id);
+        self.reported_at.remove(&session_id);
+    }
+
+    fn recently_reported(&self, session_id: u32) -> bool {
+        self.reported_at
+            .get(&session_id)
+            .is_some_and(|reported| reported.elapsed() < REPORT_GRACE)
+    }
+
+    /// Feeds a batch of output for `session`. Returns a detection when the
+    /// inferred status changed.
+    pub fn observe(&self, session: &SessionConfig, output: &str) -> Option<Detection> {
+        if self.recently_reported(session.id) {
+            return None;
+        }
+
+        let config = self
+            .project_rules
+            .get(&session.project_path)
+            .map(|c| c.clone())
+            .unwrap_or_default();
+        if !config.enabled {
+            return None;
+        }
+
+        let mut entry = self.detectors.entry(session.id).or_insert_with(|| {
+            let mut rules = config.rules;
+            rules.extend(builtin_rules(&session.mode));
+            SessionDetector {
+                project_path: session.project_path.clone(),
+                detector: StatusDetector::new(&rules),
+                last_output: Instant::now(),
+                settle_pending: false,
+            }
+        });
+        entry.last_output = Instant::now();
+        entry.detector.feed(output)
+    }
+
+    /// Claims the job of settling a session after its latest output. Returns
+    /// true if the caller should call `settle` once `QUIET_PERIOD` has passed;
+    /// false if its detector has no `quiet` rules or a settle is already due.
+    pub fn schedule_settle(&self, session_id: u32) -> bool {
+        let Some(mut entry) = self.detectors.get_mut(&session_id) else {
+            return false;
+        };
+        if entry.settle_pending || !entry.detector.has_quiet_rules() {
+            return false;
+        }
+        entry.settle_pending = true;
+        true
+    }
+
+    /// Runs a session's `quiet` rules if no output arrived for `QUIET_PERIOD`.
+    pub fn settle(&self, session_id: u32) -> Settle {
+        let Some(mut entry) = self.detectors.get_mut(&session_id) else {
+            return Settle::Done(None);
+        };
+        let quiet_for = entry.last_output.elapsed();
+        if quiet_for < QUIET_PERIOD {
+            return Settle::Wait(QUIET_PERIOD - quiet_for);
+        }
+        entry.settle_pending = false;
+        if self.recently_reported(session_id) {
+            return Settle::Done(None);
+        }
+        Settle::Done(entry.detector.settle())
+    }
+}
+
+/// Reads a project's rule config from its store, falling back to defaults.
+pub fn load_project_rules(app: &AppHandle, project_path: &str) -> StatusRuleConfig {
+    app.store(StatusServer::project_store_name(project_path))
+        .ok()
+        .and_then(|store| store.get(STATUS_RULES_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Runs inference on a batch of PTY output and publishes any status change.
+///
+/// Called from the app's `PtyEventSink`. Does nothing until the session is
+/// registered with `SessionManager`.
+pub fn observe_output(app: &AppHandle, session_id: u32, output: &str) {
+    let Some(engine) = app.try_state::<StatusInference>() else {
+        return;
+    };
+    let session_mgr = app.state::<SessionManager>();
+    let Some(session) = session_mgr.get_session(session_id) else {
+        return;
+    };
+
+    if !engine.has_project_rules(&session.project_path) {
+        let config = load_project_rules(app, &session.project_path);
+        engine.set_project_rules(&session.project_path, config);
+    }
+
+    let detection = engine.observe(&session, output);
+    if engine.schedule_settle(session_id) {
+        spawn_settle(app.clone(), session_id);
+    }
+    if let Some(detection) = detection {
+        publish_detection(app, session_id, session.project_path, detection);
+    }
+}
+
+/// Waits until a session's output has been quiet for `QUIET_PERIOD`, then
+/// runs its `quiet` rules and publishes any status change.
+fn spawn_settle(app: AppHandle, session_id: u32) {
+    tauri::async_runtime::spawn(async move {
+        let mut wait = QUIET_PERIOD;
+        loop {
+            tokio::time::sleep(wait).await;
+            let engine = app.state::<StatusInference>();
+            match engine.settle(session_id) {
+                Settle::Wait(remaining) => wait = remaining,
+                Settle::Done(detection) => {
+                    let session = app.state::<SessionManager>().get_session(session_id);
+                    if let (Some(detection), Some(session)) = (detection, session) {
+                        publish_detection(&app, session_id, session.project_path, detection);
+                    }
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Updates the session's status from an inferred detection.
+fn publish_detection(app: &AppHandle, session_id: u32, project_path: String, detection: Detection) {
+    log::debug!(
+        "Inferred status {:?} for session {} from rule '{}'",
+        detection.status,
+        session_id,
+        detection.rule
+    );
+
+    let needs_input_prompt =
+        (detection.status == SessionStatus::NeedsInput).then(|| detection.line.clone());
+    let payload = SessionStatusPayload {
+        session_id,
+        project_path,
+        status: detection.status.as_str().to_string(),
+        message: format!("Detected from terminal output ({})", detection.rule),
+        needs_input_prompt,
+        awaiting_reply: false,
+    };
+    status_events::publish(app, &payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector(mode: AiMode) -> StatusDetector {
+        StatusDetector::new(&builtin_rules(&mode))
+    }
+
+    #[test]
+    fn test_strip_ansi_removes_sequences() {
+        let input = "\x1b[1;32mgreen\x1b[0m \x1b]0;title\x07text\x1b(B";
+        assert_eq!(strip_ansi(input), "green text");
+    }
+
+    #[test]
+    fn test_strip_ansi_breaks_lines_on_cursor_moves() {
+        assert_eq!(
+            strip_ansi("one\x1b[5;1Htwo\rthree\r\n"),
+            "one\ntwo\nthree\n"
+        );
+    }
+
+    #[test]
+    fn test_claude_transitions() {
+        let mut d = detector(AiMode::Claude);
+
+        let working = d.feed("\x1b[2K✻ Thinking… (esc to interrupt)").unwrap();
+        assert_eq!(working.status, SessionStatus::Working);
+        // Spinner redraws don't repeat the detection
+        assert!(d.feed("\r✶ Thinking… (esc to interrupt)").is_none());
+
+        let prompt = d
+            .feed("\nDo you want to make this edit to main.rs?\n❯ 1. Yes\n  2. No\n")
+            .unwrap();
+        assert_eq!(prompt.status, SessionStatus::NeedsInput);
+        assert_eq!(prompt.line, "❯ 1. Yes");
+
+        let idle = d.feed("> \n  ? for shortcuts").unwrap();
+        assert_eq!(idle.status, SessionStatus::Idle);
+    }
+
+    #[test]
+    fn test_latest_match_wins() {
+        let mut d = detector(AiMode::Codex);
+        let detection = d.feed("Working (esc to interrupt)\n⏎ send").unwrap();
+        assert_eq!(detection.status, SessionStatus::Idle);
+    }
+
+    #[test]
+    fn test_pattern_split_across_batches() {
+        let mut d = detector(AiMode::Gemini);
+        assert!(d.feed("⠋ Reading files (esc to ").is_none());
+        let detection = d.feed("cancel, 3s)").unwrap();
+        assert_eq!(detection.status, SessionStatus::Working);
+    }
+
+    #[test]
+    fn test_plain_shell_prompt() {
+        let mut d = detector(AiMode::Plain);
+        // The prompt only counts once output has gone quiet
+        assert!(d.feed("user@host:~/src$ ").is_none());
+        assert_eq!(d.settle().unwrap().status, SessionStatus::Idle);
+        let detection = d.feed("ls\nRemove file? [y/N] ").unwrap();
+        assert_eq!(detection.status, SessionStatus::NeedsInput);
+        assert_eq!(detection.line, "Remove file? [y/N]");
+    }
+
+    #[test]
+    fn test_shell_prompt_needs_a_whole_prompt_line() {
+        let mut d = detector(AiMode::Plain);
+        assert!(d.feed("Progress: building <target>").is_none());
+        assert!(d.settle().is_none());
+        assert!(d.feed("\n(venv) ~/src ❯ ").is_none());
+        assert_eq!(d.settle().unwrap().status, SessionStatus::Idle);
+    }
+
+    #[test]
+    fn test_settle_waits_for_quiet_output() {
+        let engine = StatusInference::new();
+        let session = SessionConfig {
+            id: 1,
+            mode: AiMode::Plain,
+            branch: None,
+            status: SessionStatus::Idle,
+            worktree_path: None,
+            project_path: "/tmp/project".to_string(),
+        };
+        assert!(engine.observe(&session, "$ ").is_none());
+        assert!(engine.schedule_settle(1));
+        // Only one settle is pending at a time
+        assert!(!engine.schedule_settle(1));
+        assert!(matches!(engine.settle(1), Settle::Wait(_)));
+
+        engine.detectors.get_mut(&1).unwrap().last_output -= QUIET_PERIOD;
+        let Settle::Done(Some(detection)) = engine.settle(1) else {
+            panic!("expected the prompt to be detected");
+        };
+        assert_eq!(detection.status, SessionStatus::Idle);
+        assert!(engine.schedule_settle(1));
+    }
+
+    #[test]
+    fn test_user_rules_take_priority() {
+        let mut rules = vec![StatusRule::new(
+            "rate limit",
+            r"Rate limit reached",
+            SessionStatus::Error,
+        )];
+        rules.extend(builtin_rules(&AiMode::Claude));
+        let mut d = StatusDetector::new(&rules);
+        let detection = d.feed("Rate limit reached").unwrap();
+        assert_eq!(detection.status, SessionStatus::Error);
+        assert_eq!(detection.rule, "rate limit");
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let config = StatusRuleConfig {
+            enabled: true,
+            rules: vec![StatusRule::new("broken", "(unclosed", SessionStatus::Idle)],
+        };
+        assert!(config.validate().is_err());
+        // The detector skips it instead of failing
+        assert!(StatusDetector::new(&config.rules).rules.is_empty());
+    }
+
+    #[test]
+    fn test_inference_pauses_after_mcp_report() {
+        let engine = StatusInference::new();
+        let session = SessionConfig {
+            id: 1,
+            mode: AiMode::Claude,
+            branch: None,
+            status: SessionStatus::Idle,
+            worktree_path: None,
+            project_path: "/tmp/project".to_string(),
+        };
+        engine.note_reported(1);
+        assert!(engine.observe(&session, "(esc to interrupt)").is_none());
+        engine.forget(1);
+        assert!(engine.observe(&session, "(esc to interrupt)").is_some());
+    }
+}
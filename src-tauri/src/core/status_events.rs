@@ -0,0 +1,59 @@
+//! The one place a session's status changes.
+//!
+//! Statuses come from the agent's own reports (`status_server`), from
+//! inference on its terminal output (`status_detector`) and from its child
+//! process exiting (`session_lifecycle`). Each source hands the new status
+//! to `publish`, so `SessionManager`, the frontend, the status history, the
+//! notifier and webhooks see every transition no matter where it came from.
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::notifications::Notifier;
+use super::session_manager::{SessionManager, SessionStatus};
+use super::status_server::{SessionStatusPayload, StatusServer};
+use super::webhooks::{WebhookEvent, Webhooks};
+
+/// Moves a session to the status in `payload`: updates `SessionManager`,
+/// emits `session-status-changed`, records the transition in the status
+/// history and tells the notifier what the session moved from. Webhooks
+/// only hear about actual changes, not repeated reports of one status.
+pub fn publish(app: &AppHandle, payload: &SessionStatusPayload) {
+    let session_mgr = app.state::<SessionManager>();
+    let session = session_mgr.get_session(payload.session_id);
+    let previous = session.as_ref().map(|session| session.status.clone());
+    if let Some(status) = SessionStatus::from_name(&payload.status) {
+        session_mgr.update_status(payload.session_id, status);
+    }
+
+    if let Err(e) = app.emit("session-status-changed", payload) {
+        log::warn!(
+            "Failed to emit status of session {}: {}",
+            payload.session_id,
+            e
+        );
+    }
+
+    if let Some(server) = app.try_state::<Arc<StatusServer>>() {
+        server.record_status(payload);
+    }
+    if let Some(notifier) = app.try_state::<Notifier>() {
+        notifier.observe(app, payload, previous.as_ref().map(SessionStatus::as_str));
+    }
+
+    if previous.as_ref().map(SessionStatus::as_str) == Some(payload.status.as_str()) {
+        return;
+    }
+    if let Some(webhooks) = app.try_state::<Arc<Webhooks>>() {
+        webhooks.fire(WebhookEvent::status(
+            payload.session_id,
+            &payload.project_path,
+            &payload.status,
+            &payload.message,
+            session
+                .as_ref()
+                .and_then(|session| session.branch.as_deref()),
+        ));
+    }
+}
@@ -0,0 +1,378 @@
+//! Per-session timeline of status transitions.
+//!
+//! The status server records every status a session moves to, whether the
+//! agent reported it, it was inferred from terminal output or the process
+//! exited, so the UI can show how long each agent spent working, blocked or
+//! idle. Live sessions are kept in memory, along with the most recently
+//! ended ones. Timelines are also written to the `status_history` key of
+//! their project's `maestro-<hash>.json` store: periodically while the
+//! session runs and once more when it ends, so activity survives a restart
+//! or a crash.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use super::status_server::StatusServer;
+
+/// Transitions kept per session; older ones are dropped first.
+pub const MAX_TRANSITIONS: usize = 500;
+
+/// Ended sessions kept in memory.
+pub const MAX_ENDED_SESSIONS: usize = 100;
+
+/// Ended sessions kept in each project's store.
+pub const MAX_PERSISTED_SESSIONS: usize = 50;
+
+/// Store key for a project's persisted timelines.
+const STATUS_HISTORY_KEY: &str = "status_history";
+
+/// One status a session moved to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// Session status as shown in the UI (`Working`, `NeedsInput`, ...).
+    pub status: String,
+    pub message: String,
+    pub needs_input_prompt: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// A session's timeline, oldest transition first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionHistory {
+    pub session_id: u32,
+    pub project_path: String,
+    pub transitions: VecDeque<StatusTransition>,
+    /// When the session ended, or `None` while it is running.
+    pub ended_at: Option<u64>,
+}
+
+/// What a session did in a window of time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionActivity {
+    pub session_id: u32,
+    /// Transitions inside the window, preceded by the one in effect at its
+    /// start.
+    pub transitions: Vec<StatusTransition>,
+    /// Milliseconds spent in each status within the window.
+    pub time_in_status: BTreeMap<String, u64>,
+    pub ended_at: Option<u64>,
+}
+
+impl SessionHistory {
+    fn new(session_id: u32, project_path: &str) -> Self {
+        Self {
+            session_id,
+            project_path: project_path.to_string(),
+            transitions: VecDeque::new(),
+            ended_at: None,
+        }
+    }
+
+    fn started_at(&self) -> Option<u64> {
+        self.transitions.front().map(|t| t.timestamp)
+    }
+
+    /// Activity from `since` until the session ended, or until `now`.
+    pub fn activity(&self, since: u64, now: u64) -> SessionActivity {
+        let end = self.ended_at.unwrap_or(now);
+        // The transition in effect at `since` counts from `since` on.
+        let first = self
+            .transitions
+            .iter()
+            .rposition(|t| t.timestamp <= since)
+            .unwrap_or(0);
+        let transitions: Vec<StatusTransition> =
+            self.transitions.iter().skip(first).cloned().collect();
+
+        let mut time_in_status = BTreeMap::new();
+        for (i, transition) in transitions.iter().enumerate() {
+            let from = transition.timestamp.max(since);
+            let to = transitions
+                .get(i + 1)
+                .map_or(end, |next| next.timestamp)
+                .min(end);
+            *time_in_status.entry(transition.status.clone()).or_insert(0) +=
+                to.saturating_sub(from);
+        }
+
+        SessionActivity {
+            session_id: self.session_id,
+            transitions,
+            time_in_status,
+            ended_at: self.ended_at,
+        }
+    }
+}
+
+/// Timelines of the live sessions and the most recently ended ones.
+#[derive(Debug, Default)]
+pub struct StatusHistory {
+    sessions: HashMap<u32, SessionHistory>,
+    /// Ended sessions, oldest first, for eviction.
+    ended: VecDeque<u32>,
+    /// Live sessions whose timeline changed since `take_changed`.
+    changed: HashSet<u32>,
+}
+
+impl StatusHistory {
+    /// Records a transition. Repeats of the current status and message are
+    /// ignored, so periodic reports don't flood the timeline.
+    pub fn record(&mut self, session_id: u32, project_path: &str, transition: StatusTransition) {
+        // A reused session ID starts a new timeline
+        if self
+            .sessions
+            .get(&session_id)
+            .is_some_and(|h| h.ended_at.is_some())
+        {
+            self.sessions.remove(&session_id);
+            self.ended.retain(|id| *id != session_id);
+        }
+        let history = self
+            .sessions
+            .entry(session_id)
+            .or_insert_with(|| SessionHistory::new(session_id, project_path));
+
+        let unchanged = history.transitions.back().is_some_and(|last| {
+            last.status == transition.status
+                && last.message == transition.message
+                && last.needs_input_prompt == transition.needs_input_prompt
+        });
+        if unchanged {
+            return;
+        }
+        if history.transitions.len() == MAX_TRANSITIONS {
+            history.transitions.pop_front();
+        }
+        history.transitions.push_back(transition);
+        self.changed.insert(session_id);
+    }
+
+    /// Timelines of live sessions that changed since the last call.
+    pub fn take_changed(&mut self) -> Vec<SessionHistory> {
+        self.changed
+            .drain()
+            .filter_map(|id| self.sessions.get(&id))
+            .filter(|h| h.ended_at.is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Marks a session ended and returns its timeline, if it reported
+    /// anything.
+    pub fn end(&mut self, session_id: u32, now: u64) -> Option<SessionHistory> {
+        let history = self.sessions.get_mut(&session_id)?;
+        if history.ended_at.is_some() {
+            return None;
+        }
+        history.ended_at = Some(now);
+        let ended = history.clone();
+        self.changed.remove(&session_id);
+
+        self.ended.push_back(session_id);
+        while self.ended.len() > MAX_ENDED_SESSIONS {
+            if let Some(oldest) = self.ended.pop_front() {
+                self.sessions.remove(&oldest);
+            }
+        }
+        Some(ended)
+    }
+
+    pub fn session(&self, session_id: u32) -> Option<&SessionHistory> {
+        self.sessions.get(&session_id)
+    }
+
+    /// Timelines of a project's sessions, by session ID.
+    pub fn project(&self, project_path: &str) -> Vec<SessionHistory> {
+        let mut histories: Vec<SessionHistory> = self
+            .sessions
+            .values()
+            .filter(|h| h.project_path == project_path)
+            .cloned()
+            .collect();
+        histories.sort_by_key(|h| h.session_id);
+        histories
+    }
+}
+
+/// Activity of a project's sessions since `since`, merging the in-memory
+/// timelines with `persisted` ones from earlier runs. Sessions that ended
+/// before `since` are left out. A persisted timeline that never ended
+/// belongs to a run that didn't shut down cleanly; it counts as ended at
+/// its last transition.
+pub fn project_activity(
+    live: Vec<SessionHistory>,
+    persisted: Vec<SessionHistory>,
+    since: u64,
+    now: u64,
+) -> Vec<SessionActivity> {
+    let mut histories = live;
+    for mut history in persisted {
+        let duplicate = histories
+            .iter()
+            .any(|h| h.session_id == history.session_id && h.started_at() == history.started_at());
+        if !duplicate {
+            if history.ended_at.is_none() {
+                history.ended_at = history.transitions.back().map(|t| t.timestamp);
+            }
+            histories.push(history);
+        }
+    }
+    histories.retain(|h| h.ended_at.is_none_or(|ended| ended >= since));
+    histories.sort_by_key(|h| (h.started_at(), h.session_id));
+    histories.iter().map(|h| h.activity(since, now)).collect()
+}
+
+/// Timelines persisted in a project's store, oldest first.
+pub fn load_persisted(app: &AppHandle, project_path: &str) -> Vec<SessionHistory> {
+    let Ok(store) = app.store(StatusServer::project_store_name(project_path)) else {
+        return Vec::new();
+    };
+    store
+        .get(STATUS_HISTORY_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Writes a session's timeline to its project's store, replacing what was
+/// written for it before.
+pub fn persist(app: &AppHandle, history: SessionHistory) -> Result<(), String> {
+    let mut histories = load_persisted(app, &history.project_path);
+    let store = app
+        .store(StatusServer::project_store_name(&history.project_path))
+        .map_err(|e| e.to_string())?;
+    histories
+        .retain(|h| h.session_id != history.session_id || h.started_at() != history.started_at());
+    histories.push(history);
+    if histories.len() > MAX_PERSISTED_SESSIONS {
+        histories.drain(..histories.len() - MAX_PERSISTED_SESSIONS);
+    }
+    let json = serde_json::to_value(&histories).map_err(|e| e.to_string())?;
+    store.set(STATUS_HISTORY_KEY, json);
+    store.save().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(status: &str, message: &str, timestamp: u64) -> StatusTransition {
+        StatusTransition {
+            status: status.to_string(),
+            message: message.to_string(),
+            needs_input_prompt: None,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_record_skips_repeats_and_is_bounded() {
+        let mut history = StatusHistory::default();
+        history.record(1, "/p", transition("Working", "a", 0));
+        history.record(1, "/p", transition("Working", "a", 5));
+        history.record(1, "/p", transition("Working", "b", 10));
+        assert_eq!(history.session(1).unwrap().transitions.len(), 2);
+
+        for i in 0..MAX_TRANSITIONS as u64 {
+            history.record(1, "/p", transition("Idle", &i.to_string(), 20 + i));
+        }
+        let transitions = &history.session(1).unwrap().transitions;
+        assert_eq!(transitions.len(), MAX_TRANSITIONS);
+        assert_eq!(transitions.front().unwrap().message, "0");
+    }
+
+    #[test]
+    fn test_activity_durations() {
+        let mut history = StatusHistory::default();
+        history.record(1, "/p", transition("Idle", "", 0));
+        history.record(1, "/p", transition("Working", "", 100));
+        history.record(1, "/p", transition("NeedsInput", "", 400));
+        history.record(1, "/p", transition("Working", "", 500));
+
+        let activity = history.session(1).unwrap().activity(200, 1000);
+        // Starts with the status in effect at `since`
+        assert_eq!(activity.transitions.len(), 3);
+        assert_eq!(activity.time_in_status["Working"], 200 + 500);
+        assert_eq!(activity.time_in_status["NeedsInput"], 100);
+        assert!(!activity.time_in_status.contains_key("Idle"));
+
+        // Ending the session stops the clock
+        let ended = history.end(1, 600).unwrap();
+        assert_eq!(ended.activity(0, 1000).time_in_status["Working"], 300 + 100);
+        assert!(history.end(1, 700).is_none());
+    }
+
+    #[test]
+    fn test_ended_sessions_are_evicted() {
+        let mut history = StatusHistory::default();
+        for id in 0..=MAX_ENDED_SESSIONS as u32 {
+            history.record(id, "/p", transition("Working", "", 0));
+            history.end(id, 1);
+        }
+        assert!(history.session(0).is_none());
+        assert!(history.session(1).is_some());
+
+        // A reused ID starts over
+        history.record(1, "/p", transition("Idle", "", 5));
+        let restarted = history.session(1).unwrap();
+        assert_eq!(restarted.transitions.len(), 1);
+        assert_eq!(restarted.ended_at, None);
+    }
+
+    #[test]
+    fn test_project_activity_merges_persisted() {
+        let mut history = StatusHistory::default();
+        history.record(1, "/p", transition("Working", "", 100));
+        history.record(2, "/other", transition("Working", "", 100));
+        history.end(1, 200);
+
+        let mut old = SessionHistory::new(1, "/p");
+        old.transitions.push_back(transition("Idle", "", 10));
+        old.ended_at = Some(50);
+        let persisted = vec![old, history.session(1).unwrap().clone()];
+
+        let activity = project_activity(history.project("/p"), persisted.clone(), 0, 300);
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].time_in_status["Idle"], 40);
+        assert_eq!(activity[1].time_in_status["Working"], 100);
+
+        // Sessions that ended before the window are left out
+        assert_eq!(
+            project_activity(history.project("/p"), persisted, 60, 300).len(),
+            1
+        );
+    }
+    #[test]
+    fn test_take_changed_returns_live_sessions_once() {
+        let mut history = StatusHistory::default();
+        history.record(1, "/p", transition("Working", "", 0));
+        history.record(2, "/p", transition("Working", "", 0));
+        history.end(2, 10);
+
+        let changed = history.take_changed();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].session_id, 1);
+        assert!(history.take_changed().is_empty());
+
+        // Repeats don't count as changes
+        history.record(1, "/p", transition("Working", "", 20));
+        assert!(history.take_changed().is_empty());
+        history.record(1, "/p", transition("Idle", "", 30));
+        assert_eq!(history.take_changed().len(), 1);
+    }
+
+    #[test]
+    fn test_unfinished_persisted_timeline_ends_at_last_transition() {
+        let mut crashed = SessionHistory::new(3, "/p");
+        crashed.transitions.push_back(transition("Working", "", 10));
+        crashed.transitions.push_back(transition("Idle", "", 40));
+
+        let activity = project_activity(Vec::new(), vec![crashed], 0, 1000);
+        assert_eq!(activity[0].ended_at, Some(40));
+        assert_eq!(activity[0].time_in_status["Working"], 30);
+        assert_eq!(activity[0].time_in_status["Idle"], 0);
+    }
+}
@@ -3,19 +3,81 @@
 //! Replaces the file-polling approach with an HTTP endpoint that receives
 //! status updates from the Rust MCP server. Provides real-time updates
 //! and eliminates race conditions.
+//!
+//! Every registered session gets its own secret token, handed to its MCP
+//! server through the `maestro-status` env block. Requests must carry it as
+//! `Authorization: Bearer <token>`, so other local processes cannot spoof a
+//! session's status or read its replies.
+//!
+//! Sessions of the same project can also message each other through
+//! per-session mailboxes, so a lead agent can hand work to others and
+//! collect their results. Sessions only ever see peers registered by this
+//! instance for the same project.
+//!
+//! Routes:
+//! - `GET /instance` - this instance's ID, so an MCP server can find the
+//!   Maestro that started it after it moved to another port
+//! - `POST /status` - report a session's state; `410 Gone` means the
+//!   request reached another Maestro instance, `409 Conflict` that the
+//!   session is not registered (yet)
+//! - `GET /sessions/{session_id}/status` - the last reported state
+//! - `GET /sessions/{session_id}/replies[?wait=<secs>]` - drain the user's
+//!   replies to `needs_input` prompts, long-polling up to `wait` seconds
+//!   while none are queued
+//! - `GET /sessions/{session_id}/peers` - other sessions of the project
+//! - `POST /sessions/{session_id}/messages` - message a peer
+//! - `GET /sessions/{session_id}/messages[?wait=<secs>]` - drain the inbox,
+//!   long-polling like `/replies`
+//! - `GET|POST /sessions/{session_id}/tasks` - list or add tasks on the
+//!   project's task board
+//! - `POST /sessions/{session_id}/tasks/claim` - claim a task
+//! - `POST /sessions/{session_id}/tasks/{task_id}/complete|fail` - finish a
+//!   claimed task
+//!
+//! By default the server takes the first free port of 127.0.0.1:9900-9999.
+//! `StatusServerConfig` reads overrides from the environment, including a
+//! Unix domain socket mode where the status URL handed to MCP servers is
+//! `unix:<socket path>`.
 
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use axum::{
-    extract::State,
-    http::StatusCode,
-    routing::post,
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tauri::{AppHandle, Emitter};
-use tokio::sync::RwLock;
+use tauri::{AppHandle, Emitter, Manager};
+use thiserror::Error;
+use tokio::sync::{Notify, RwLock};
+
+use super::notifications::Notifier;
+use super::session_manager::{AiMode, SessionManager};
+use super::status_history::{
+    self, SessionActivity, SessionHistory, StatusHistory, StatusTransition,
+};
+use super::status_detector::StatusInference;
+use super::status_events;
+use super::task_board::{Task, TaskBoardError, TaskBoards};
+
+/// Longest a `GET /replies` or `GET /messages` long-poll may wait before
+/// returning empty.
+pub const MAX_POLL_WAIT: Duration = Duration::from_secs(60);
+
+/// Longest message one session may send another, in bytes.
+pub const MAX_MESSAGE_LEN: usize = 16 * 1024;
+
+/// Unread messages a mailbox holds before further sends are refused.
+pub const MAX_INBOX_LEN: usize = 256;
+
+/// Most plan steps, or artifact files, one progress report may carry.
+pub const MAX_PROGRESS_ITEMS: usize = 100;
 
 /// Status payload received from MCP server.
 #[derive(Debug, Deserialize)]
@@ -25,6 +87,10 @@ pub struct StatusRequest {
     pub state: String,
     pub message: String,
     pub needs_input_prompt: Option<String>,
+    /// Set by `maestro_ask_user`: the agent waits for an answer through
+    /// `/replies`.
+    #[serde(default)]
+    pub awaiting_reply: bool,
     #[allow(dead_code)]
     pub timestamp: String,
 }
@@ -37,34 +103,511 @@ pub struct SessionStatusPayload {
     pub status: String,
     pub message: String,
     pub needs_input_prompt: Option<String>,
+    /// Whether the agent asked through `maestro_ask_user` and waits for an
+    /// answer from the UI, rather than just looking like it needs input.
+    pub awaiting_reply: bool,
+}
+
+/// Identifies the Maestro instance behind a status server, as returned by
+/// `GET /instance`.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstanceInfo {
+    pub instance_id: String,
+}
+
+/// The last status a session's MCP server reported, as returned by
+/// `GET /sessions/{session_id}/status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportedStatus {
+    /// MCP state (`idle`, `working`, `needs_input`, ...), `None` until the
+    /// first report.
+    pub state: Option<String>,
+    pub message: Option<String>,
+    pub needs_input_prompt: Option<String>,
+    /// Replies waiting to be read from `/replies`.
+    pub pending_replies: usize,
+}
+
+/// A user's answer to a `needs_input` prompt, queued until the session's
+/// MCP server reads it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PromptReply {
+    /// The prompt that was open when the user answered, if any.
+    pub prompt: Option<String>,
+    pub reply: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// A message from one session to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgentMessage {
+    pub id: u64,
+    pub from_session: u32,
+    pub text: String,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Body of `POST /sessions/{session_id}/messages`.
+#[derive(Debug, Deserialize)]
+struct SendMessageRequest {
+    instance_id: String,
+    to_session: u32,
+    text: String,
+}
+
+/// Response to a successful send.
+#[derive(Debug, Serialize)]
+struct SendMessageResponse {
+    message_id: u64,
+}
+
+/// Another session of the same project, as listed by
+/// `GET /sessions/{session_id}/peers`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerSession {
+    pub session_id: u32,
+    pub mode: Option<AiMode>,
+    pub branch: Option<String>,
+    /// Session status as shown in the UI (`Working`, `NeedsInput`, ...).
+    pub status: Option<String>,
+    /// The peer's last reported status message.
+    pub message: Option<String>,
+}
+
+/// Payload of the `session-message-sent` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMessagePayload {
+    pub project_path: String,
+    pub from_session: u32,
+    pub to_session: u32,
+    pub text: String,
+}
+
+/// Files and links an agent has produced so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProgressArtifacts {
+    /// Files touched, relative to the worktree.
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub pr_url: Option<String>,
+}
+
+/// Body of `POST /sessions/{session_id}/progress`.
+#[derive(Debug, Deserialize)]
+struct ProgressRequest {
+    instance_id: String,
+    steps: Vec<String>,
+    current_step: usize,
+    percent: Option<u8>,
+    #[serde(default)]
+    artifacts: ProgressArtifacts,
+}
+
+/// The latest plan and position an agent reported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionProgress {
+    pub steps: Vec<String>,
+    /// Index into `steps` of the step being worked on. Equal to
+    /// `steps.len()` once every step is done.
+    pub current_step: usize,
+    /// Percent complete, 0-100, when the agent can tell.
+    pub percent: Option<u8>,
+    pub artifacts: ProgressArtifacts,
+    /// Milliseconds since the Unix epoch.
+    pub updated_at: u64,
+}
+
+impl SessionProgress {
+    /// Validates a report, explaining what is wrong with it.
+    fn from_request(request: ProgressRequest) -> Result<Self, String> {
+        if request.steps.len() > MAX_PROGRESS_ITEMS
+            || request.artifacts.files.len() > MAX_PROGRESS_ITEMS
+        {
+            return Err(format!(
+                "At most {} steps and {} files per report",
+                MAX_PROGRESS_ITEMS, MAX_PROGRESS_ITEMS
+            ));
+        }
+        let size: usize = request
+            .steps
+            .iter()
+            .chain(&request.artifacts.files)
+            .chain(&request.artifacts.pr_url)
+            .map(String::len)
+            .sum();
+        if size > MAX_MESSAGE_LEN {
+            return Err(format!("Progress report exceeds {} bytes", MAX_MESSAGE_LEN));
+        }
+        if request.current_step > request.steps.len() {
+            return Err(format!(
+                "current_step {} is past the last of {} steps",
+                request.current_step,
+                request.steps.len()
+            ));
+        }
+        if request.percent.is_some_and(|p| p > 100) {
+            return Err("percent must be between 0 and 100".to_string());
+        }
+        Ok(Self {
+            steps: request.steps,
+            current_step: request.current_step,
+            percent: request.percent,
+            artifacts: request.artifacts,
+            updated_at: now_millis(),
+        })
+    }
+}
+
+/// Payload of the `session-progress-changed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionProgressPayload {
+    pub session_id: u32,
+    pub project_path: String,
+    pub progress: SessionProgress,
+}
+
+/// Per-session state kept by the server.
+#[derive(Debug)]
+struct RegisteredSession {
+    project_path: String,
+    token: String,
+    last_status: Option<StatusRequest>,
+    progress: Option<SessionProgress>,
+    replies: VecDeque<PromptReply>,
+    inbox: VecDeque<AgentMessage>,
+    /// Wakes long-polling `GET /replies` and `GET /messages` requests when
+    /// something is queued for the session.
+    wakeup: Arc<Notify>,
+}
+
+/// Registered sessions, keyed by session ID.
+#[derive(Debug, Default)]
+struct SessionRegistry {
+    sessions: HashMap<u32, RegisteredSession>,
+    next_message_id: u64,
+}
+
+impl SessionRegistry {
+    /// Registers a session and returns its token. Re-registering keeps the
+    /// existing token so an already running MCP server stays authorized.
+    fn register(&mut self, session_id: u32, project_path: &str) -> String {
+        let session = self
+            .sessions
+            .entry(session_id)
+            .or_insert_with(|| RegisteredSession {
+                project_path: String::new(),
+                token: uuid::Uuid::new_v4().simple().to_string(),
+                last_status: None,
+                progress: None,
+                replies: VecDeque::new(),
+                inbox: VecDeque::new(),
+                wakeup: Arc::new(Notify::new()),
+            });
+        session.project_path = project_path.to_string();
+        session.token.clone()
+    }
+
+    /// Looks up a session and checks the request's bearer token against it.
+    fn authorize(
+        &mut self,
+        session_id: u32,
+        headers: &HeaderMap,
+    ) -> Result<&mut RegisteredSession, StatusCode> {
+        let session = self
+            .sessions
+            .get_mut(&session_id)
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        match bearer_token(headers) {
+            Some(token) if tokens_match(&session.token, token) => Ok(session),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+
+    fn push_reply(&mut self, session_id: u32, reply: String) -> bool {
+        let Some(session) = self.sessions.get_mut(&session_id) else {
+            return false;
+        };
+        let prompt = session
+            .last_status
+            .as_ref()
+            .and_then(|status| status.needs_input_prompt.clone());
+        session.replies.push_back(PromptReply {
+            prompt,
+            reply,
+            timestamp: now_millis(),
+        });
+        session.wakeup.notify_waiters();
+        true
+    }
+
+    /// IDs of the other sessions registered for the same project.
+    fn peers(&self, session_id: u32) -> Vec<u32> {
+        let Some(project_path) = self.sessions.get(&session_id).map(|s| &s.project_path) else {
+            return Vec::new();
+        };
+        let mut peers: Vec<u32> = self
+            .sessions
+            .iter()
+            .filter(|(id, s)| **id != session_id && &s.project_path == project_path)
+            .map(|(id, _)| *id)
+            .collect();
+        peers.sort_unstable();
+        peers
+    }
+
+    /// Queues a message in a peer's inbox. Returns the message ID.
+    ///
+    /// Fails with `NOT_FOUND` if the recipient isn't a peer of the sender
+    /// (so other projects' sessions can't be probed), `PAYLOAD_TOO_LARGE`
+    /// for overlong text and `TOO_MANY_REQUESTS` when the inbox is full.
+    fn send_message(&mut self, from: u32, to: u32, text: String) -> Result<u64, StatusCode> {
+        if text.len() > MAX_MESSAGE_LEN {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        if !self.peers(from).contains(&to) {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        self.next_message_id += 1;
+        let id = self.next_message_id;
+        let recipient = self.sessions.get_mut(&to).ok_or(StatusCode::NOT_FOUND)?;
+        if recipient.inbox.len() >= MAX_INBOX_LEN {
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+        recipient.inbox.push_back(AgentMessage {
+            id,
+            from_session: from,
+            text,
+            timestamp: now_millis(),
+        });
+        recipient.wakeup.notify_waiters();
+        Ok(id)
+    }
+}
+
+/// Drains one of a session's queues, waiting up to `wait` for something to
+/// arrive if it is empty. Returns an empty list on timeout.
+async fn wait_for_queue<T>(
+    registry: &RwLock<SessionRegistry>,
+    session_id: u32,
+    headers: &HeaderMap,
+    wait: Duration,
+    queue: fn(&mut RegisteredSession) -> &mut VecDeque<T>,
+) -> Result<Vec<T>, StatusCode> {
+    let deadline = tokio::time::Instant::now() + wait.min(MAX_POLL_WAIT);
+    loop {
+        let wakeup;
+        let notified;
+        {
+            let mut registry = registry.write().await;
+            let session = registry.authorize(session_id, headers)?;
+            if !queue(session).is_empty() || tokio::time::Instant::now() >= deadline {
+                return Ok(queue(session).drain(..).collect());
+            }
+            wakeup = session.wakeup.clone();
+            // Created while the lock is held, so anything queued right after
+            // it's released still wakes us
+            notified = wakeup.notified();
+        }
+        let _ = tokio::time::timeout_at(deadline, notified).await;
+    }
+}
+
+/// Extracts the token from an `Authorization: Bearer <token>` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Compares tokens without short-circuiting on the first differing byte.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Env var overriding the address the server binds to.
+pub const BIND_ENV: &str = "MAESTRO_STATUS_BIND";
+
+/// Env var overriding the port range, e.g. `9900-9999` or `9900`.
+pub const PORTS_ENV: &str = "MAESTRO_STATUS_PORTS";
+
+/// Env var switching the server to a Unix domain socket at the given path.
+pub const SOCKET_ENV: &str = "MAESTRO_STATUS_SOCKET";
+
+/// Where the status server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusServerConfig {
+    pub bind_address: IpAddr,
+    /// First and last port to try, inclusive.
+    pub port_range: (u16, u16),
+    /// Listen on this Unix domain socket instead of a TCP port.
+    pub unix_socket: Option<PathBuf>,
+}
+
+impl Default for StatusServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port_range: (9900, 9999),
+            unix_socket: None,
+        }
+    }
+}
+
+impl StatusServerConfig {
+    /// The defaults, overridden by `MAESTRO_STATUS_BIND`,
+    /// `MAESTRO_STATUS_PORTS` and `MAESTRO_STATUS_SOCKET`.
+    pub fn from_env() -> Result<Self, StatusServerError> {
+        Self::from_vars(|name| std::env::var(name).ok().filter(|v| !v.trim().is_empty()))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, StatusServerError> {
+        let mut config = Self::default();
+        if let Some(bind) = var(BIND_ENV) {
+            config.bind_address = bind.trim().parse().map_err(|_| {
+                StatusServerError::InvalidConfig(format!(
+                    "{} must be an IP address, got '{}'",
+                    BIND_ENV, bind
+                ))
+            })?;
+        }
+        if let Some(ports) = var(PORTS_ENV) {
+            config.port_range = parse_port_range(&ports).ok_or_else(|| {
+                StatusServerError::InvalidConfig(format!(
+                    "{} must be a port or a range like 9900-9999, got '{}'",
+                    PORTS_ENV, ports
+                ))
+            })?;
+        }
+        config.unix_socket = var(SOCKET_ENV).map(PathBuf::from);
+        Ok(config)
+    }
+}
+
+fn parse_port_range(value: &str) -> Option<(u16, u16)> {
+    let (start, end) = match value.split_once('-') {
+        Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+        None => {
+            let port = value.trim().parse().ok()?;
+            (port, port)
+        }
+    };
+    (start > 0 && start <= end).then_some((start, end))
+}
+
+/// Why the status server could not start.
+#[derive(Debug, Error)]
+pub enum StatusServerError {
+    #[error("No free port for the status server on {address} in {start}-{end}")]
+    NoFreePort { address: IpAddr, start: u16, end: u16 },
+
+    #[error("Invalid status server configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Status server socket {} is already in use", .0.display())]
+    SocketInUse(PathBuf),
+
+    #[error("Failed to bind status server socket {}: {source}", path.display())]
+    Socket {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Unix domain sockets are not supported on this platform")]
+    Unsupported,
+}
+
+/// Address the server is listening on.
+#[derive(Debug, Clone)]
+enum ListenAddress {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
 }
 
 /// State shared with the HTTP handler.
 struct ServerState {
     app_handle: AppHandle,
     instance_id: String,
-    /// Maps session_id -> project_path for routing status updates
-    session_projects: Arc<RwLock<std::collections::HashMap<u32, String>>>,
+    /// Registered sessions, used for routing status updates and auth
+    registry: Arc<RwLock<SessionRegistry>>,
 }
 
 /// HTTP status server that receives status updates from MCP servers.
 pub struct StatusServer {
-    port: u16,
+    address: ListenAddress,
     instance_id: String,
-    session_projects: Arc<RwLock<std::collections::HashMap<u32, String>>>,
+    registry: Arc<RwLock<SessionRegistry>>,
+    /// Every status transition; outlives the sessions themselves.
+    history: Arc<std::sync::Mutex<StatusHistory>>,
+    app_handle: AppHandle,
 }
 
 impl StatusServer {
     /// Find and bind to an available port in the given range.
     /// Returns the bound listener to avoid race conditions.
-    async fn find_and_bind_port(range_start: u16, range_end: u16) -> Option<(u16, tokio::net::TcpListener)> {
+    async fn find_and_bind_port(
+        address: IpAddr,
+        range_start: u16,
+        range_end: u16,
+    ) -> Result<tokio::net::TcpListener, StatusServerError> {
         for port in range_start..=range_end {
-            let addr = format!("127.0.0.1:{}", port);
-            if let Ok(listener) = tokio::net::TcpListener::bind(&addr).await {
-                return Some((port, listener));
+            if let Ok(listener) = tokio::net::TcpListener::bind((address, port)).await {
+                return Ok(listener);
             }
         }
-        None
+        Err(StatusServerError::NoFreePort {
+            address,
+            start: range_start,
+            end: range_end,
+        })
+    }
+
+    /// Bind the Unix domain socket at `path`, replacing a stale socket left
+    /// behind by an instance that did not shut down cleanly.
+    #[cfg(unix)]
+    async fn bind_socket(
+        path: &std::path::Path,
+    ) -> Result<tokio::net::UnixListener, StatusServerError> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        let socket_error = |source| StatusServerError::Socket {
+            path: path.to_path_buf(),
+            source,
+        };
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(StatusServerError::InvalidConfig(format!(
+                    "{} exists and is not a socket",
+                    path.display()
+                )));
+            }
+            if tokio::net::UnixStream::connect(path).await.is_ok() {
+                return Err(StatusServerError::SocketInUse(path.to_path_buf()));
+            }
+            std::fs::remove_file(path).map_err(socket_error)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path).map_err(socket_error)?;
+        // Only this user's processes may report status
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(socket_error)?;
+        Ok(listener)
     }
 
     /// Generate a stable hash for a project path.
@@ -76,46 +619,113 @@ impl StatusServer {
         hex::encode(&result[..6])
     }
 
+    /// Name of the `tauri-plugin-store` file holding a project's settings.
+    /// `project_path` must already be canonicalized.
+    pub fn project_store_name(project_path: &str) -> String {
+        format!("maestro-{}.json", Self::generate_project_hash(project_path))
+    }
+
     /// Start the HTTP status server.
     ///
-    /// Returns the server instance with the port it's listening on.
-    pub async fn start(app_handle: AppHandle, instance_id: String) -> Option<Self> {
-        // Find and bind in one step to avoid race conditions where another
-        // process grabs the port between checking and binding
-        let (port, listener) = Self::find_and_bind_port(9900, 9999).await?;
-        let session_projects = Arc::new(RwLock::new(std::collections::HashMap::new()));
+    /// Returns the server instance with the address it's listening on, or
+    /// why it could not bind one.
+    pub async fn start(
+        app_handle: AppHandle,
+        instance_id: String,
+        config: StatusServerConfig,
+    ) -> Result<Self, StatusServerError> {
+        let registry = Arc::new(RwLock::new(SessionRegistry::default()));
 
         let state = Arc::new(ServerState {
-            app_handle,
+            app_handle: app_handle.clone(),
             instance_id: instance_id.clone(),
-            session_projects: session_projects.clone(),
+            registry: registry.clone(),
         });
 
         let app = Router::new()
+            .route("/instance", get(handle_get_instance))
             .route("/status", post(handle_status))
+            .route("/sessions/{session_id}/status", get(handle_get_status))
+            .route("/sessions/{session_id}/replies", get(handle_get_replies))
+            .route(
+                "/sessions/{session_id}/progress",
+                get(handle_get_progress).post(handle_progress),
+            )
+            .route("/sessions/{session_id}/peers", get(handle_get_peers))
+            .route(
+                "/sessions/{session_id}/messages",
+                get(handle_get_messages).post(handle_send_message),
+            )
+            .route(
+                "/sessions/{session_id}/tasks",
+                get(handle_get_tasks).post(handle_create_task),
+            )
+            .route("/sessions/{session_id}/tasks/claim", post(handle_claim_task))
+            .route(
+                "/sessions/{session_id}/tasks/{task_id}/complete",
+                post(handle_complete_task),
+            )
+            .route("/sessions/{session_id}/tasks/{task_id}/fail", post(handle_fail_task))
             .with_state(state);
 
-        let addr = format!("127.0.0.1:{}", port);
-        eprintln!("[STATUS SERVER] Started on http://{}", addr);
-        eprintln!("[STATUS SERVER] Instance ID: {}", instance_id);
-
-        // Spawn the server in the background
-        tokio::spawn(async move {
-            if let Err(e) = axum::serve(listener, app).await {
-                eprintln!("[STATUS SERVER] Error: {}", e);
+        // Find and bind in one step to avoid race conditions where another
+        // process grabs the port between checking and binding
+        let address = match &config.unix_socket {
+            #[cfg(unix)]
+            Some(path) => {
+                let listener = Self::bind_socket(path).await?;
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(listener, app).await {
+                        eprintln!("[STATUS SERVER] Error: {}", e);
+                    }
+                });
+                ListenAddress::Unix(path.clone())
             }
-        });
+            #[cfg(not(unix))]
+            Some(_) => return Err(StatusServerError::Unsupported),
+            None => {
+                let (start, end) = config.port_range;
+                let listener = Self::find_and_bind_port(config.bind_address, start, end).await?;
+                let local_addr = listener.local_addr().map_err(|e| {
+                    StatusServerError::InvalidConfig(format!("Bound socket has no address: {}", e))
+                })?;
+                if !config.bind_address.is_loopback() {
+                    log::warn!(
+                        "Status server is listening on non-loopback address {}",
+                        local_addr
+                    );
+                }
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(listener, app).await {
+                        eprintln!("[STATUS SERVER] Error: {}", e);
+                    }
+                });
+                ListenAddress::Tcp(local_addr)
+            }
+        };
+
+        let history = Arc::new(std::sync::Mutex::new(StatusHistory::default()));
+        tokio::spawn(flush_history(app_handle.clone(), history.clone()));
 
-        Some(Self {
-            port,
+        let server = Self {
+            address,
             instance_id,
-            session_projects,
-        })
+            registry,
+            history,
+            app_handle,
+        };
+        eprintln!("[STATUS SERVER] Started on {}", server.status_url());
+        eprintln!("[STATUS SERVER] Instance ID: {}", server.instance_id);
+        Ok(server)
     }
 
-    /// Get the port the server is listening on.
-    pub fn port(&self) -> u16 {
-        self.port
+    /// Get the port the server is listening on, or `None` when it listens
+    /// on a Unix domain socket.
+    pub fn port(&self) -> Option<u16> {
+        match &self.address {
+            ListenAddress::Tcp(addr) => Some(addr.port()),
+            ListenAddress::Unix(_) => None,
+        }
     }
 
     /// Get the instance ID for this server.
@@ -125,39 +735,147 @@ impl StatusServer {
 
     /// Get the status URL for MCP servers to report to.
     pub fn status_url(&self) -> String {
-        format!("http://127.0.0.1:{}/status", self.port)
+        match &self.address {
+            ListenAddress::Tcp(addr) => {
+                // A wildcard bind is still reached over loopback
+                let mut addr = *addr;
+                if addr.ip().is_unspecified() {
+                    addr.set_ip(match addr {
+                        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        SocketAddr::V6(_) => IpAddr::V6(std::net::Ipv6Addr::LOCALHOST),
+                    });
+                }
+                format!("http://{}/status", addr)
+            }
+            ListenAddress::Unix(path) => format!("unix:{}", path.display()),
+        }
     }
 
     /// Register a session with its project path.
     /// This allows routing status updates to the correct project.
-    pub async fn register_session(&self, session_id: u32, project_path: &str) {
-        let mut projects = self.session_projects.write().await;
-        projects.insert(session_id, project_path.to_string());
+    ///
+    /// Returns the session's secret token, which its MCP server must send
+    /// as a bearer token. Registering an already known session keeps its
+    /// token.
+    pub async fn register_session(&self, session_id: u32, project_path: &str) -> String {
+        let token = self.registry.write().await.register(session_id, project_path);
         eprintln!(
             "[STATUS SERVER] Registered session {} for project '{}'",
             session_id,
             project_path
         );
+        token
     }
 
-    /// Unregister a session when it's killed.
+    /// Unregister a session when it's killed or exits.
     pub async fn unregister_session(&self, session_id: u32) {
-        let mut projects = self.session_projects.write().await;
-        if projects.remove(&session_id).is_some() {
+        let mut registry = self.registry.write().await;
+        let removed = registry.sessions.remove(&session_id);
+        drop(registry);
+        if let Some(session) = removed {
+            // Release any long-poll still waiting on this session
+            session.wakeup.notify_waiters();
             log::debug!("Unregistered session {}", session_id);
         }
+        let ended = self.history().end(session_id, now_millis());
+
+        if let Some(notifier) = self.app_handle.try_state::<Notifier>() {
+            notifier.forget(session_id);
+        }
+
+        if let Some(history) = ended {
+            persist_history(self.app_handle.clone(), vec![history]).await;
+        }
+    }
+
+    /// Records a session's new status in its timeline.
+    pub fn record_status(&self, payload: &SessionStatusPayload) {
+        self.history().record(
+            payload.session_id,
+            &payload.project_path,
+            StatusTransition {
+                status: payload.status.clone(),
+                message: payload.message.clone(),
+                needs_input_prompt: payload.needs_input_prompt.clone(),
+                timestamp: now_millis(),
+            },
+        );
+    }
+
+    fn history(&self) -> std::sync::MutexGuard<'_, StatusHistory> {
+        self.history.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue the user's reply to a session's `needs_input` prompt for its
+    /// MCP server to read. Returns `false` if the session isn't registered.
+    pub async fn push_reply(&self, session_id: u32, reply: String) -> bool {
+        self.registry.write().await.push_reply(session_id, reply)
+    }
+
+    /// The latest progress a session reported, if any.
+    pub async fn progress(&self, session_id: u32) -> Option<SessionProgress> {
+        let registry = self.registry.read().await;
+        registry.sessions.get(&session_id)?.progress.clone()
+    }
+
+    /// Every status a session moved to, oldest first. Sessions are
+    /// remembered for a while after they end.
+    pub async fn status_history(&self, session_id: u32) -> Option<SessionHistory> {
+        self.history().session(session_id).cloned()
+    }
+
+    /// What each of a project's sessions did since `since` (milliseconds
+    /// since the Unix epoch), including sessions from earlier runs.
+    pub async fn project_activity(&self, project_path: &str, since: u64) -> Vec<SessionActivity> {
+        let live = self.history().project(project_path);
+        let persisted = status_history::load_persisted(&self.app_handle, project_path);
+        status_history::project_activity(live, persisted, since, now_millis())
     }
 
     /// Get list of registered session IDs (for debugging).
     pub async fn registered_sessions(&self) -> Vec<u32> {
-        let projects = self.session_projects.read().await;
-        projects.keys().copied().collect()
+        let registry = self.registry.read().await;
+        registry.sessions.keys().copied().collect()
+    }
+}
+
+/// How often timelines of running sessions are written to their projects'
+/// stores, so a crash loses at most this much history.
+const HISTORY_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically persists the timelines that changed since the last flush.
+async fn flush_history(app: AppHandle, history: Arc<std::sync::Mutex<StatusHistory>>) {
+    let mut interval = tokio::time::interval(HISTORY_FLUSH_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let changed = history.lock().unwrap_or_else(|e| e.into_inner()).take_changed();
+        if !changed.is_empty() {
+            persist_history(app.clone(), changed).await;
+        }
+    }
+}
+
+/// Writes timelines to their projects' stores off the async runtime.
+async fn persist_history(app: AppHandle, histories: Vec<SessionHistory>) {
+    let saved = tokio::task::spawn_blocking(move || {
+        for history in histories {
+            let session_id = history.session_id;
+            if let Err(e) = status_history::persist(&app, history) {
+                log::warn!("Failed to persist status history of session {}: {}", session_id, e);
+            }
+        }
+    })
+    .await;
+    if let Err(e) = saved {
+        log::warn!("Status history flush panicked: {}", e);
     }
 }
 
 /// Handle incoming status POST requests.
 async fn handle_status(
     State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
     Json(payload): Json<StatusRequest>,
 ) -> StatusCode {
     eprintln!(
@@ -174,30 +892,40 @@ async fn handle_status(
             state.instance_id,
             payload.instance_id
         );
-        return StatusCode::OK;
+        return StatusCode::GONE;
     }
 
-    // Get the project path for this session
+    // Get the project path for this session, checking its token
     let project_path = {
-        let projects = state.session_projects.read().await;
+        let mut registry = state.registry.write().await;
         eprintln!(
             "[STATUS] Registered sessions: {:?}",
-            projects.keys().collect::<Vec<_>>()
+            registry.sessions.keys().collect::<Vec<_>>()
         );
-        projects.get(&payload.session_id).cloned()
-    };
-
-    let project_path = match project_path {
-        Some(p) => p,
-        None => {
+        if !registry.sessions.contains_key(&payload.session_id) {
             eprintln!(
                 "[STATUS] REJECTED - unknown session {}",
                 payload.session_id
             );
-            return StatusCode::OK;
+            return StatusCode::CONFLICT;
+        }
+        match registry.authorize(payload.session_id, &headers) {
+            Ok(session) => session.project_path.clone(),
+            Err(code) => {
+                eprintln!(
+                    "[STATUS] REJECTED - bad token for session {}",
+                    payload.session_id
+                );
+                return code;
+            }
         }
     };
 
+    // The agent reports for itself, so hold off on guessing from its output
+    if let Some(inference) = state.app_handle.try_state::<StatusInference>() {
+        inference.note_reported(payload.session_id);
+    }
+
     // Map MCP state to session status string
     let status = match payload.state.as_str() {
         "idle" => "Idle",
@@ -222,24 +950,413 @@ async fn handle_status(
         session_id: payload.session_id,
         project_path,
         status: status.to_string(),
-        message: payload.message,
-        needs_input_prompt: payload.needs_input_prompt,
+        message: payload.message.clone(),
+        needs_input_prompt: payload.needs_input_prompt.clone(),
+        awaiting_reply: payload.awaiting_reply,
     };
-
-    // Emit Tauri event immediately - no polling delay!
-    if let Err(e) = state.app_handle.emit("session-status-changed", &event_payload) {
-        eprintln!("[STATUS] EMIT FAILED: {}", e);
-    } else {
-        eprintln!("[STATUS] EMIT SUCCESS");
+    let session_id = payload.session_id;
+    {
+        let mut registry = state.registry.write().await;
+        if let Some(session) = registry.sessions.get_mut(&session_id) {
+            session.last_status = Some(payload);
+        }
     }
 
+    // Publish immediately - no polling delay!
+    status_events::publish(&state.app_handle, &event_payload);
+
     StatusCode::OK
 }
 
+/// Return this instance's ID.
+async fn handle_get_instance(State(state): State<Arc<ServerState>>) -> Json<InstanceInfo> {
+    Json(InstanceInfo {
+        instance_id: state.instance_id.clone(),
+    })
+}
+
+/// Return the last status a session reported.
+async fn handle_get_status(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Json<ReportedStatus>, StatusCode> {
+    let mut registry = state.registry.write().await;
+    let session = registry.authorize(session_id, &headers)?;
+    let last = session.last_status.as_ref();
+    Ok(Json(ReportedStatus {
+        state: last.map(|s| s.state.clone()),
+        message: last.map(|s| s.message.clone()),
+        needs_input_prompt: last.and_then(|s| s.needs_input_prompt.clone()),
+        pending_replies: session.replies.len(),
+    }))
+}
+
+/// Store a session's plan and position, and tell the UI.
+async fn handle_progress(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+    Json(payload): Json<ProgressRequest>,
+) -> Result<Json<SessionProgress>, (StatusCode, String)> {
+    if payload.instance_id != state.instance_id {
+        return Err((StatusCode::FORBIDDEN, "Wrong Maestro instance".to_string()));
+    }
+    let progress = SessionProgress::from_request(payload)
+        .map_err(|message| (StatusCode::UNPROCESSABLE_ENTITY, message))?;
+
+    let mut registry = state.registry.write().await;
+    let session = registry
+        .authorize(session_id, &headers)
+        .map_err(|code| (code, code.to_string()))?;
+    session.progress = Some(progress.clone());
+    let project_path = session.project_path.clone();
+    drop(registry);
+
+    let event_payload = SessionProgressPayload {
+        session_id,
+        project_path,
+        progress: progress.clone(),
+    };
+    if let Err(e) = state.app_handle.emit("session-progress-changed", &event_payload) {
+        log::warn!("Failed to emit session-progress-changed: {}", e);
+    }
+    Ok(Json(progress))
+}
+
+/// Return the latest progress a session reported, `null` before the first.
+async fn handle_get_progress(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Json<Option<SessionProgress>>, StatusCode> {
+    let mut registry = state.registry.write().await;
+    let session = registry.authorize(session_id, &headers)?;
+    Ok(Json(session.progress.clone()))
+}
+
+/// Query parameters for the long-polling `GET` routes.
+#[derive(Debug, Deserialize)]
+struct PollQuery {
+    /// Seconds to wait if nothing is queued (capped at `MAX_POLL_WAIT`).
+    /// Returns immediately when absent.
+    wait: Option<u64>,
+}
+
+/// Return and clear the replies queued for a session, long-polling if
+/// asked to.
+async fn handle_get_replies(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    Query(query): Query<PollQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PromptReply>>, StatusCode> {
+    let wait = Duration::from_secs(query.wait.unwrap_or(0));
+    wait_for_queue(&state.registry, session_id, &headers, wait, |s| &mut s.replies)
+        .await
+        .map(Json)
+}
+
+/// List the other sessions of the caller's project.
+async fn handle_get_peers(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PeerSession>>, StatusCode> {
+    let mut registry = state.registry.write().await;
+    registry.authorize(session_id, &headers)?;
+    let session_mgr = state.app_handle.try_state::<SessionManager>();
+
+    let peers = registry
+        .peers(session_id)
+        .into_iter()
+        .map(|id| {
+            let config = session_mgr.as_ref().and_then(|mgr| mgr.get_session(id));
+            let last_status = registry.sessions.get(&id).and_then(|s| s.last_status.as_ref());
+            PeerSession {
+                session_id: id,
+                mode: config.as_ref().map(|c| c.mode.clone()),
+                branch: config.as_ref().and_then(|c| c.branch.clone()),
+                status: config.as_ref().map(|c| c.status.as_str().to_string()),
+                message: last_status.map(|s| s.message.clone()),
+            }
+        })
+        .collect();
+    Ok(Json(peers))
+}
+
+/// Queue a message for another session of the same project.
+async fn handle_send_message(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+    Json(payload): Json<SendMessageRequest>,
+) -> Result<Json<SendMessageResponse>, StatusCode> {
+    let mut registry = state.registry.write().await;
+    let project_path = registry.authorize(session_id, &headers)?.project_path.clone();
+    if payload.instance_id != state.instance_id {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let message_id = registry.send_message(session_id, payload.to_session, payload.text.clone())?;
+    drop(registry);
+
+    let event_payload = SessionMessagePayload {
+        project_path,
+        from_session: session_id,
+        to_session: payload.to_session,
+        text: payload.text,
+    };
+    if let Err(e) = state.app_handle.emit("session-message-sent", &event_payload) {
+        log::warn!("Failed to emit session-message-sent: {}", e);
+    }
+    Ok(Json(SendMessageResponse { message_id }))
+}
+
+/// Return and clear a session's inbox, long-polling if asked to.
+async fn handle_get_messages(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    Query(query): Query<PollQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AgentMessage>>, StatusCode> {
+    let wait = Duration::from_secs(query.wait.unwrap_or(0));
+    wait_for_queue(&state.registry, session_id, &headers, wait, |s| &mut s.inbox)
+        .await
+        .map(Json)
+}
+
+/// Error response for the task routes: a status code and a message the
+/// agent can show.
+type TaskRouteError = (StatusCode, String);
+
+/// Authenticates a task request and returns the caller's project path
+/// along with the task boards.
+async fn task_context<'a>(
+    state: &'a ServerState,
+    session_id: u32,
+    headers: &HeaderMap,
+) -> Result<(String, tauri::State<'a, TaskBoards>), TaskRouteError> {
+    let project_path = state
+        .registry
+        .write()
+        .await
+        .authorize(session_id, headers)
+        .map_err(|code| (code, "Unauthorized".to_string()))?
+        .project_path
+        .clone();
+    let boards = state.app_handle.try_state::<TaskBoards>().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Task board unavailable".to_string(),
+    ))?;
+    Ok((project_path, boards))
+}
+
+fn task_error(error: TaskBoardError) -> TaskRouteError {
+    let code = match error {
+        TaskBoardError::NotFound(_) | TaskBoardError::NoOpenTasks => StatusCode::NOT_FOUND,
+        TaskBoardError::InvalidState { .. } => StatusCode::CONFLICT,
+        TaskBoardError::NotAssignee { .. } => StatusCode::FORBIDDEN,
+        TaskBoardError::InvalidText => StatusCode::BAD_REQUEST,
+        TaskBoardError::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (code, error.to_string())
+}
+
+/// Body of `POST /sessions/{session_id}/tasks`.
+#[derive(Debug, Deserialize)]
+struct CreateTaskRequest {
+    body: String,
+}
+
+/// Body of `POST /sessions/{session_id}/tasks/claim`.
+#[derive(Debug, Default, Deserialize)]
+struct ClaimTaskRequest {
+    /// Task to claim; the oldest open task when absent.
+    task_id: Option<u64>,
+}
+
+/// Body of the `complete` and `fail` task routes.
+#[derive(Debug, Default, Deserialize)]
+struct FinishTaskRequest {
+    result: Option<String>,
+}
+
+/// List the tasks on the caller's project board.
+async fn handle_get_tasks(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Task>>, TaskRouteError> {
+    let (project_path, boards) = task_context(&state, session_id, &headers).await?;
+    let board = boards
+        .load(&state.app_handle, &project_path)
+        .map_err(task_error)?;
+    Ok(Json(board.tasks))
+}
+
+/// Add a task to the caller's project board.
+async fn handle_create_task(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateTaskRequest>,
+) -> Result<Json<Task>, TaskRouteError> {
+    let (project_path, boards) = task_context(&state, session_id, &headers).await?;
+    boards
+        .update(&state.app_handle, &project_path, move |board| {
+            board.create(payload.body)
+        })
+        .await
+        .map(Json)
+        .map_err(task_error)
+}
+
+/// Claim a task for the caller.
+async fn handle_claim_task(
+    State(state): State<Arc<ServerState>>,
+    Path(session_id): Path<u32>,
+    headers: HeaderMap,
+    Json(payload): Json<ClaimTaskRequest>,
+) -> Result<Json<Task>, TaskRouteError> {
+    let (project_path, boards) = task_context(&state, session_id, &headers).await?;
+    boards
+        .update(&state.app_handle, &project_path, move |board| {
+            board.claim(session_id, payload.task_id)
+        })
+        .await
+        .map(Json)
+        .map_err(task_error)
+}
+
+/// Mark a task the caller claimed as done.
+async fn handle_complete_task(
+    State(state): State<Arc<ServerState>>,
+    Path((session_id, task_id)): Path<(u32, u64)>,
+    headers: HeaderMap,
+    Json(payload): Json<FinishTaskRequest>,
+) -> Result<Json<Task>, TaskRouteError> {
+    finish_task(&state, session_id, task_id, &headers, true, payload.result).await
+}
+
+/// Mark a task the caller claimed as failed.
+async fn handle_fail_task(
+    State(state): State<Arc<ServerState>>,
+    Path((session_id, task_id)): Path<(u32, u64)>,
+    headers: HeaderMap,
+    Json(payload): Json<FinishTaskRequest>,
+) -> Result<Json<Task>, TaskRouteError> {
+    finish_task(&state, session_id, task_id, &headers, false, payload.result).await
+}
+
+async fn finish_task(
+    state: &ServerState,
+    session_id: u32,
+    task_id: u64,
+    headers: &HeaderMap,
+    success: bool,
+    result: Option<String>,
+) -> Result<Json<Task>, TaskRouteError> {
+    let (project_path, boards) = task_context(state, session_id, headers).await?;
+    boards
+        .update(&state.app_handle, &project_path, move |board| {
+            board.finish(task_id, Some(session_id), success, result)
+        })
+        .await
+        .map(Json)
+        .map_err(task_error)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_from_vars() {
+        let vars = |values: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                values
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            StatusServerConfig::from_vars(vars(&[])).unwrap(),
+            StatusServerConfig::default()
+        );
+        let config = StatusServerConfig::from_vars(vars(&[
+            (BIND_ENV, "::1"),
+            (PORTS_ENV, "12000 - 12010"),
+            (SOCKET_ENV, "/tmp/maestro.sock"),
+        ]))
+        .unwrap();
+        assert_eq!(config.bind_address, "::1".parse::<IpAddr>().unwrap());
+        assert_eq!(config.port_range, (12000, 12010));
+        assert_eq!(config.unix_socket, Some(PathBuf::from("/tmp/maestro.sock")));
+        assert_eq!(
+            StatusServerConfig::from_vars(vars(&[(PORTS_ENV, "9950")]))
+                .unwrap()
+                .port_range,
+            (9950, 9950)
+        );
+
+        let invalid: [&'static [(&str, &str)]; 3] = [
+            &[(PORTS_ENV, "9999-9900")],
+            &[(PORTS_ENV, "0")],
+            &[(BIND_ENV, "localhost")],
+        ];
+        for bad in invalid {
+            assert!(matches!(
+                StatusServerConfig::from_vars(vars(bad)),
+                Err(StatusServerError::InvalidConfig(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocked_port_range_is_an_error() {
+        let taken = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = taken.local_addr().unwrap().port();
+        let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let err = StatusServer::find_and_bind_port(loopback, port, port)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StatusServerError::NoFreePort { start, end, .. } if start == port && end == port
+        ));
+        assert!(err.to_string().contains(&format!("{}-{}", port, port)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bind_socket_replaces_stale_socket() {
+        let dir = std::env::temp_dir().join(format!("maestro-status-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("status.sock");
+        let _ = std::fs::remove_file(&path);
+
+        // A socket nobody listens on is left over from a crash
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        let listener = StatusServer::bind_socket(&path).await.unwrap();
+
+        // A live one belongs to another instance
+        assert!(matches!(
+            StatusServer::bind_socket(&path).await,
+            Err(StatusServerError::SocketInUse(_))
+        ));
+        drop(listener);
+
+        std::fs::write(dir.join("file"), "").unwrap();
+        assert!(matches!(
+            StatusServer::bind_socket(&dir.join("file")).await,
+            Err(StatusServerError::InvalidConfig(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_generate_project_hash() {
         let hash = StatusServer::generate_project_hash("/Users/test/project");
@@ -253,4 +1370,192 @@ mod tests {
         let hash2 = StatusServer::generate_project_hash("/Users/test/project");
         assert_eq!(hash1, hash2);
     }
+
+    fn auth(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, format!("Bearer {token}").parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_register_keeps_token() {
+        let mut registry = SessionRegistry::default();
+        let token = registry.register(1, "/a");
+        assert_eq!(token.len(), 32);
+        assert_eq!(registry.register(1, "/b"), token);
+        assert_ne!(registry.register(2, "/b"), token);
+        assert_eq!(registry.sessions[&1].project_path, "/b");
+    }
+
+    #[test]
+    fn test_authorize() {
+        let mut registry = SessionRegistry::default();
+        let token = registry.register(1, "/a");
+        let other = registry.register(2, "/a");
+
+        assert!(registry.authorize(1, &auth(&token)).is_ok());
+        assert_eq!(
+            registry.authorize(1, &auth(&other)).unwrap_err(),
+            StatusCode::UNAUTHORIZED
+        );
+        assert!(registry.authorize(1, &HeaderMap::new()).is_err());
+        assert!(registry.authorize(3, &auth(&token)).is_err());
+    }
+
+    #[test]
+    fn test_replies_carry_open_prompt() {
+        let mut registry = SessionRegistry::default();
+        registry.register(1, "/a");
+        assert!(!registry.push_reply(2, "yes".into()));
+
+        registry.sessions.get_mut(&1).unwrap().last_status = Some(StatusRequest {
+            session_id: 1,
+            instance_id: "i".into(),
+            state: "needs_input".into(),
+            message: "Waiting".into(),
+            needs_input_prompt: Some("Deploy?".into()),
+            awaiting_reply: true,
+            timestamp: String::new(),
+        });
+        assert!(registry.push_reply(1, "yes".into()));
+
+        let session = registry.sessions.get_mut(&1).unwrap();
+        let replies: Vec<_> = session.replies.drain(..).collect();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].prompt.as_deref(), Some("Deploy?"));
+        assert_eq!(replies[0].reply, "yes");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_replies_wakes_on_reply() {
+        let registry = Arc::new(RwLock::new(SessionRegistry::default()));
+        let token = registry.write().await.register(1, "/a");
+
+        let waiter = {
+            let registry = registry.clone();
+            let headers = auth(&token);
+            tokio::spawn(async move {
+                wait_for_queue(&registry, 1, &headers, Duration::from_secs(10), |s| {
+                    &mut s.replies
+                })
+                .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(registry.write().await.push_reply(1, "go ahead".into()));
+
+        let replies = tokio::time::timeout(Duration::from_secs(2), waiter)
+            .await
+            .expect("long-poll should return once a reply arrives")
+            .unwrap()
+            .unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].reply, "go ahead");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_replies_times_out_empty() {
+        let registry = RwLock::new(SessionRegistry::default());
+        let token = registry.write().await.register(1, "/a");
+
+        let replies = wait_for_queue(&registry, 1, &auth(&token), Duration::from_millis(50), |s| {
+            &mut s.replies
+        })
+        .await
+        .unwrap();
+        assert!(replies.is_empty());
+        assert_eq!(
+            wait_for_queue(&registry, 1, &HeaderMap::new(), Duration::ZERO, |s| {
+                &mut s.replies
+            })
+            .await
+            .unwrap_err(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[test]
+    fn test_peers_are_scoped_to_project() {
+        let mut registry = SessionRegistry::default();
+        registry.register(1, "/a");
+        registry.register(2, "/a");
+        registry.register(3, "/a");
+        registry.register(4, "/b");
+
+        assert_eq!(registry.peers(1), vec![2, 3]);
+        assert!(registry.peers(4).is_empty());
+        assert!(registry.peers(9).is_empty());
+    }
+
+    #[test]
+    fn test_send_message() {
+        let mut registry = SessionRegistry::default();
+        registry.register(1, "/a");
+        registry.register(2, "/a");
+        registry.register(3, "/b");
+
+        let id = registry.send_message(1, 2, "take the API tests".into()).unwrap();
+        let inbox = &registry.sessions[&2].inbox;
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].id, id);
+        assert_eq!(inbox[0].from_session, 1);
+
+        // Other projects, unknown sessions and yourself are not peers
+        assert_eq!(registry.send_message(1, 3, "hi".into()), Err(StatusCode::NOT_FOUND));
+        assert_eq!(registry.send_message(1, 9, "hi".into()), Err(StatusCode::NOT_FOUND));
+        assert_eq!(registry.send_message(1, 1, "hi".into()), Err(StatusCode::NOT_FOUND));
+        assert_eq!(
+            registry.send_message(1, 2, "x".repeat(MAX_MESSAGE_LEN + 1)),
+            Err(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+    }
+
+    #[test]
+    fn test_send_message_refuses_when_inbox_full() {
+        let mut registry = SessionRegistry::default();
+        registry.register(1, "/a");
+        registry.register(2, "/a");
+        for _ in 0..MAX_INBOX_LEN {
+            registry.send_message(1, 2, "ping".into()).unwrap();
+        }
+        assert_eq!(
+            registry.send_message(1, 2, "ping".into()),
+            Err(StatusCode::TOO_MANY_REQUESTS)
+        );
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abc", "abd"));
+        assert!(!tokens_match("abc", "ab"));
+    }
+
+    fn progress_request(steps: &[&str], current_step: usize, percent: Option<u8>) -> ProgressRequest {
+        ProgressRequest {
+            instance_id: "instance".to_string(),
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            current_step,
+            percent,
+            artifacts: ProgressArtifacts::default(),
+        }
+    }
+
+    #[test]
+    fn test_progress_validation() {
+        let progress =
+            SessionProgress::from_request(progress_request(&["plan", "build"], 1, Some(50))).unwrap();
+        assert_eq!(progress.current_step, 1);
+        assert_eq!(progress.percent, Some(50));
+
+        // Pointing just past the last step means the plan is finished
+        assert!(SessionProgress::from_request(progress_request(&["plan"], 1, None)).is_ok());
+        assert!(SessionProgress::from_request(progress_request(&["plan"], 2, None)).is_err());
+        assert!(SessionProgress::from_request(progress_request(&[], 0, Some(101))).is_err());
+
+        let too_many = vec!["step"; MAX_PROGRESS_ITEMS + 1];
+        assert!(SessionProgress::from_request(progress_request(&too_many, 0, None)).is_err());
+        let huge = "x".repeat(MAX_MESSAGE_LEN + 1);
+        assert!(SessionProgress::from_request(progress_request(&[&huge], 0, None)).is_err());
+    }
 }
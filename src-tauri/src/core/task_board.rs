@@ -0,0 +1,462 @@
+//! Project-scoped task queue shared by the user and the project's agents.
+//!
+//! The user adds tasks from the UI; agents pick them up through the
+//! `maestro_claim_task`/`maestro_complete_task` MCP tools, which reach the
+//! board through the status server. Each project's board lives under the
+//! `task_board` key of its `maestro-<hash>.json` store, and every change is
+//! announced to the frontend as `task-board-changed`. Tasks a session still
+//! holds when it ends go back in the queue.
+
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+use thiserror::Error;
+
+use super::status_server::StatusServer;
+
+/// Store key holding a project's board.
+pub const TASK_BOARD_KEY: &str = "task_board";
+
+/// Longest task body or result accepted, in bytes.
+pub const MAX_TASK_TEXT_LEN: usize = 16 * 1024;
+
+/// Where a task is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Open,
+    Claimed,
+    Done,
+    Failed,
+}
+
+/// One unit of work on the board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: u64,
+    pub body: String,
+    pub status: TaskStatus,
+    /// Session that claimed the task; kept after it finishes.
+    pub assignee: Option<u32>,
+    /// Outcome reported on completion, or the reason it failed.
+    pub result: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum TaskBoardError {
+    #[error("Task {0} not found")]
+    NotFound(u64),
+
+    #[error("No open tasks to claim")]
+    NoOpenTasks,
+
+    #[error("Task {id} is {status:?}, not {expected:?}")]
+    InvalidState {
+        id: u64,
+        status: TaskStatus,
+        expected: TaskStatus,
+    },
+
+    #[error("Task {id} is assigned to session {assignee}, not session {session_id}")]
+    NotAssignee {
+        id: u64,
+        assignee: u32,
+        session_id: u32,
+    },
+
+    #[error("Task text must be non-empty and at most {MAX_TASK_TEXT_LEN} bytes")]
+    InvalidText,
+
+    #[error("Task board store error: {0}")]
+    Store(String),
+}
+
+impl serde::Serialize for TaskBoardError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A project's tasks, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskBoard {
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    next_id: u64,
+}
+
+impl TaskBoard {
+    /// Adds an open task.
+    pub fn create(&mut self, body: String) -> Result<Task, TaskBoardError> {
+        let body = validate_text(body)?;
+        self.next_id += 1;
+        let now = now_millis();
+        let task = Task {
+            id: self.next_id,
+            body,
+            status: TaskStatus::Open,
+            assignee: None,
+            result: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.tasks.push(task.clone());
+        Ok(task)
+    }
+
+    /// Assigns a task to a session: `task_id`, or the oldest open task when
+    /// `None`. Claiming a task the session already holds is a no-op.
+    pub fn claim(&mut self, session_id: u32, task_id: Option<u64>) -> Result<Task, TaskBoardError> {
+        let task = match task_id {
+            Some(id) => self.get_mut(id)?,
+            None => self
+                .tasks
+                .iter_mut()
+                .find(|t| t.status == TaskStatus::Open)
+                .ok_or(TaskBoardError::NoOpenTasks)?,
+        };
+        match task.status {
+            TaskStatus::Open => {}
+            TaskStatus::Claimed if task.assignee == Some(session_id) => return Ok(task.clone()),
+            status => {
+                return Err(TaskBoardError::InvalidState {
+                    id: task.id,
+                    status,
+                    expected: TaskStatus::Open,
+                })
+            }
+        }
+        task.status = TaskStatus::Claimed;
+        task.assignee = Some(session_id);
+        task.updated_at = now_millis();
+        Ok(task.clone())
+    }
+
+    /// Marks a task done (`success`) or failed, with an optional result.
+    ///
+    /// `by` is the reporting session; agents can only finish tasks they
+    /// claimed, while the user (`None`) can finish any open or claimed task.
+    pub fn finish(
+        &mut self,
+        task_id: u64,
+        by: Option<u32>,
+        success: bool,
+        result: Option<String>,
+    ) -> Result<Task, TaskBoardError> {
+        let result = result.map(validate_text).transpose()?;
+        let task = self.get_mut(task_id)?;
+        let expected = match by {
+            Some(_) => task.status == TaskStatus::Claimed,
+            None => matches!(task.status, TaskStatus::Open | TaskStatus::Claimed),
+        };
+        if !expected {
+            return Err(TaskBoardError::InvalidState {
+                id: task_id,
+                status: task.status,
+                expected: TaskStatus::Claimed,
+            });
+        }
+        if let Some(session_id) = by {
+            if task.assignee != Some(session_id) {
+                return Err(TaskBoardError::NotAssignee {
+                    id: task_id,
+                    assignee: task.assignee.unwrap_or_default(),
+                    session_id,
+                });
+            }
+        }
+        task.status = if success {
+            TaskStatus::Done
+        } else {
+            TaskStatus::Failed
+        };
+        task.result = result;
+        task.updated_at = now_millis();
+        Ok(task.clone())
+    }
+
+    /// Puts every task `session_id` holds back in the queue. Returns the
+    /// reopened tasks.
+    pub fn release(&mut self, session_id: u32) -> Vec<Task> {
+        let now = now_millis();
+        self.tasks
+            .iter_mut()
+            .filter(|t| t.status == TaskStatus::Claimed && t.assignee == Some(session_id))
+            .map(|task| {
+                task.status = TaskStatus::Open;
+                task.assignee = None;
+                task.updated_at = now;
+                task.clone()
+            })
+            .collect()
+    }
+
+    /// Puts a task back in the queue, unassigned.
+    pub fn reopen(&mut self, task_id: u64) -> Result<Task, TaskBoardError> {
+        let task = self.get_mut(task_id)?;
+        task.status = TaskStatus::Open;
+        task.assignee = None;
+        task.result = None;
+        task.updated_at = now_millis();
+        Ok(task.clone())
+    }
+
+    /// Removes a task from the board.
+    pub fn delete(&mut self, task_id: u64) -> Result<(), TaskBoardError> {
+        let index = self
+            .tasks
+            .iter()
+            .position(|t| t.id == task_id)
+            .ok_or(TaskBoardError::NotFound(task_id))?;
+        self.tasks.remove(index);
+        Ok(())
+    }
+
+    fn get_mut(&mut self, task_id: u64) -> Result<&mut Task, TaskBoardError> {
+        self.tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or(TaskBoardError::NotFound(task_id))
+    }
+}
+
+fn validate_text(text: String) -> Result<String, TaskBoardError> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_TASK_TEXT_LEN {
+        return Err(TaskBoardError::InvalidText);
+    }
+    Ok(trimmed.to_string())
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Payload of the `task-board-changed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskBoardChangedPayload {
+    pub project_path: String,
+    pub tasks: Vec<Task>,
+}
+
+/// Serializes read-modify-write access to the boards in the project stores.
+///
+/// Held in managed state; the UI commands and the status server's agent
+/// routes both go through it.
+#[derive(Default)]
+pub struct TaskBoards {
+    lock: Arc<Mutex<()>>,
+}
+
+impl TaskBoards {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a project's board. `project_path` must be canonical.
+    pub fn load(&self, app: &AppHandle, project_path: &str) -> Result<TaskBoard, TaskBoardError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        read_board(app, project_path)
+    }
+
+    /// Applies `f` to a project's board, then saves it and emits
+    /// `task-board-changed`. Nothing is saved if `f` fails. The store is
+    /// written on a blocking thread.
+    pub async fn update<T: Send + 'static>(
+        &self,
+        app: &AppHandle,
+        project_path: &str,
+        f: impl FnOnce(&mut TaskBoard) -> Result<T, TaskBoardError> + Send + 'static,
+    ) -> Result<T, TaskBoardError> {
+        let lock = Arc::clone(&self.lock);
+        let (task_app, task_project) = (app.clone(), project_path.to_string());
+        let (board, value) = tokio::task::spawn_blocking(move || {
+            let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+            let mut board = read_board(&task_app, &task_project)?;
+            let value = f(&mut board)?;
+            let store = task_app
+                .store(StatusServer::project_store_name(&task_project))
+                .map_err(|e| TaskBoardError::Store(e.to_string()))?;
+            let json =
+                serde_json::to_value(&board).map_err(|e| TaskBoardError::Store(e.to_string()))?;
+            store.set(TASK_BOARD_KEY, json);
+            store
+                .save()
+                .map_err(|e| TaskBoardError::Store(e.to_string()))?;
+            Ok((board, value))
+        })
+        .await
+        .map_err(|e| TaskBoardError::Store(e.to_string()))??;
+
+        let payload = TaskBoardChangedPayload {
+            project_path: project_path.to_string(),
+            tasks: board.tasks,
+        };
+        if let Err(e) = app.emit("task-board-changed", &payload) {
+            log::warn!("Failed to emit task-board-changed: {}", e);
+        }
+        Ok(value)
+    }
+
+    /// Reopens the tasks an ended session still held, so another session
+    /// can pick them up. `project_path` must be canonical.
+    pub async fn release_session(&self, app: &AppHandle, project_path: &str, session_id: u32) {
+        let holds_tasks = self.load(app, project_path).is_ok_and(|board| {
+            board
+                .tasks
+                .iter()
+                .any(|t| t.status == TaskStatus::Claimed && t.assignee == Some(session_id))
+        });
+        if !holds_tasks {
+            return;
+        }
+        match self
+            .update(
+                app,
+                project_path,
+                move |board| Ok(board.release(session_id)),
+            )
+            .await
+        {
+            Ok(released) => log::info!(
+                "Reopened {} task(s) held by ended session {}",
+                released.len(),
+                session_id
+            ),
+            Err(e) => log::warn!("Failed to release tasks of session {}: {}", session_id, e),
+        }
+    }
+}
+
+fn read_board(app: &AppHandle, project_path: &str) -> Result<TaskBoard, TaskBoardError> {
+    let store = app
+        .store(StatusServer::project_store_name(project_path))
+        .map_err(|e| TaskBoardError::Store(e.to_string()))?;
+    match store.get(TASK_BOARD_KEY) {
+        Some(value) => {
+            serde_json::from_value(value).map_err(|e| TaskBoardError::Store(e.to_string()))
+        }
+        None => Ok(TaskBoard::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_takes_oldest_open_task() {
+        let mut board = TaskBoard::default();
+        let first = board.create("write the parser".into()).unwrap();
+        let second = board.create("write the tests".into()).unwrap();
+
+        let claimed = board.claim(7, None).unwrap();
+        assert_eq!(claimed.id, first.id);
+        assert_eq!(claimed.status, TaskStatus::Claimed);
+        assert_eq!(claimed.assignee, Some(7));
+
+        assert_eq!(board.claim(8, None).unwrap().id, second.id);
+        assert_eq!(board.claim(9, None), Err(TaskBoardError::NoOpenTasks));
+    }
+
+    #[test]
+    fn test_claim_specific_task() {
+        let mut board = TaskBoard::default();
+        let task = board.create("refactor".into()).unwrap();
+
+        board.claim(1, Some(task.id)).unwrap();
+        // Idempotent for the holder, refused for anyone else
+        assert!(board.claim(1, Some(task.id)).is_ok());
+        assert!(matches!(
+            board.claim(2, Some(task.id)),
+            Err(TaskBoardError::InvalidState { .. })
+        ));
+        assert_eq!(board.claim(1, Some(99)), Err(TaskBoardError::NotFound(99)));
+    }
+
+    #[test]
+    fn test_finish_requires_assignee() {
+        let mut board = TaskBoard::default();
+        let task = board.create("fix CI".into()).unwrap();
+
+        // Agents can't finish unclaimed tasks
+        assert!(matches!(
+            board.finish(task.id, Some(1), true, None),
+            Err(TaskBoardError::InvalidState { .. })
+        ));
+        board.claim(1, Some(task.id)).unwrap();
+        assert!(matches!(
+            board.finish(task.id, Some(2), true, None),
+            Err(TaskBoardError::NotAssignee { assignee: 1, .. })
+        ));
+
+        let done = board
+            .finish(task.id, Some(1), true, Some("green".into()))
+            .unwrap();
+        assert_eq!(done.status, TaskStatus::Done);
+        assert_eq!(done.result.as_deref(), Some("green"));
+    }
+
+    #[test]
+    fn test_user_can_fail_and_reopen() {
+        let mut board = TaskBoard::default();
+        let task = board.create("migrate db".into()).unwrap();
+
+        let failed = board
+            .finish(task.id, None, false, Some("obsolete".into()))
+            .unwrap();
+        assert_eq!(failed.status, TaskStatus::Failed);
+
+        let reopened = board.reopen(task.id).unwrap();
+        assert_eq!(reopened.status, TaskStatus::Open);
+        assert_eq!(reopened.assignee, None);
+        assert_eq!(reopened.result, None);
+    }
+
+    #[test]
+    fn test_release_reopens_only_the_sessions_claimed_tasks() {
+        let mut board = TaskBoard::default();
+        let held = board.create("held".into()).unwrap();
+        let finished = board.create("finished".into()).unwrap();
+        let other = board.create("other".into()).unwrap();
+        board.claim(1, Some(held.id)).unwrap();
+        board.claim(1, Some(finished.id)).unwrap();
+        board.finish(finished.id, Some(1), true, None).unwrap();
+        board.claim(2, Some(other.id)).unwrap();
+
+        let released = board.release(1);
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].id, held.id);
+        assert_eq!(released[0].status, TaskStatus::Open);
+        assert_eq!(released[0].assignee, None);
+        assert_eq!(board.tasks[1].status, TaskStatus::Done);
+        assert_eq!(board.tasks[2].assignee, Some(2));
+        assert!(board.release(1).is_empty());
+    }
+
+    #[test]
+    fn test_ids_are_not_reused_after_delete() {
+        let mut board = TaskBoard::default();
+        let first = board.create("a".into()).unwrap();
+        board.delete(first.id).unwrap();
+        assert_eq!(board.create("b".into()).unwrap().id, first.id + 1);
+        assert_eq!(
+            board.delete(first.id),
+            Err(TaskBoardError::NotFound(first.id))
+        );
+    }
+
+    #[test]
+    fn test_rejects_empty_text() {
+        let mut board = TaskBoard::default();
+        assert_eq!(board.create("  ".into()), Err(TaskBoardError::InvalidText));
+    }
+}
@@ -39,6 +39,8 @@ pub struct TerminalState {
     pub scrollback_total: u32,
     /// Terminal title (set by shell escape sequences).
     pub title: Option<String>,
+    /// Visible screen contents, for backends that model the full cell grid.
+    pub screen: Option<ScreenSnapshot>,
 }
 
 /// Cursor shape variants.
@@ -50,6 +52,91 @@ pub enum CursorShape {
     Bar,
 }
 
+/// Foreground or background color of a cell, as set by SGR.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CellColor {
+    /// The terminal's default color.
+    #[default]
+    Default,
+    /// A 256-color palette entry (0-15 are the ANSI colors).
+    Indexed(u8),
+    /// A 24-bit true color.
+    Rgb(u8, u8, u8),
+}
+
+/// SGR rendition attributes of a cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellAttributes {
+    pub fg: CellColor,
+    pub bg: CellColor,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    pub inverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+/// A single cell of the screen grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// Character shown in the cell (a space when blank).
+    pub c: char,
+    /// Display width: 1 for normal cells, 2 for the first half of a wide
+    /// character, and 0 for the spacer cell covered by its second half.
+    pub width: u8,
+    pub attrs: CellAttributes,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            c: ' ',
+            width: 1,
+            attrs: CellAttributes::default(),
+        }
+    }
+}
+
+/// The visible screen at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScreenSnapshot {
+    pub rows: u16,
+    pub cols: u16,
+    /// `rows` rows of `cols` cells each, top to bottom.
+    pub cells: Vec<Vec<Cell>>,
+    /// Whether the alternate screen (used by full-screen TUIs) is active.
+    pub alternate_screen: bool,
+}
+
+impl ScreenSnapshot {
+    /// Returns the text of each row with trailing blanks trimmed.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|row| {
+                let line: String = row
+                    .iter()
+                    .filter(|cell| cell.width > 0)
+                    .map(|cell| cell.c)
+                    .collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Returns the screen as newline-separated text, without trailing blank rows.
+    pub fn text(&self) -> String {
+        let mut lines = self.lines();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+}
+
 /// Handle for managing output subscriptions.
 /// Dropping this handle unsubscribes the callback.
 pub struct SubscriptionHandle {
@@ -1,31 +1,34 @@
 //! VTE-based terminal backend implementation.
 //!
 //! This backend uses the `vte` crate (same parser as Alacritty) for VT sequence
-//! parsing while maintaining xterm.js for rendering. This provides terminal state
-//! tracking (cursor position, title, etc.) with cross-platform compatibility.
+//! parsing while maintaining xterm.js for rendering. Parsed output drives a
+//! `ScreenGrid`, so `get_state` reports the full screen contents (cells, SGR
+//! attributes, alternate screen) alongside cursor position and title.
 //!
 //! # Architecture
 //!
 //! ```text
-//! PTY Output → VTE Parser → State Update + Tauri Event → xterm.js (render)
+//! PTY Output → VTE Parser → ScreenGrid + Tauri Event → xterm.js (render)
 //! ```
 
 use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
 
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Notify;
-use vte::{Parser, Perform};
+use vte::Parser;
 
 #[cfg(unix)]
 use libc;
 
+use super::screen_grid::ScreenGrid;
+use super::pty_sink::PtyOutput;
 use super::terminal_backend::{
-    BackendCapabilities, BackendType, CursorShape, SubscriptionHandle, TerminalBackend,
-    TerminalConfig, TerminalError, TerminalState,
+    BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
+    TerminalError, TerminalState,
 };
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
@@ -90,170 +93,6 @@ impl Utf8Decoder {
     }
 }
 
-/// VTE event handler that tracks terminal state.
-struct VteHandler {
-    state: Arc<RwLock<TerminalState>>,
-    rows: u16,
-    cols: u16,
-}
-
-impl VteHandler {
-    fn new(rows: u16, cols: u16) -> Self {
-        Self {
-            state: Arc::new(RwLock::new(TerminalState::default())),
-            rows,
-            cols,
-        }
-    }
-
-    fn get_state(&self) -> TerminalState {
-        self.state.read().unwrap().clone()
-    }
-
-    fn resize(&mut self, rows: u16, cols: u16) {
-        self.rows = rows;
-        self.cols = cols;
-    }
-}
-
-impl Perform for VteHandler {
-    fn print(&mut self, _c: char) {
-        let mut state = self.state.write().unwrap();
-        state.cursor_col = state.cursor_col.saturating_add(1);
-        if state.cursor_col >= self.cols {
-            state.cursor_col = 0;
-            state.cursor_row = state.cursor_row.saturating_add(1).min(self.rows - 1);
-        }
-    }
-
-    fn execute(&mut self, byte: u8) {
-        let mut state = self.state.write().unwrap();
-        match byte {
-            // Carriage return
-            0x0D => state.cursor_col = 0,
-            // Line feed / newline
-            0x0A => {
-                state.cursor_row = state.cursor_row.saturating_add(1).min(self.rows - 1);
-            }
-            // Backspace
-            0x08 => {
-                state.cursor_col = state.cursor_col.saturating_sub(1);
-            }
-            // Tab
-            0x09 => {
-                state.cursor_col = ((state.cursor_col / 8) + 1) * 8;
-                if state.cursor_col >= self.cols {
-                    state.cursor_col = self.cols - 1;
-                }
-            }
-            // Bell - ignore
-            0x07 => {}
-            _ => {}
-        }
-    }
-
-    fn hook(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {
-        // DCS sequence start - not used for state tracking
-    }
-
-    fn put(&mut self, _byte: u8) {
-        // DCS data - not used for state tracking
-    }
-
-    fn unhook(&mut self) {
-        // DCS sequence end - not used for state tracking
-    }
-
-    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
-        // OSC sequences (e.g., title setting)
-        if params.is_empty() {
-            return;
-        }
-
-        // OSC 0, 1, 2 - Set window/icon title
-        if let Some(&[b'0' | b'1' | b'2']) = params.first() {
-            if let Some(title_bytes) = params.get(1) {
-                if let Ok(title) = std::str::from_utf8(title_bytes) {
-                    let mut state = self.state.write().unwrap();
-                    state.title = Some(title.to_string());
-                }
-            }
-        }
-    }
-
-    fn csi_dispatch(
-        &mut self,
-        params: &vte::Params,
-        _intermediates: &[u8],
-        _ignore: bool,
-        action: char,
-    ) {
-        let mut state = self.state.write().unwrap();
-
-        // Get first parameter with default
-        let param = |idx: usize, default: u16| -> u16 {
-            params
-                .iter()
-                .nth(idx)
-                .and_then(|p| p.first().copied())
-                .and_then(|v| if v == 0 { None } else { Some(v) })
-                .unwrap_or(default)
-        };
-
-        match action {
-            // CUU - Cursor Up
-            'A' => {
-                let n = param(0, 1);
-                state.cursor_row = state.cursor_row.saturating_sub(n);
-            }
-            // CUD - Cursor Down
-            'B' => {
-                let n = param(0, 1);
-                state.cursor_row = state.cursor_row.saturating_add(n).min(self.rows - 1);
-            }
-            // CUF - Cursor Forward
-            'C' => {
-                let n = param(0, 1);
-                state.cursor_col = state.cursor_col.saturating_add(n).min(self.cols - 1);
-            }
-            // CUB - Cursor Back
-            'D' => {
-                let n = param(0, 1);
-                state.cursor_col = state.cursor_col.saturating_sub(n);
-            }
-            // CUP / HVP - Cursor Position
-            'H' | 'f' => {
-                let row = param(0, 1).saturating_sub(1);
-                let col = param(1, 1).saturating_sub(1);
-                state.cursor_row = row.min(self.rows - 1);
-                state.cursor_col = col.min(self.cols - 1);
-            }
-            // DECSCUSR - Set Cursor Shape
-            'q' => {
-                let shape = param(0, 0);
-                state.cursor_shape = match shape {
-                    0 | 1 | 2 => CursorShape::Block,
-                    3 | 4 => CursorShape::Underline,
-                    5 | 6 => CursorShape::Bar,
-                    _ => CursorShape::Block,
-                };
-            }
-            // DECTCEM - Show/Hide Cursor
-            'h' | 'l' => {
-                // Check for ?25h (show) or ?25l (hide)
-                if let Some(&[25]) = params.iter().next() {
-                    state.cursor_visible = action == 'h';
-                }
-            }
-            _ => {}
-        }
-    }
-
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {
-        // ESC sequences - not heavily used for state tracking
-    }
-}
-
 /// Internal session state for VTE backend.
 struct SessionState {
     writer: Box<dyn Write + Send>,
@@ -271,7 +110,8 @@ struct SessionState {
 /// while maintaining xterm.js for rendering.
 pub struct VteBackend {
     session: Mutex<Option<SessionState>>,
-    handler: RwLock<Option<VteHandler>>,
+    /// Screen model, shared with the output task that feeds it.
+    grid: Arc<Mutex<Option<ScreenGrid>>>,
     session_id: Mutex<Option<u32>>,
     app_handle: Mutex<Option<AppHandle>>,
     initialized: AtomicBool,
@@ -287,7 +127,7 @@ impl VteBackend {
     pub fn new() -> Self {
         Self {
             session: Mutex::new(None),
-            handler: RwLock::new(None),
+            grid: Arc::new(Mutex::new(None)),
             session_id: Mutex::new(None),
             app_handle: Mutex::new(None),
             initialized: AtomicBool::new(false),
@@ -301,9 +141,8 @@ impl VteBackend {
 
 impl TerminalBackend for VteBackend {
     fn init(&self, config: TerminalConfig) -> Result<(), TerminalError> {
-        // Initialize VTE handler
-        let handler = VteHandler::new(config.rows, config.cols);
-        *self.handler.write().unwrap() = Some(handler);
+        // Initialize the screen model
+        *self.grid.lock().unwrap() = Some(ScreenGrid::new(config.rows, config.cols));
 
         // Set up PTY
         let pty_system = native_pty_system();
@@ -396,12 +235,12 @@ impl TerminalBackend for VteBackend {
         // Event loop: parse with VTE and emit to frontend
         let event_name = format!("pty-output-{session_id}");
         let app = config.app_handle.clone();
+        let grid = self.grid.clone();
 
         tokio::spawn(async move {
             let mut parser = Parser::new();
             let mut decoder = Utf8Decoder::new();
-            // Note: We can't easily share VteHandler with the async task due to lifetime constraints
-            // For now, just forward data to the frontend - state tracking happens on read
+            let mut end_offset = 0u64;
             loop {
                 tokio::select! {
                     data = rx.recv() => {
@@ -410,11 +249,16 @@ impl TerminalBackend for VteBackend {
                                 // Forward to frontend with proper UTF-8 decoding
                                 let text = decoder.decode(&bytes);
                                 if !text.is_empty() {
-                                    let _ = app.emit(&event_name, text);
+                                    end_offset += text.len() as u64;
+                                    let output = PtyOutput { data: text, end_offset };
+                                    let _ = app.emit(&event_name, output);
                                 }
 
-                                // Parse for state (in a real impl, we'd update shared state here)
-                                parser.advance(&mut DummyPerform, &bytes);
+                                // Update the screen model. The parser buffers split
+                                // UTF-8 and escape sequences across chunks itself.
+                                if let Some(grid) = grid.lock().unwrap().as_mut() {
+                                    parser.advance(grid, &bytes);
+                                }
                             }
                             None => break,
                         }
@@ -491,9 +335,9 @@ impl TerminalBackend for VteBackend {
             return Err(TerminalError::NotInitialized);
         }
 
-        // Resize VTE handler
-        if let Some(ref mut handler) = *self.handler.write().unwrap() {
-            handler.resize(rows, cols);
+        // Resize the screen model
+        if let Some(grid) = self.grid.lock().unwrap().as_mut() {
+            grid.resize(rows, cols);
         }
 
         // Resize PTY
@@ -518,11 +362,7 @@ impl TerminalBackend for VteBackend {
             return None;
         }
 
-        self.handler
-            .read()
-            .unwrap()
-            .as_ref()
-            .map(|h| h.get_state())
+        self.grid.lock().unwrap().as_ref().map(|grid| grid.state())
     }
 
     fn subscribe_output(&self, _callback: Box<dyn Fn(&[u8]) + Send + Sync>) -> SubscriptionHandle {
@@ -534,7 +374,7 @@ impl TerminalBackend for VteBackend {
             return Ok(());
         }
 
-        *self.handler.write().unwrap() = None;
+        *self.grid.lock().unwrap() = None;
 
         let mut session_guard = self.session.lock().unwrap();
         let session = match session_guard.take() {
@@ -618,17 +458,3 @@ impl Drop for VteBackend {
         }
     }
 }
-
-/// Dummy Perform implementation for async parsing
-struct DummyPerform;
-
-impl Perform for DummyPerform {
-    fn print(&mut self, _c: char) {}
-    fn execute(&mut self, _byte: u8) {}
-    fn hook(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
-    fn put(&mut self, _byte: u8) {}
-    fn unhook(&mut self) {}
-    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
-    fn csi_dispatch(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
-}
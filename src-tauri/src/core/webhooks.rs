@@ -0,0 +1,525 @@
+//! Outgoing webhooks for session lifecycle and status events.
+//!
+//! Hooks are configured globally in `webhooks.json`. Each one has a URL,
+//! an event filter and an optional JSON body template, and fires when a
+//! session is created or removed (via `SessionObserver`) or its status
+//! changes (via `status_events::publish`), whether the agent reported the
+//! new status, it was inferred from output or the process exited.
+//!
+//! Deliveries run in the background with exponential backoff. When a hook
+//! has a secret, the body is signed with HMAC-SHA256 and the signature sent
+//! as `X-Maestro-Signature-256: sha256=<hex>`, the way GitHub signs its
+//! webhooks.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use super::session_manager::{SessionConfig, SessionObserver};
+
+/// Store file holding the hooks.
+pub const WEBHOOKS_STORE: &str = "webhooks.json";
+const WEBHOOKS_KEY: &str = "webhooks";
+
+/// Event names.
+pub const SESSION_CREATED: &str = "session.created";
+pub const SESSION_REMOVED: &str = "session.removed";
+pub const SESSION_STATUS: &str = "session.status";
+
+/// Header carrying the body's HMAC-SHA256 signature.
+pub const SIGNATURE_HEADER: &str = "X-Maestro-Signature-256";
+/// Header carrying the event name.
+pub const EVENT_HEADER: &str = "X-Maestro-Event";
+/// Header carrying a unique ID per event, the same across retries.
+pub const DELIVERY_HEADER: &str = "X-Maestro-Delivery";
+
+/// One configured webhook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub id: String,
+    pub url: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Events that fire the hook; all of them when empty. Entries are event
+    /// names (`session.created`, `session.removed`, `session.status`), a
+    /// status event narrowed to one status (`session.status:Done`), or `*`.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// JSON body with `{{placeholder}}`s filled in from the event (see
+    /// `render_body`). The event itself is sent as JSON when absent.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Key for the signature header; unsigned when absent.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Something that happened to a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WebhookEvent {
+    pub event: String,
+    pub session_id: u32,
+    pub project_path: String,
+    pub status: Option<String>,
+    pub message: Option<String>,
+    pub branch: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl WebhookEvent {
+    fn for_session(event: &str, session: &SessionConfig) -> Self {
+        Self {
+            event: event.to_string(),
+            session_id: session.id,
+            project_path: session.project_path.clone(),
+            status: Some(session.status.as_str().to_string()),
+            message: None,
+            branch: session.branch.clone(),
+            timestamp: now_millis(),
+        }
+    }
+
+    /// A session's new status.
+    pub fn status(
+        session_id: u32,
+        project_path: &str,
+        status: &str,
+        message: &str,
+        branch: Option<&str>,
+    ) -> Self {
+        Self {
+            event: SESSION_STATUS.to_string(),
+            session_id,
+            project_path: project_path.to_string(),
+            status: Some(status.to_string()),
+            message: Some(message.to_string()),
+            branch: branch.map(str::to_string),
+            timestamp: now_millis(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Whether this hook fires for `event`.
+    pub fn matches(&self, event: &WebhookEvent) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        self.events.is_empty()
+            || self
+                .events
+                .iter()
+                .any(|filter| match filter.split_once(':') {
+                    Some((name, status)) => {
+                        name == event.event && event.status.as_deref() == Some(status)
+                    }
+                    None => filter == "*" || *filter == event.event,
+                })
+    }
+
+    /// Checks the URL and that the template renders to valid JSON.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+            return Err(format!("Webhook '{}' needs an http(s) URL", self.id));
+        }
+        let sample = WebhookEvent::status(1, "/project", "Done", "Finished \"task\"", Some("main"));
+        render_body(self, &sample)
+            .map(|_| ())
+            .map_err(|e| format!("Webhook '{}': {}", self.id, e))
+    }
+}
+
+/// The body to send: the hook's template with placeholders replaced, or
+/// the event as JSON.
+///
+/// Placeholders are `{{event}}`, `{{session_id}}`, `{{project_path}}`,
+/// `{{project_name}}`, `{{status}}`, `{{message}}`, `{{branch}}` and
+/// `{{timestamp}}`. Values are JSON-escaped but not quoted, so string
+/// placeholders belong inside quotes, e.g.
+/// `{"text": "Session {{session_id}} is {{status}}"}`. The template is
+/// filled in one pass, so placeholders inside values are left alone;
+/// unknown placeholders are kept as written.
+pub fn render_body(hook: &WebhookConfig, event: &WebhookEvent) -> Result<String, String> {
+    let Some(template) = &hook.template else {
+        return serde_json::to_string(event).map_err(|e| e.to_string());
+    };
+
+    let project_name = std::path::Path::new(&event.project_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let values = [
+        ("event", event.event.clone()),
+        ("session_id", event.session_id.to_string()),
+        ("project_path", event.project_path.clone()),
+        ("project_name", project_name),
+        ("status", event.status.clone().unwrap_or_default()),
+        ("message", event.message.clone().unwrap_or_default()),
+        ("branch", event.branch.clone().unwrap_or_default()),
+        ("timestamp", event.timestamp.to_string()),
+    ];
+    let mut body = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find("{{") {
+        body.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let value = values.iter().find(|(name, _)| *name == &after[..end])?;
+            Some((end, &value.1))
+        });
+        match value {
+            Some((end, value)) => {
+                let escaped = serde_json::to_string(value).map_err(|e| e.to_string())?;
+                body.push_str(&escaped[1..escaped.len() - 1]);
+                rest = &after[end + 2..];
+            }
+            None => {
+                body.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    body.push_str(rest);
+    serde_json::from_str::<Value>(&body)
+        .map_err(|e| format!("Template is not valid JSON: {}", e))?;
+    Ok(body)
+}
+
+/// Value of the signature header for `body`.
+pub fn signature(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// How failed deliveries are retried.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first.
+    pub attempts: u32,
+    /// Wait before the first retry; doubles after each one.
+    pub initial_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 4,
+            initial_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Sends `event` to one hook, retrying network errors, `429`s and `5xx`s.
+/// Returns the number of attempts it took.
+pub async fn deliver(
+    client: &reqwest::Client,
+    hook: &WebhookConfig,
+    event: &WebhookEvent,
+    delivery_id: &str,
+    retry: RetryPolicy,
+) -> Result<u32, String> {
+    let body = render_body(hook, event)?;
+    let mut delay = retry.initial_delay;
+    let mut attempt = 1;
+    loop {
+        let mut request = client
+            .post(&hook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &event.event)
+            .header(DELIVERY_HEADER, delivery_id)
+            .timeout(Duration::from_secs(10))
+            .body(body.clone());
+        if let Some(secret) = &hook.secret {
+            request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(attempt),
+            Ok(response) => {
+                let status = response.status();
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    return Err(format!("{} answered {}", hook.url, status));
+                }
+                format!("{} answered {}", hook.url, status)
+            }
+            Err(e) => e.to_string(),
+        };
+        if attempt >= retry.attempts {
+            return Err(format!("Gave up after {} attempts: {}", attempt, error));
+        }
+        log::debug!(
+            "Webhook '{}' attempt {} failed: {}",
+            hook.id,
+            attempt,
+            error
+        );
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// The configured hooks.
+pub fn load_webhooks(app: &AppHandle) -> Vec<WebhookConfig> {
+    app.store(WEBHOOKS_STORE)
+        .ok()
+        .and_then(|store| store.get(WEBHOOKS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Replaces the configured hooks after validating each.
+pub fn save_webhooks(app: &AppHandle, hooks: &[WebhookConfig]) -> Result<(), String> {
+    for hook in hooks {
+        hook.validate()?;
+    }
+    let store = app.store(WEBHOOKS_STORE).map_err(|e| e.to_string())?;
+    let json = serde_json::to_value(hooks).map_err(|e| e.to_string())?;
+    store.set(WEBHOOKS_KEY, json);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Fires the configured hooks. Held in managed state and attached to the
+/// `SessionManager` as its observer.
+pub struct Webhooks {
+    app: AppHandle,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl Webhooks {
+    pub fn new(app: AppHandle) -> Arc<Self> {
+        Arc::new(Self {
+            app,
+            client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    /// Delivers `event` to every hook that wants it, in the background.
+    pub fn fire(&self, event: WebhookEvent) {
+        let hooks: Vec<WebhookConfig> = load_webhooks(&self.app)
+            .into_iter()
+            .filter(|hook| hook.matches(&event))
+            .collect();
+        if hooks.is_empty() {
+            return;
+        }
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        for hook in hooks {
+            let client = self.client.clone();
+            let event = event.clone();
+            let delivery_id = delivery_id.clone();
+            let retry = self.retry;
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = deliver(&client, &hook, &event, &delivery_id, retry).await {
+                    log::warn!("Webhook '{}' failed for {}: {}", hook.id, event.event, e);
+                }
+            });
+        }
+    }
+}
+
+impl SessionObserver for Webhooks {
+    fn session_created(&self, session: &SessionConfig) {
+        self.fire(WebhookEvent::for_session(SESSION_CREATED, session));
+    }
+
+    fn session_removed(&self, session: &SessionConfig) {
+        self.fire(WebhookEvent::for_session(SESSION_REMOVED, session));
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    fn hook(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            id: "ci".to_string(),
+            url: url.to_string(),
+            enabled: true,
+            events: Vec::new(),
+            template: None,
+            secret: None,
+        }
+    }
+
+    fn done_event() -> WebhookEvent {
+        WebhookEvent::status(
+            3,
+            "/work/maestro",
+            "Done",
+            "All \"tests\" pass",
+            Some("feature/login"),
+        )
+    }
+
+    #[test]
+    fn test_signature_rfc4231() {
+        // Test case 2 of RFC 4231
+        assert_eq!(
+            signature("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let mut hook = hook("http://localhost");
+        assert!(hook.matches(&done_event()));
+
+        hook.events = vec![
+            "session.status:Error".to_string(),
+            SESSION_CREATED.to_string(),
+        ];
+        assert!(!hook.matches(&done_event()));
+        hook.events.push("session.status:Done".to_string());
+        assert!(hook.matches(&done_event()));
+
+        hook.events = vec!["*".to_string()];
+        assert!(hook.matches(&done_event()));
+        hook.enabled = false;
+        assert!(!hook.matches(&done_event()));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let mut hook = hook("http://localhost");
+        hook.template =
+            Some(r#"{"text": "{{project_name}} #{{session_id}} {{status}}: {{message}}"}"#.into());
+        let body: Value =
+            serde_json::from_str(&render_body(&hook, &done_event()).unwrap()).unwrap();
+        assert_eq!(body["text"], "maestro #3 Done: All \"tests\" pass");
+
+        // Values are not themselves searched for placeholders
+        let mut event = done_event();
+        event.message = Some("{{branch}}".into());
+        hook.template = Some(r#"{"text": "{{message}} on {{branch}} {{unknown}}"}"#.into());
+        let body: Value = serde_json::from_str(&render_body(&hook, &event).unwrap()).unwrap();
+        assert_eq!(body["text"], "{{branch}} on feature/login {{unknown}}");
+
+        hook.template = Some("{\"text\": {{message}}}".into());
+        assert!(hook.validate().is_err());
+        hook.template = None;
+        assert!(hook.validate().is_ok());
+        hook.url = "ftp://example.com".into();
+        assert!(hook.validate().is_err());
+    }
+
+    #[derive(Default)]
+    struct StandIn {
+        /// Requests to fail with 503 before succeeding.
+        failures: AtomicU32,
+        received: Mutex<Vec<(HeaderMap, String)>>,
+    }
+
+    async fn receive(
+        State(stand_in): State<Arc<StandIn>>,
+        headers: HeaderMap,
+        body: String,
+    ) -> StatusCode {
+        stand_in.received.lock().unwrap().push((headers, body));
+        let failing = stand_in
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if failing {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::NO_CONTENT
+        }
+    }
+
+    async fn start_stand_in(failures: u32) -> (String, Arc<StandIn>) {
+        let stand_in = Arc::new(StandIn::default());
+        stand_in.failures.store(failures, Ordering::SeqCst);
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(stand_in.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, stand_in)
+    }
+
+    fn fast_retry(attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            attempts,
+            initial_delay: Duration::from_millis(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deliver_signs_and_retries() {
+        let (url, stand_in) = start_stand_in(2).await;
+        let mut hook = hook(&url);
+        hook.secret = Some("s3cret".into());
+        let client = reqwest::Client::new();
+
+        let attempts = deliver(&client, &hook, &done_event(), "delivery-1", fast_retry(4))
+            .await
+            .unwrap();
+        assert_eq!(attempts, 3);
+
+        let received = stand_in.received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let (headers, body) = received.last().unwrap();
+        assert_eq!(headers[EVENT_HEADER], SESSION_STATUS);
+        assert_eq!(headers[DELIVERY_HEADER], "delivery-1");
+        assert_eq!(
+            headers[SIGNATURE_HEADER].to_str().unwrap(),
+            signature("s3cret", body)
+        );
+        let json: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["status"], "Done");
+        assert_eq!(json["session_id"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up() {
+        let (url, stand_in) = start_stand_in(10).await;
+        let client = reqwest::Client::new();
+
+        let result = deliver(&client, &hook(&url), &done_event(), "d", fast_retry(3)).await;
+        assert!(result.unwrap_err().starts_with("Gave up after 3 attempts"));
+        assert_eq!(stand_in.received.lock().unwrap().len(), 3);
+        assert!(!stand_in.received.lock().unwrap()[0]
+            .0
+            .contains_key(SIGNATURE_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_deliver_does_not_retry_client_errors() {
+        let client = reqwest::Client::new();
+        let (url, stand_in) = start_stand_in(0).await;
+        let missing = url.replace("/hook", "/nope");
+
+        let result = deliver(&client, &hook(&missing), &done_event(), "d", fast_retry(3)).await;
+        assert!(result.unwrap_err().contains("404"));
+        assert!(stand_in.received.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,673 @@
+//! Bootstrapping of freshly created worktrees.
+//!
+//! A new worktree only has the tracked files, so untracked ones such as
+//! `.env` or `.claude/settings.local.json` are missing and dependencies
+//! need installing. Each project can keep a `WorktreeBootstrap` under the
+//! `worktree_bootstrap` key of its `maestro-<hash>.json` store, listing
+//! files (or globs) to copy or symlink from the main checkout and commands
+//! to run in the new worktree.
+//!
+//! Bootstrapping finishes before the session launches so the agent never
+//! starts in a half-installed worktree. Commands such as `npm install` can
+//! take minutes, so each step is reported through a `ProgressSink`; the app
+//! forwards those as `worktree-bootstrap-progress` events.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::status_server::StatusServer;
+use super::windows_process::TokioCommandExt;
+
+/// Store key for a project's bootstrap config.
+const WORKTREE_BOOTSTRAP_KEY: &str = "worktree_bootstrap";
+
+/// Output kept per stream of a command; earlier output is dropped.
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// How long to keep reading a finished or killed command's output. A
+/// process that left its process group can hold the pipes open forever.
+const OUTPUT_GRACE: Duration = Duration::from_secs(2);
+
+/// Event carrying a `BootstrapProgressEvent`.
+pub const PROGRESS_EVENT: &str = "worktree-bootstrap-progress";
+
+/// How to set up a project's new worktrees.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorktreeBootstrap {
+    /// Files or globs, relative to the main checkout, copied into the
+    /// worktree (e.g. `.env*`, `.claude/settings.local.json`).
+    pub copy: Vec<String>,
+    /// Files or globs symlinked instead of copied, for large directories
+    /// such as `node_modules`.
+    pub symlink: Vec<String>,
+    /// Shell commands run in the worktree, in order, after the files are
+    /// in place. A failing command stops the rest.
+    pub commands: Vec<String>,
+    /// Longest each command may run before it is killed.
+    pub command_timeout_secs: u64,
+}
+
+impl Default for WorktreeBootstrap {
+    fn default() -> Self {
+        Self {
+            copy: Vec::new(),
+            symlink: Vec::new(),
+            commands: Vec::new(),
+            command_timeout_secs: 300,
+        }
+    }
+}
+
+impl WorktreeBootstrap {
+    pub fn is_empty(&self) -> bool {
+        self.copy.is_empty() && self.symlink.is_empty() && self.commands.is_empty()
+    }
+
+    /// Checks that every pattern stays inside the checkout.
+    pub fn validate(&self) -> Result<(), String> {
+        for pattern in self.copy.iter().chain(&self.symlink) {
+            let escapes = Path::new(pattern).components().any(|c| {
+                matches!(
+                    c,
+                    Component::ParentDir | Component::RootDir | Component::Prefix(_)
+                )
+            });
+            if pattern.trim().is_empty() || escapes {
+                return Err(format!(
+                    "'{}' must be a path inside the project, without '..'",
+                    pattern
+                ));
+            }
+            glob::Pattern::new(pattern)
+                .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        }
+        if self.command_timeout_secs == 0 {
+            return Err("Command timeout must be at least one second".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one bootstrap command.
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapCommandResult {
+    pub command: String,
+    /// `None` if the command was killed or could not be started.
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+}
+
+impl BootstrapCommandResult {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// What bootstrapping a worktree did.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BootstrapResult {
+    /// Paths copied, relative to the worktree.
+    pub copied: Vec<String>,
+    /// Paths symlinked, relative to the worktree.
+    pub symlinked: Vec<String>,
+    /// Matches left alone because the worktree already has them.
+    pub skipped: Vec<String>,
+    pub commands: Vec<BootstrapCommandResult>,
+    pub errors: Vec<String>,
+}
+
+impl BootstrapResult {
+    /// Whether every file and command succeeded.
+    pub fn succeeded(&self) -> bool {
+        self.errors.is_empty() && self.commands.iter().all(|c| c.succeeded())
+    }
+}
+
+/// One step of a running bootstrap.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BootstrapProgress {
+    /// The files were copied and symlinked.
+    FilesPlaced {
+        copied: usize,
+        symlinked: usize,
+        skipped: usize,
+    },
+    /// Command `index` of `total` started.
+    CommandStarted {
+        index: usize,
+        total: usize,
+        command: String,
+    },
+    /// Command `index` finished, failed or timed out.
+    CommandFinished {
+        index: usize,
+        result: BootstrapCommandResult,
+    },
+    /// Everything that was going to run has run.
+    Finished { result: BootstrapResult },
+}
+
+/// Receives a bootstrap's progress as it runs, with the worktree it is for.
+pub type ProgressSink = Arc<dyn Fn(&Path, BootstrapProgress) + Send + Sync>;
+
+/// Payload of `PROGRESS_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BootstrapProgressEvent {
+    pub worktree_path: String,
+    #[serde(flatten)]
+    pub progress: BootstrapProgress,
+}
+
+/// A `ProgressSink` that emits `PROGRESS_EVENT`.
+pub fn event_sink(app: AppHandle) -> ProgressSink {
+    Arc::new(move |worktree, progress| {
+        let event = BootstrapProgressEvent {
+            worktree_path: worktree.to_string_lossy().into_owned(),
+            progress,
+        };
+        if let Err(e) = app.emit(PROGRESS_EVENT, &event) {
+            log::warn!("Failed to emit bootstrap progress: {}", e);
+        }
+    })
+}
+
+/// Sets up `worktree` from `main_checkout` as `config` describes,
+/// reporting each step to `progress` and ending with
+/// `BootstrapProgress::Finished`.
+pub async fn bootstrap(
+    config: &WorktreeBootstrap,
+    main_checkout: &Path,
+    worktree: &Path,
+    progress: &ProgressSink,
+) -> BootstrapResult {
+    let result = run_bootstrap(config, main_checkout, worktree, progress).await;
+    progress(
+        worktree,
+        BootstrapProgress::Finished {
+            result: result.clone(),
+        },
+    );
+    result
+}
+
+async fn run_bootstrap(
+    config: &WorktreeBootstrap,
+    main_checkout: &Path,
+    worktree: &Path,
+    progress: &ProgressSink,
+) -> BootstrapResult {
+    let mut result = BootstrapResult::default();
+    if let Err(e) = config.validate() {
+        result.errors.push(e);
+        return result;
+    }
+
+    for (patterns, link) in [(&config.copy, false), (&config.symlink, true)] {
+        for pattern in patterns {
+            let matches = match expand(main_checkout, pattern) {
+                Ok(matches) => matches,
+                Err(e) => {
+                    result.errors.push(e);
+                    continue;
+                }
+            };
+            for relative in matches {
+                place(main_checkout, worktree, &relative, link, &mut result).await;
+            }
+        }
+    }
+
+    progress(
+        worktree,
+        BootstrapProgress::FilesPlaced {
+            copied: result.copied.len(),
+            symlinked: result.symlinked.len(),
+            skipped: result.skipped.len(),
+        },
+    );
+
+    let timeout = Duration::from_secs(config.command_timeout_secs);
+    for (i, command) in config.commands.iter().enumerate() {
+        progress(
+            worktree,
+            BootstrapProgress::CommandStarted {
+                index: i,
+                total: config.commands.len(),
+                command: command.clone(),
+            },
+        );
+        let outcome = run_command(command, main_checkout, worktree, timeout).await;
+        let failed = !outcome.succeeded();
+        progress(
+            worktree,
+            BootstrapProgress::CommandFinished {
+                index: i,
+                result: outcome.clone(),
+            },
+        );
+        result.commands.push(outcome);
+        if failed {
+            let remaining = config.commands.len() - i - 1;
+            if remaining > 0 {
+                result.errors.push(format!(
+                    "Skipped {} remaining command(s) after `{}` failed",
+                    remaining, command
+                ));
+            }
+            break;
+        }
+    }
+    result
+}
+
+/// Paths under `root` matching `pattern`, relative to `root`.
+fn expand(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let full = format!(
+        "{}/{}",
+        glob::Pattern::escape(&root.to_string_lossy()),
+        pattern
+    );
+    let paths = glob::glob(&full).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    Ok(paths
+        .filter_map(Result::ok)
+        .filter_map(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect())
+}
+
+async fn place(
+    main_checkout: &Path,
+    worktree: &Path,
+    relative: &Path,
+    link: bool,
+    result: &mut BootstrapResult,
+) {
+    let source = main_checkout.join(relative);
+    let target = worktree.join(relative);
+    let name = relative.to_string_lossy().to_string();
+    if tokio::fs::symlink_metadata(&target).await.is_ok() {
+        result.skipped.push(name);
+        return;
+    }
+    if let Some(parent) = target.parent() {
+        if let Err(e) = tokio::fs::create_dir_all(parent).await {
+            result
+                .errors
+                .push(format!("Failed to create {}: {}", parent.display(), e));
+            return;
+        }
+    }
+
+    let placed = if link {
+        symlink(&source, &target).await
+    } else {
+        let (source, target) = (source.clone(), target.clone());
+        tokio::task::spawn_blocking(move || copy_recursive(&source, &target))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)))
+    };
+    match placed {
+        Ok(()) if link => result.symlinked.push(name),
+        Ok(()) => result.copied.push(name),
+        Err(e) => result.errors.push(format!(
+            "Failed to {} {}: {}",
+            if link { "symlink" } else { "copy" },
+            name,
+            e
+        )),
+    }
+}
+
+fn copy_recursive(source: &Path, target: &Path) -> std::io::Result<()> {
+    if std::fs::metadata(source)?.is_dir() {
+        std::fs::create_dir_all(target)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(source, target).map(|_| ())
+    }
+}
+
+#[cfg(unix)]
+async fn symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    tokio::fs::symlink(source, target).await
+}
+
+#[cfg(windows)]
+async fn symlink(source: &Path, target: &Path) -> std::io::Result<()> {
+    if tokio::fs::metadata(source).await?.is_dir() {
+        tokio::fs::symlink_dir(source, target).await
+    } else {
+        tokio::fs::symlink_file(source, target).await
+    }
+}
+
+/// Runs `command` through the platform shell in `worktree`. The main
+/// checkout is passed as `MAESTRO_MAIN_CHECKOUT`.
+///
+/// The command gets its own process group so a timeout kills everything
+/// it started, not just the shell. Output read before the kill is kept.
+async fn run_command(
+    command: &str,
+    main_checkout: &Path,
+    worktree: &Path,
+    timeout: Duration,
+) -> BootstrapCommandResult {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.args(["-c", command]).process_group(0);
+        cmd
+    };
+    cmd.current_dir(worktree)
+        .env("MAESTRO_MAIN_CHECKOUT", main_checkout)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .hide_console_window();
+
+    let started = Instant::now();
+    let mut outcome = BootstrapCommandResult {
+        command: command.to_string(),
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        timed_out: false,
+        duration_ms: 0,
+    };
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            outcome.stderr = format!("Failed to start: {}", e);
+            return outcome;
+        }
+    };
+
+    let stdout = OutputTail::capture(child.stdout.take());
+    let stderr = OutputTail::capture(child.stderr.take());
+    let mut wait_error = None;
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => outcome.exit_code = status.code(),
+        Ok(Err(e)) => wait_error = Some(format!("Failed to wait for command: {}", e)),
+        Err(_) => {
+            outcome.timed_out = true;
+            kill_tree(&mut child).await;
+        }
+    }
+    (outcome.stdout, outcome.stderr) = tokio::join!(stdout.finish(), stderr.finish());
+    if let Some(e) = wait_error {
+        outcome.stderr.push_str(&e);
+    }
+
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
+    log::info!(
+        "Worktree bootstrap `{}` in {} finished with {:?} after {} ms{}",
+        command,
+        worktree.display(),
+        outcome.exit_code,
+        outcome.duration_ms,
+        if outcome.timed_out {
+            " (timed out)"
+        } else {
+            ""
+        }
+    );
+    outcome
+}
+
+/// Kills a timed-out command and everything in its process group.
+async fn kill_tree(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // The child leads its own group, so this reaches its descendants
+        unsafe { libc::kill(-(pid as i32), libc::SIGKILL) };
+    }
+    #[cfg(windows)]
+    if let Some(pid) = child.id() {
+        let _ = tokio::process::Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .hide_console_window()
+            .output()
+            .await;
+    }
+    if let Err(e) = child.kill().await {
+        log::warn!("Failed to kill timed-out bootstrap command: {}", e);
+    }
+}
+
+/// The last `MAX_OUTPUT_BYTES` read from one of a command's pipes.
+struct OutputTail {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    reader: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl OutputTail {
+    fn capture(pipe: Option<impl AsyncRead + Unpin + Send + 'static>) -> Self {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let reader = pipe.map(|mut pipe| {
+            let buffer = buffer.clone();
+            tokio::spawn(async move {
+                let mut chunk = [0u8; 8192];
+                while let Ok(n @ 1..) = pipe.read(&mut chunk).await {
+                    let mut buffer = buffer.lock().unwrap();
+                    buffer.extend_from_slice(&chunk[..n]);
+                    if buffer.len() > 2 * MAX_OUTPUT_BYTES {
+                        let excess = buffer.len() - MAX_OUTPUT_BYTES;
+                        buffer.drain(..excess);
+                    }
+                }
+            })
+        });
+        Self { buffer, reader }
+    }
+
+    /// Waits up to `OUTPUT_GRACE` for the pipe to close, then returns
+    /// whatever was read.
+    async fn finish(self) -> String {
+        if let Some(mut reader) = self.reader {
+            if tokio::time::timeout(OUTPUT_GRACE, &mut reader)
+                .await
+                .is_err()
+            {
+                reader.abort();
+            }
+        }
+        let buffer = self.buffer.lock().unwrap();
+        tail(&buffer)
+    }
+}
+
+/// The last `MAX_OUTPUT_BYTES` of `output`, as text.
+fn tail(output: &[u8]) -> String {
+    let start = output.len().saturating_sub(MAX_OUTPUT_BYTES);
+    String::from_utf8_lossy(&output[start..]).into_owned()
+}
+
+/// A project's bootstrap config, or an empty one if it has none.
+pub fn load_config(app: &AppHandle, project_path: &str) -> WorktreeBootstrap {
+    app.store(StatusServer::project_store_name(project_path))
+        .ok()
+        .and_then(|store| store.get(WORKTREE_BOOTSTRAP_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Saves a project's bootstrap config.
+pub fn save_config(
+    app: &AppHandle,
+    project_path: &str,
+    config: &WorktreeBootstrap,
+) -> Result<(), String> {
+    let store = app
+        .store(StatusServer::project_store_name(project_path))
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_value(config).map_err(|e| e.to_string())?;
+    store.set(WORKTREE_BOOTSTRAP_KEY, json);
+    store.save().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn no_progress() -> ProgressSink {
+        Arc::new(|_, _| {})
+    }
+
+    #[test]
+    fn test_validate_rejects_escaping_patterns() {
+        let mut config = WorktreeBootstrap {
+            copy: vec![".env*".into(), ".claude/settings.local.json".into()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        for bad in ["../secrets", "/etc/passwd", "", "[unclosed"] {
+            config.symlink = vec![bad.to_string()];
+            assert!(config.validate().is_err(), "accepted {:?}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_copies_links_and_skips_existing() {
+        let main = tempdir().unwrap();
+        let worktree = tempdir().unwrap();
+        std::fs::write(main.path().join(".env"), "A=1").unwrap();
+        std::fs::write(main.path().join(".env.local"), "B=2").unwrap();
+        std::fs::create_dir_all(main.path().join(".claude")).unwrap();
+        std::fs::write(main.path().join(".claude/settings.local.json"), "{}").unwrap();
+        std::fs::create_dir_all(main.path().join("node_modules/pkg")).unwrap();
+        // Tracked files are already in the worktree
+        std::fs::write(worktree.path().join(".env.local"), "mine").unwrap();
+
+        let config = WorktreeBootstrap {
+            copy: vec![".env*".into(), ".claude/settings.local.json".into()],
+            symlink: vec!["node_modules".into()],
+            ..Default::default()
+        };
+        let result = bootstrap(&config, main.path(), worktree.path(), &no_progress()).await;
+
+        assert!(result.succeeded(), "{:?}", result.errors);
+        assert_eq!(result.copied, vec![".env", ".claude/settings.local.json"]);
+        assert_eq!(result.skipped, vec![".env.local"]);
+        assert_eq!(result.symlinked, vec!["node_modules"]);
+        assert_eq!(
+            std::fs::read_to_string(worktree.path().join(".env.local")).unwrap(),
+            "mine"
+        );
+        assert!(
+            std::fs::symlink_metadata(worktree.path().join("node_modules"))
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bootstrap_commands_capture_output_and_stop_on_failure() {
+        let main = tempdir().unwrap();
+        let worktree = tempdir().unwrap();
+        let config = WorktreeBootstrap {
+            commands: vec![
+                "echo ready > marker && echo out && echo err >&2".into(),
+                "exit 3".into(),
+                "touch never".into(),
+            ],
+            ..Default::default()
+        };
+        let result = bootstrap(&config, main.path(), worktree.path(), &no_progress()).await;
+
+        assert_eq!(result.commands.len(), 2);
+        assert_eq!(result.commands[0].stdout, "out\n");
+        assert_eq!(result.commands[0].stderr, "err\n");
+        assert_eq!(result.commands[1].exit_code, Some(3));
+        assert_eq!(result.errors.len(), 1);
+        assert!(worktree.path().join("marker").exists());
+        assert!(!worktree.path().join("never").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_process_group_and_keeps_output() {
+        let main = tempdir().unwrap();
+        let worktree = tempdir().unwrap();
+        let slow = WorktreeBootstrap {
+            commands: vec!["echo started; sleep 30 & sleep 30".into()],
+            command_timeout_secs: 1,
+            ..Default::default()
+        };
+        let result = bootstrap(&slow, main.path(), worktree.path(), &no_progress()).await;
+
+        let command = &result.commands[0];
+        assert!(command.timed_out);
+        assert_eq!(command.stdout, "started\n");
+        assert!(!result.succeeded());
+        // A surviving background job would hold the pipes open for the
+        // whole output grace period
+        assert!(
+            command.duration_ms < 1000 + OUTPUT_GRACE.as_millis() as u64 / 2,
+            "took {} ms",
+            command.duration_ms
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_reports_progress() {
+        let main = tempdir().unwrap();
+        let worktree = tempdir().unwrap();
+        std::fs::write(main.path().join(".env"), "A=1").unwrap();
+        let config = WorktreeBootstrap {
+            copy: vec![".env".into()],
+            commands: vec!["echo one".into(), "echo two".into()],
+            ..Default::default()
+        };
+        let steps = Arc::new(Mutex::new(Vec::new()));
+        let sink: ProgressSink = {
+            let steps = steps.clone();
+            Arc::new(move |_, progress| steps.lock().unwrap().push(progress))
+        };
+        bootstrap(&config, main.path(), worktree.path(), &sink).await;
+
+        let steps = steps.lock().unwrap();
+        assert_eq!(steps.len(), 6);
+        assert!(matches!(
+            steps[0],
+            BootstrapProgress::FilesPlaced { copied: 1, .. }
+        ));
+        match &steps[3] {
+            BootstrapProgress::CommandStarted {
+                index,
+                total,
+                command,
+            } => assert_eq!((*index, *total, command.as_str()), (1, 2, "echo two")),
+            other => panic!("expected CommandStarted, got {:?}", other),
+        }
+        match &steps[5] {
+            BootstrapProgress::Finished { result } => assert!(result.succeeded()),
+            other => panic!("expected Finished, got {:?}", other),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 
 use crate::git::{Git, GitError, WorktreeInfo};
@@ -53,6 +54,101 @@ fn sanitize_branch(branch: &str) -> String {
     sanitized
 }
 
+/// What the garbage collector found a managed worktree to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WorktreeGcState {
+    /// A live session is running in it; never collected.
+    InUse,
+    /// Registered with its repo, used by no session and without local
+    /// changes. Safe to remove.
+    Stale,
+    /// Used by no session but has uncommitted changes; only reported.
+    Dirty,
+    /// Unknown to its repo's `git worktree list` (e.g. already pruned), so
+    /// git can't tell whether it holds unsaved work. Only removed when the
+    /// caller forces it.
+    Orphaned,
+    /// The repository it was created from no longer exists; only reported,
+    /// since its changes can't be checked.
+    MissingRepo,
+}
+
+impl WorktreeGcState {
+    /// Whether applying the GC removes worktrees in this state, given
+    /// whether orphaned ones were explicitly included.
+    pub fn is_removable(self, force_orphaned: bool) -> bool {
+        match self {
+            Self::Stale => true,
+            Self::Orphaned => force_orphaned,
+            Self::InUse | Self::Dirty | Self::MissingRepo => false,
+        }
+    }
+}
+
+/// One directory under the managed base dir.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeGcEntry {
+    pub path: String,
+    /// The repository the worktree belongs to, if it could be determined.
+    pub repo_path: Option<String>,
+    pub branch: Option<String>,
+    pub state: WorktreeGcState,
+    /// Bytes used on disk, without following symlinks.
+    pub disk_usage: u64,
+}
+
+/// Result of a dry-run GC pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorktreeGcReport {
+    pub entries: Vec<WorktreeGcEntry>,
+    /// Bytes freed by removing every stale entry.
+    pub reclaimable_bytes: u64,
+    /// Bytes used by orphaned entries, freed only by a forced apply.
+    pub orphaned_bytes: u64,
+}
+
+/// Result of applying the GC.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorktreeGcResult {
+    pub removed: Vec<String>,
+    /// Paths that were not removed, with the reason.
+    pub skipped: Vec<(String, String)>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Repository a worktree belongs to, read from the `gitdir:` line of its
+/// `.git` file (`<repo>/.git/worktrees/<name>`).
+async fn worktree_repo(wt_path: &Path) -> Option<PathBuf> {
+    let contents = tokio::fs::read_to_string(wt_path.join(".git")).await.ok()?;
+    let gitdir = PathBuf::from(contents.trim().strip_prefix("gitdir:")?.trim());
+    let git_dir = gitdir.parent()?.parent()?;
+    Some(git_dir.parent()?.to_path_buf())
+}
+
+/// Bytes used by everything under `path`, without following symlinks.
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+async fn canonical(path: &Path) -> PathBuf {
+    tokio::fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
 /// Manages Maestro-owned git worktrees under a deterministic, repo-specific
 /// directory inside XDG data dirs.
 ///
@@ -215,6 +311,171 @@ impl WorktreeManager {
 
         Ok(())
     }
+
+    /// Dry-run garbage collection across every repo's managed worktrees.
+    ///
+    /// `in_use` holds the worktree paths of live sessions. Nothing is
+    /// modified; pass the paths the user confirms to `gc_apply`.
+    pub async fn gc_scan(&self, in_use: &[PathBuf]) -> WorktreeGcReport {
+        gc_scan_in(&worktree_base_dir(), in_use).await
+    }
+
+    /// Removes the given worktrees, re-checking each one first so that only
+    /// those still stale (and not under `in_use`) are deleted. Orphaned
+    /// ones are deleted too only if `force_orphaned` is set.
+    pub async fn gc_apply(
+        &self,
+        paths: &[PathBuf],
+        in_use: &[PathBuf],
+        force_orphaned: bool,
+    ) -> WorktreeGcResult {
+        gc_apply_in(&worktree_base_dir(), paths, in_use, force_orphaned).await
+    }
+}
+
+async fn gc_scan_in(base: &Path, in_use: &[PathBuf]) -> WorktreeGcReport {
+    let mut live = HashSet::with_capacity(in_use.len());
+    for path in in_use {
+        live.insert(canonical(path).await);
+    }
+
+    let mut candidates = Vec::new();
+    if let Ok(mut repos) = tokio::fs::read_dir(base).await {
+        while let Ok(Some(repo_dir)) = repos.next_entry().await {
+            let Ok(mut entries) = tokio::fs::read_dir(repo_dir.path()).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+                    candidates.push(entry.path());
+                }
+            }
+        }
+    }
+    candidates.sort();
+
+    // `git worktree list` of each repo, keyed by repo path
+    let mut registered: HashMap<PathBuf, Option<HashMap<PathBuf, WorktreeInfo>>> =
+        HashMap::new();
+    let mut report = WorktreeGcReport::default();
+    for path in candidates {
+        let canonical_path = canonical(&path).await;
+        let repo = worktree_repo(&path).await;
+
+        let mut branch = None;
+        let state = match &repo {
+            _ if live.contains(&canonical_path) => WorktreeGcState::InUse,
+            None => WorktreeGcState::Orphaned,
+            Some(repo) if !tokio::fs::try_exists(repo).await.unwrap_or(false) => {
+                WorktreeGcState::MissingRepo
+            }
+            Some(repo) => {
+                if !registered.contains_key(repo) {
+                    let listed = match Git::new(repo).worktree_list().await {
+                        Ok(list) => {
+                            let mut by_path = HashMap::with_capacity(list.len());
+                            for wt in list {
+                                by_path.insert(canonical(Path::new(&wt.path)).await, wt);
+                            }
+                            Some(by_path)
+                        }
+                        Err(_) => None,
+                    };
+                    registered.insert(repo.clone(), listed);
+                }
+                match registered[repo].as_ref() {
+                    None => WorktreeGcState::MissingRepo,
+                    Some(listed) => match listed.get(&canonical_path) {
+                        None => WorktreeGcState::Orphaned,
+                        Some(wt) => {
+                            branch = wt.branch.clone();
+                            match Git::new(&path).uncommitted_count().await {
+                                Ok(0) => WorktreeGcState::Stale,
+                                _ => WorktreeGcState::Dirty,
+                            }
+                        }
+                    },
+                }
+            }
+        };
+
+        let usage_path = path.clone();
+        let disk_usage = tokio::task::spawn_blocking(move || disk_usage(&usage_path))
+            .await
+            .unwrap_or(0);
+        match state {
+            WorktreeGcState::Stale => report.reclaimable_bytes += disk_usage,
+            WorktreeGcState::Orphaned => report.orphaned_bytes += disk_usage,
+            _ => {}
+        }
+        report.entries.push(WorktreeGcEntry {
+            path: path.to_string_lossy().to_string(),
+            repo_path: repo.map(|r| r.to_string_lossy().to_string()),
+            branch,
+            state,
+            disk_usage,
+        });
+    }
+    report
+}
+
+async fn gc_apply_in(
+    base: &Path,
+    paths: &[PathBuf],
+    in_use: &[PathBuf],
+    force_orphaned: bool,
+) -> WorktreeGcResult {
+    let report = gc_scan_in(base, in_use).await;
+    let mut result = WorktreeGcResult::default();
+    for path in paths {
+        let key = path.to_string_lossy();
+        let Some(entry) = report.entries.iter().find(|e| e.path == key) else {
+            result
+                .skipped
+                .push((key.to_string(), "Not a Maestro-managed worktree".to_string()));
+            continue;
+        };
+        if !entry.state.is_removable(force_orphaned) {
+            let reason = match entry.state {
+                WorktreeGcState::Orphaned => {
+                    "Worktree is Orphaned; remove it explicitly to delete it".to_string()
+                }
+                state => format!("Worktree is {:?}", state),
+            };
+            result.skipped.push((entry.path.clone(), reason));
+            continue;
+        }
+
+        let removed = match (entry.state, &entry.repo_path) {
+            (WorktreeGcState::Stale, Some(repo)) => {
+                WorktreeManager::new()
+                    .remove(Path::new(repo), path)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            _ => {
+                let removed = tokio::fs::remove_dir_all(path)
+                    .await
+                    .map_err(|e| e.to_string());
+                if let Some(parent) = path.parent() {
+                    let _ = tokio::fs::remove_dir(parent).await; // only succeeds if empty
+                }
+                removed
+            }
+        };
+        match removed {
+            Ok(()) => {
+                log::info!("Collected worktree {}", entry.path);
+                result.reclaimed_bytes += entry.disk_usage;
+                result.removed.push(entry.path.clone());
+            }
+            Err(e) => {
+                log::warn!("Failed to collect worktree {}: {}", entry.path, e);
+                result.skipped.push((entry.path.clone(), e));
+            }
+        }
+    }
+    result
 }
 
 #[cfg(test)]
@@ -401,4 +662,76 @@ mod tests {
         let path2 = wm.worktree_path(&path, "branch-b").await;
         assert_ne!(path1, path2);
     }
+
+    #[tokio::test]
+    async fn test_gc_scan_and_apply() {
+        let (_dir, repo) = create_test_repo().await;
+        let base_dir = tempdir().unwrap();
+        let base = base_dir.path().join("worktrees");
+        let managed = base.join("0123456789abcdef");
+        let git = Git::new(&repo);
+
+        let mut paths = Vec::new();
+        for name in ["stale", "dirty", "live"] {
+            let wt_path = managed.join(name);
+            git.run(&["branch", name]).await.unwrap();
+            git.worktree_add(&wt_path, None, Some(name)).await.unwrap();
+            paths.push(wt_path);
+        }
+        let (stale, dirty, live) = (&paths[0], &paths[1], &paths[2]);
+        tokio::fs::write(dirty.join("notes.txt"), "wip").await.unwrap();
+
+        let orphan = managed.join("orphan");
+        tokio::fs::create_dir_all(&orphan).await.unwrap();
+        tokio::fs::write(orphan.join("leftover"), "12345").await.unwrap();
+
+        let gone = base.join("fedcba9876543210").join("gone");
+        tokio::fs::create_dir_all(&gone).await.unwrap();
+        tokio::fs::write(gone.join(".git"), "gitdir: /nonexistent/repo/.git/worktrees/gone\n")
+            .await
+            .unwrap();
+
+        let report = gc_scan_in(&base, std::slice::from_ref(live)).await;
+        let state_of = |path: &Path| {
+            report
+                .entries
+                .iter()
+                .find(|e| e.path == path.to_string_lossy())
+                .map(|e| e.state)
+        };
+        assert_eq!(report.entries.len(), 5);
+        assert_eq!(state_of(stale), Some(WorktreeGcState::Stale));
+        assert_eq!(state_of(dirty), Some(WorktreeGcState::Dirty));
+        assert_eq!(state_of(live), Some(WorktreeGcState::InUse));
+        assert_eq!(state_of(&orphan), Some(WorktreeGcState::Orphaned));
+        assert_eq!(state_of(&gone), Some(WorktreeGcState::MissingRepo));
+        let stale_entry = report.entries.iter().find(|e| e.state == WorktreeGcState::Stale);
+        assert_eq!(stale_entry.unwrap().branch.as_deref(), Some("stale"));
+        assert_eq!(report.orphaned_bytes, 5);
+
+        // The dry run changed nothing
+        assert!(stale.exists() && orphan.exists());
+
+        let requested = vec![
+            stale.clone(),
+            dirty.clone(),
+            orphan.clone(),
+            gone.clone(),
+            live.clone(),
+        ];
+        let result = gc_apply_in(&base, &requested, std::slice::from_ref(live), false).await;
+        assert_eq!(result.removed, vec![stale.to_string_lossy().to_string()]);
+        assert_eq!(result.skipped.len(), 4);
+        assert!(!stale.exists());
+        assert!(orphan.exists() && dirty.exists() && gone.exists() && live.exists());
+
+        // Orphans go only when explicitly forced
+        let result = gc_apply_in(&base, &requested, std::slice::from_ref(live), true).await;
+        assert_eq!(result.removed, vec![orphan.to_string_lossy().to_string()]);
+        assert!(!orphan.exists());
+        assert!(dirty.exists() && gone.exists() && live.exists());
+
+        let listed = git.worktree_list().await.unwrap();
+        assert!(!listed.iter().any(|wt| wt.branch.as_deref() == Some("stale")));
+    }
 }
@@ -16,6 +16,7 @@ use tokio::sync::Notify;
 #[cfg(unix)]
 use libc;
 
+use super::pty_sink::PtyOutput;
 use super::terminal_backend::{
     BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
     TerminalError, TerminalState,
@@ -237,6 +238,7 @@ impl TerminalBackend for XtermPassthroughBackend {
         let app = config.app_handle.clone();
         tokio::spawn(async move {
             let mut decoder = Utf8Decoder::new();
+            let mut end_offset = 0u64;
             loop {
                 tokio::select! {
                     data = rx.recv() => {
@@ -244,7 +246,9 @@ impl TerminalBackend for XtermPassthroughBackend {
                             Some(bytes) => {
                                 let text = decoder.decode(&bytes);
                                 if !text.is_empty() {
-                                    let _ = app.emit(&event_name, text);
+                                    end_offset += text.len() as u64;
+                                    let output = PtyOutput { data: text, end_offset };
+                                    let _ = app.emit(&event_name, output);
                                 }
                             }
                             None => break,
@@ -0,0 +1,702 @@
+//! App-side connection to the session daemon.
+//!
+//! `DaemonClient` lives in Tauri managed state. It connects lazily (starting
+//! the daemon binary if nothing is listening), multiplexes requests over one
+//! socket, and forwards pushed output and exits to a `PtyEventSink`. The
+//! connection watches every session, so the sink sees each exit and all
+//! output even while no pane shows the session; output of sessions that are
+//! not attached goes to `PtyEventSink::hidden_output`. When the app exits the
+//! socket closes, which the daemon treats as a detach; sessions keep running.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{oneshot, OnceCell};
+
+use super::error::DaemonError;
+use super::protocol::{
+    DaemonInfo, DaemonSessionInfo, Request, RequestEnvelope, ServerMessage, PROTOCOL_VERSION,
+};
+use crate::core::scrollback::ScrollbackChunk;
+use crate::core::windows_process::StdCommandExt;
+use crate::core::{PtyEventSink, PtyExit};
+
+/// How long to wait for a freshly started daemon to accept connections.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<ServerMessage>>>>;
+
+/// Routes daemon events to the app's sink, showing output only for sessions
+/// a pane has attached to.
+struct AttachedFilter {
+    sink: Arc<dyn PtyEventSink>,
+    attached: Arc<Mutex<HashSet<u32>>>,
+}
+
+impl AttachedFilter {
+    fn is_attached(&self, session_id: u32) -> bool {
+        self.attached
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&session_id)
+    }
+}
+
+impl PtyEventSink for AttachedFilter {
+    fn output(&self, session_id: u32, data: String, end_offset: u64) {
+        if self.is_attached(session_id) {
+            self.sink.output(session_id, data, end_offset);
+        } else {
+            self.sink.hidden_output(session_id, &data);
+        }
+    }
+
+    fn exit(&self, session_id: u32, status: PtyExit) {
+        self.attached
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&session_id);
+        self.sink.exit(session_id, status);
+    }
+}
+
+/// One live socket connection.
+struct Connection {
+    writer: tokio::sync::Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    pending: PendingMap,
+    next_id: AtomicU64,
+}
+
+impl Connection {
+    /// Splits the stream and starts the task that routes incoming messages.
+    fn start<R, W>(reader: R, writer: W, sink: Arc<dyn PtyEventSink>) -> Arc<Self>
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let pending_clone = pending.clone();
+
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match serde_json::from_str::<ServerMessage>(&line) {
+                    Ok(ServerMessage::Output {
+                        session_id,
+                        data,
+                        end_offset,
+                    }) => sink.output(session_id, data, end_offset),
+                    Ok(ServerMessage::Exit { session_id, status }) => sink.exit(session_id, status),
+                    Ok(response @ ServerMessage::Response { id, .. }) => {
+                        let waiter = pending_clone
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .remove(&id);
+                        if let Some(waiter) = waiter {
+                            let _ = waiter.send(response);
+                        }
+                    }
+                    Err(e) => log::warn!("Ignoring invalid daemon message: {}", e),
+                }
+            }
+            // Dropping the senders wakes every waiter with `Disconnected`
+            pending_clone
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+            log::info!("Disconnected from session daemon");
+        });
+
+        Arc::new(Self {
+            writer: tokio::sync::Mutex::new(Box::new(writer)),
+            pending,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    async fn request(&self, request: Request) -> Result<Value, DaemonError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, tx);
+
+        let mut line = serde_json::to_vec(&RequestEnvelope { id, request })
+            .map_err(|e| DaemonError::Protocol(e.to_string()))?;
+        line.push(b'\n');
+        {
+            let mut writer = self.writer.lock().await;
+            if let Err(e) = writer.write_all(&line).await {
+                self.pending
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&id);
+                return Err(e.into());
+            }
+        }
+
+        match rx.await.map_err(|_| DaemonError::Disconnected)? {
+            ServerMessage::Response {
+                error: Some(error), ..
+            } => Err(DaemonError::Remote(error)),
+            ServerMessage::Response { result, .. } => Ok(result.unwrap_or(Value::Null)),
+            ServerMessage::Output { .. } | ServerMessage::Exit { .. } => Err(
+                DaemonError::Protocol("session event routed as a response".to_string()),
+            ),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        // The reader task holds the only other reference to `pending`
+        Arc::strong_count(&self.pending) < 2
+    }
+}
+
+/// Client for the session daemon.
+pub struct DaemonClient {
+    socket_path: PathBuf,
+    sink: Arc<dyn PtyEventSink>,
+    /// Sessions whose output is shown, i.e. attached by a pane
+    attached: Arc<Mutex<HashSet<u32>>>,
+    /// Scrollback limit applied on every (re)connect; 0 keeps the daemon's
+    /// default
+    scrollback_limit: AtomicUsize,
+    connection: tokio::sync::Mutex<Option<Arc<Connection>>>,
+    enabled: OnceCell<bool>,
+}
+
+impl DaemonClient {
+    /// Creates a client for the daemon at `socket_path`. No connection is
+    /// made until the first request.
+    pub fn new(socket_path: impl Into<PathBuf>, sink: impl PtyEventSink + 'static) -> Self {
+        let attached = Arc::new(Mutex::new(HashSet::new()));
+        Self {
+            socket_path: socket_path.into(),
+            sink: Arc::new(AttachedFilter {
+                sink: Arc::new(sink),
+                attached: attached.clone(),
+            }),
+            attached,
+            scrollback_limit: AtomicUsize::new(0),
+            connection: tokio::sync::Mutex::new(None),
+            enabled: OnceCell::new(),
+        }
+    }
+
+    /// Sets the scrollback limit the daemon is given whenever the client
+    /// connects, without connecting now. Use `set_scrollback_limit` to also
+    /// update a running daemon.
+    pub fn configure_scrollback_limit(&self, limit: usize) {
+        self.scrollback_limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// Whether sessions should be hosted by the daemon for this app run.
+    ///
+    /// Decided once, on first use: `true` if the daemon could be reached (or
+    /// started) and speaks this build's protocol. Otherwise the app falls
+    /// back to its in-process `ProcessManager` for the whole run, so session
+    /// IDs from the two never mix.
+    pub async fn enabled(&self) -> bool {
+        *self
+            .enabled
+            .get_or_init(|| async {
+                match self.ping().await {
+                    Ok(info) if info.protocol_version == PROTOCOL_VERSION => true,
+                    Ok(info) => {
+                        log::warn!(
+                            "Session daemon (pid={}) speaks protocol {}, expected {}; \
+                             hosting sessions in-process",
+                            info.pid,
+                            info.protocol_version,
+                            PROTOCOL_VERSION
+                        );
+                        false
+                    }
+                    Err(e) => {
+                        log::info!(
+                            "Session daemon unavailable, hosting sessions in-process: {}",
+                            e
+                        );
+                        false
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Returns the current connection, connecting (and starting the daemon
+    /// if needed) when there is none or the previous one dropped.
+    async fn connection(&self) -> Result<Arc<Connection>, DaemonError> {
+        let mut guard = self.connection.lock().await;
+        if let Some(conn) = guard.as_ref() {
+            if !conn.is_closed() {
+                return Ok(conn.clone());
+            }
+        }
+
+        let conn = match connect(&self.socket_path, self.sink.clone()).await {
+            Ok(conn) => conn,
+            Err(DaemonError::NotRunning { .. }) => {
+                start_daemon(&self.socket_path)?;
+                wait_for_daemon(&self.socket_path, self.sink.clone()).await?
+            }
+            Err(e) => return Err(e),
+        };
+        self.set_up(&conn).await;
+        *guard = Some(conn.clone());
+        Ok(conn)
+    }
+
+    /// Subscribes a new connection to every session and applies the
+    /// configured scrollback limit. Failures are logged: a daemon speaking
+    /// another protocol is reported by `enabled` instead.
+    async fn set_up(&self, conn: &Connection) {
+        if let Err(e) = conn.request(Request::Watch).await {
+            log::warn!("Failed to watch daemon sessions: {}", e);
+        }
+        let limit = self.scrollback_limit.load(Ordering::Relaxed);
+        if limit > 0 {
+            if let Err(e) = conn.request(Request::SetScrollbackLimit { limit }).await {
+                log::warn!("Failed to set daemon scrollback limit: {}", e);
+            }
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, request: Request) -> Result<T, DaemonError> {
+        let value = self.connection().await?.request(request).await?;
+        serde_json::from_value(value).map_err(|e| DaemonError::Protocol(e.to_string()))
+    }
+
+    /// Checks the daemon is up, starting it if necessary.
+    pub async fn ping(&self) -> Result<DaemonInfo, DaemonError> {
+        self.call(Request::Ping).await
+    }
+
+    /// Spawns a login shell owned by the daemon.
+    pub async fn spawn(
+        &self,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+    ) -> Result<u32, DaemonError> {
+        self.call(Request::Spawn { cwd, env }).await
+    }
+
+    /// Runs `program` directly as a daemon-owned PTY's child.
+    pub async fn spawn_command(
+        &self,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+    ) -> Result<u32, DaemonError> {
+        self.call(Request::SpawnCommand {
+            program,
+            args,
+            cwd,
+            env,
+        })
+        .await
+    }
+
+    /// Lists the daemon's sessions.
+    pub async fn list(&self) -> Result<Vec<DaemonSessionInfo>, DaemonError> {
+        self.call(Request::List).await
+    }
+
+    /// Attaches to a session and returns its scrollback since `since_offset`.
+    /// Live output follows through the sink's `output`, starting right after
+    /// the chunk; output that raced with the attach may overlap it and is
+    /// told apart by its end offset.
+    pub async fn attach(
+        &self,
+        session_id: u32,
+        since_offset: u64,
+    ) -> Result<ScrollbackChunk, DaemonError> {
+        self.set_attached(session_id, true);
+        let result = self
+            .call(Request::Attach {
+                session_id,
+                since_offset,
+            })
+            .await;
+        if result.is_err() {
+            self.set_attached(session_id, false);
+        }
+        result
+    }
+
+    /// Stops showing a session's output. The session keeps running and its
+    /// output and exit still reach the sink, as `hidden_output` and `exit`.
+    pub async fn detach(&self, session_id: u32) -> Result<(), DaemonError> {
+        self.set_attached(session_id, false);
+        self.call(Request::Detach { session_id }).await
+    }
+
+    fn set_attached(&self, session_id: u32, attached: bool) {
+        let mut set = self.attached.lock().unwrap_or_else(|e| e.into_inner());
+        if attached {
+            set.insert(session_id);
+        } else {
+            set.remove(&session_id);
+        }
+    }
+
+    /// Writes to a session's stdin.
+    pub async fn write(&self, session_id: u32, data: String) -> Result<(), DaemonError> {
+        self.call(Request::Write { session_id, data }).await
+    }
+
+    /// Resizes a session's PTY.
+    pub async fn resize(&self, session_id: u32, rows: u16, cols: u16) -> Result<(), DaemonError> {
+        self.call(Request::Resize {
+            session_id,
+            rows,
+            cols,
+        })
+        .await
+    }
+
+    /// Terminates a session.
+    pub async fn kill(&self, session_id: u32) -> Result<(), DaemonError> {
+        self.call(Request::Kill { session_id }).await
+    }
+
+    /// Returns the root PID of a session, or `None` if it is not running.
+    pub async fn session_pid(&self, session_id: u32) -> Result<Option<i32>, DaemonError> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .find(|s| s.id == session_id)
+            .map(|s| s.pid))
+    }
+
+    /// Returns every session's ID and root PID.
+    pub async fn session_pids(&self) -> Result<Vec<(u32, i32)>, DaemonError> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s.pid))
+            .collect())
+    }
+
+    /// Starts recording a session into `dir` and returns the file's path.
+    pub async fn start_recording(
+        &self,
+        session_id: u32,
+        dir: &Path,
+    ) -> Result<PathBuf, DaemonError> {
+        self.call(Request::StartRecording {
+            session_id,
+            dir: dir.to_string_lossy().into_owned(),
+        })
+        .await
+    }
+
+    /// Stops recording a session and returns the finished file's path.
+    pub async fn stop_recording(&self, session_id: u32) -> Result<Option<PathBuf>, DaemonError> {
+        self.call(Request::StopRecording { session_id }).await
+    }
+
+    /// Returns the path of the recording a session is writing, if any.
+    pub async fn recording_path(&self, session_id: u32) -> Result<Option<PathBuf>, DaemonError> {
+        self.call(Request::RecordingPath { session_id }).await
+    }
+
+    /// Sets the daemon's scrollback limit now and on every reconnect.
+    pub async fn set_scrollback_limit(&self, limit: usize) -> Result<(), DaemonError> {
+        self.configure_scrollback_limit(limit);
+        self.call(Request::SetScrollbackLimit { limit }).await
+    }
+
+    /// Kills every daemon session and stops the daemon.
+    pub async fn shutdown(&self) -> Result<(), DaemonError> {
+        self.call(Request::Shutdown).await
+    }
+}
+
+#[cfg(unix)]
+async fn connect(
+    socket_path: &Path,
+    sink: Arc<dyn PtyEventSink>,
+) -> Result<Arc<Connection>, DaemonError> {
+    let stream = tokio::net::UnixStream::connect(socket_path)
+        .await
+        .map_err(|source| DaemonError::NotRunning {
+            path: socket_path.to_path_buf(),
+            source,
+        })?;
+    let (reader, writer) = stream.into_split();
+    Ok(Connection::start(reader, writer, sink))
+}
+
+#[cfg(not(unix))]
+async fn connect(
+    _socket_path: &Path,
+    _sink: Arc<dyn PtyEventSink>,
+) -> Result<Arc<Connection>, DaemonError> {
+    Err(DaemonError::Unsupported)
+}
+
+/// Launches `maestro-daemon` detached from the app's process group so it
+/// survives the app exiting.
+///
+/// The binary is expected next to the app executable; `MAESTRO_DAEMON_BIN`
+/// overrides the location.
+fn start_daemon(socket_path: &Path) -> Result<(), DaemonError> {
+    let program = match std::env::var_os("MAESTRO_DAEMON_BIN") {
+        Some(path) => PathBuf::from(path),
+        None => std::env::current_exe()?.with_file_name("maestro-daemon"),
+    };
+
+    let mut command = std::process::Command::new(&program);
+    command
+        .env(super::SOCKET_ENV, socket_path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .hide_console_window();
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let child = command.spawn()?;
+    log::info!(
+        "Started session daemon {} (pid={})",
+        program.display(),
+        child.id()
+    );
+    Ok(())
+}
+
+/// Polls the socket until a newly started daemon accepts connections.
+async fn wait_for_daemon(
+    socket_path: &Path,
+    sink: Arc<dyn PtyEventSink>,
+) -> Result<Arc<Connection>, DaemonError> {
+    let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        match connect(socket_path, sink.clone()).await {
+            Err(DaemonError::NotRunning { .. }) if tokio::time::Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Collects output and exits pushed by the daemon.
+    #[derive(Clone, Default)]
+    struct RecordingSink {
+        output: Arc<Mutex<Vec<(u32, String, u64)>>>,
+        hidden: Arc<Mutex<String>>,
+        exits: Arc<Mutex<Vec<(u32, PtyExit)>>>,
+    }
+
+    impl PtyEventSink for RecordingSink {
+        fn output(&self, session_id: u32, data: String, end_offset: u64) {
+            self.output
+                .lock()
+                .unwrap()
+                .push((session_id, data, end_offset));
+        }
+
+        fn hidden_output(&self, _session_id: u32, data: &str) {
+            self.hidden.lock().unwrap().push_str(data);
+        }
+
+        fn exit(&self, session_id: u32, status: PtyExit) {
+            self.exits.lock().unwrap().push((session_id, status));
+        }
+    }
+
+    impl RecordingSink {
+        fn text(&self, session_id: u32) -> String {
+            self.output
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(id, _, _)| *id == session_id)
+                .map(|(_, data, _)| data.as_str())
+                .collect()
+        }
+    }
+
+    async fn wait_for(mut check: impl FnMut() -> bool) -> bool {
+        for _ in 0..100 {
+            if check() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_sessions_survive_client_disconnect() {
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server_socket = socket.clone();
+        let server = tokio::spawn(async move { super::super::server::serve(&server_socket).await });
+
+        let sink = RecordingSink::default();
+        let conn = wait_for_daemon(&socket, Arc::new(sink.clone()))
+            .await
+            .unwrap();
+        let id: u32 = serde_json::from_value(
+            conn.request(Request::Spawn {
+                cwd: Some(dir.path().to_string_lossy().into_owned()),
+                env: None,
+            })
+            .await
+            .unwrap(),
+        )
+        .unwrap();
+        conn.request(Request::Attach {
+            session_id: id,
+            since_offset: 0,
+        })
+        .await
+        .unwrap();
+        conn.request(Request::Write {
+            session_id: id,
+            data: "echo daemon-$((40 + 2))\n".to_string(),
+        })
+        .await
+        .unwrap();
+        assert!(wait_for(|| sink.text(id).contains("daemon-42")).await);
+        drop(conn);
+
+        // A second client sees the same session and its scrollback
+        let client = DaemonClient::new(&socket, RecordingSink::default());
+        let sessions = client.list().await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, id);
+        let chunk = client.attach(id, 0).await.unwrap();
+        assert!(chunk.data.contains("daemon-42"));
+
+        client.shutdown().await.unwrap();
+        server.await.unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+
+    #[tokio::test]
+    async fn test_exit_is_forwarded_and_session_untracked() {
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server_socket = socket.clone();
+        let server = tokio::spawn(async move { super::super::server::serve(&server_socket).await });
+
+        let sink = RecordingSink::default();
+        wait_for_daemon(&socket, Arc::new(sink.clone()))
+            .await
+            .unwrap();
+        let client = DaemonClient::new(&socket, sink.clone());
+        let id = client
+            .spawn(Some(dir.path().to_string_lossy().into_owned()), None)
+            .await
+            .unwrap();
+        client.attach(id, 0).await.unwrap();
+        assert_eq!(client.ping().await.unwrap().session_count, 1);
+
+        client.write(id, "exit 3\n".to_string()).await.unwrap();
+        assert!(wait_for(|| !sink.exits.lock().unwrap().is_empty()).await);
+        let (exited, status) = sink.exits.lock().unwrap()[0].clone();
+        assert_eq!(exited, id);
+        assert_eq!(status.exit_code, 3);
+        assert!(!status.killed);
+
+        assert_eq!(client.ping().await.unwrap().session_count, 0);
+        assert!(client.list().await.unwrap().is_empty());
+
+        client.shutdown().await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_unattached_sessions_reach_the_sink() {
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server_socket = socket.clone();
+        let server = tokio::spawn(async move { super::super::server::serve(&server_socket).await });
+
+        let sink = RecordingSink::default();
+        wait_for_daemon(&socket, Arc::new(sink.clone()))
+            .await
+            .unwrap();
+        let client = DaemonClient::new(&socket, sink.clone());
+        client.configure_scrollback_limit(64 * 1024);
+        let id = client
+            .spawn(Some(dir.path().to_string_lossy().into_owned()), None)
+            .await
+            .unwrap();
+
+        // Never attached: output is hidden, but the exit still arrives
+        client
+            .write(id, "echo hidden-$((40 + 2)); exit 4\n".to_string())
+            .await
+            .unwrap();
+        assert!(wait_for(|| !sink.exits.lock().unwrap().is_empty()).await);
+        assert_eq!(sink.exits.lock().unwrap()[0].1.exit_code, 4);
+        assert!(sink.hidden.lock().unwrap().contains("hidden-42"));
+        assert!(sink.text(id).is_empty());
+
+        client.shutdown().await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_kill_does_not_stall_the_connection() {
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server_socket = socket.clone();
+        let server = tokio::spawn(async move { super::super::server::serve(&server_socket).await });
+
+        let sink = RecordingSink::default();
+        wait_for_daemon(&socket, Arc::new(sink.clone()))
+            .await
+            .unwrap();
+        let client = Arc::new(DaemonClient::new(&socket, sink.clone()));
+        // Ignores SIGTERM, so the kill waits out the grace period
+        let id = client
+            .spawn_command(
+                "sh".to_string(),
+                vec![
+                    "-c".to_string(),
+                    "trap '' TERM; echo ready; while :; do sleep 0.1; done".to_string(),
+                ],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(wait_for(|| sink.hidden.lock().unwrap().contains("ready")).await);
+
+        let killer = client.clone();
+        let kill = tokio::spawn(async move { killer.kill(id).await });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let ping = tokio::time::timeout(Duration::from_secs(1), client.ping()).await;
+        assert!(ping.is_ok(), "ping waited for the kill");
+        assert!(!kill.is_finished());
+
+        kill.await.unwrap().unwrap();
+        assert_eq!(client.ping().await.unwrap().session_count, 0);
+        client.shutdown().await.unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
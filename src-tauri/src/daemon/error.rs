@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+/// Errors talking to the session daemon, serialized as a string to the
+/// Tauri frontend like `GitError`.
+#[derive(Debug, thiserror::Error)]
+pub enum DaemonError {
+    /// The daemon is only available on Unix (it listens on a Unix socket).
+    #[error("the session daemon is not supported on this platform")]
+    Unsupported,
+
+    /// No daemon is listening on the socket, and none could be started.
+    #[error("session daemon is not running at {path}: {source}")]
+    NotRunning {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The connection to the daemon was closed.
+    #[error("connection to the session daemon was lost")]
+    Disconnected,
+
+    /// The daemon sent a message that could not be decoded.
+    #[error("invalid message from session daemon: {0}")]
+    Protocol(String),
+
+    /// The request was rejected before it was sent.
+    #[error("{0}")]
+    InvalidRequest(String),
+
+    /// The daemon processed the request and reported an error.
+    #[error("{0}")]
+    Remote(String),
+
+    /// Socket I/O failed.
+    #[error("session daemon I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl serde::Serialize for DaemonError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
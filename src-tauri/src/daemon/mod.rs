@@ -0,0 +1,44 @@
+//! Headless session daemon.
+//!
+//! `maestro-daemon` owns PTYs in a process separate from the Tauri app, so
+//! long-running agents survive the window closing. The app attaches through
+//! `DaemonClient` over a Unix socket; see `protocol` for the wire format.
+
+pub mod client;
+pub mod error;
+pub mod protocol;
+#[cfg(unix)]
+pub mod server;
+
+use std::path::PathBuf;
+
+pub use client::DaemonClient;
+pub use error::DaemonError;
+
+/// Environment variable overriding the socket location for both the daemon
+/// and the app.
+pub const SOCKET_ENV: &str = "MAESTRO_DAEMON_SOCKET";
+
+/// Returns the daemon socket path: `SOCKET_ENV` if set, otherwise
+/// `daemon.sock` in `default_socket_dir()`.
+pub fn socket_path() -> PathBuf {
+    match std::env::var_os(SOCKET_ENV) {
+        Some(path) => PathBuf::from(path),
+        None => default_socket_dir().join("daemon.sock"),
+    }
+}
+
+/// Directory Maestro creates for its socket when `SOCKET_ENV` is unset.
+///
+/// Prefers the per-user runtime directory (`$XDG_RUNTIME_DIR` on Linux) and
+/// falls back to the app data directory, matching `worktree_manager`. The
+/// daemon restricts this directory, and only this one, to the current user.
+pub fn default_socket_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "maestro", "maestro")
+        .map(|p| {
+            p.runtime_dir()
+                .unwrap_or_else(|| p.data_dir())
+                .to_path_buf()
+        })
+        .unwrap_or_else(|| std::env::temp_dir().join("maestro"))
+}
@@ -0,0 +1,214 @@
+//! Wire protocol between the daemon and its clients.
+//!
+//! Messages are newline-delimited JSON over a Unix socket. Clients send
+//! `RequestEnvelope`s; the daemon answers each with a `ServerMessage::Response`
+//! carrying the same `id`, and pushes `ServerMessage::Output` and
+//! `ServerMessage::Exit` for every session the connection is attached to, or
+//! for every session at all once it sent `Request::Watch`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::PtyExit;
+
+/// Bumped whenever a message changes shape incompatibly.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// A request from a client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum Request {
+    /// Health check; returns `DaemonInfo`.
+    Ping,
+    /// Spawns a login shell; returns the new session ID.
+    Spawn {
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default)]
+        env: Option<HashMap<String, String>>,
+    },
+    /// Runs `program` directly as the PTY's child (see
+    /// `ProcessManager::spawn_command`); returns the new session ID.
+    SpawnCommand {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default)]
+        env: Option<HashMap<String, String>>,
+    },
+    /// Lists live sessions; returns `Vec<DaemonSessionInfo>`.
+    List,
+    /// Forwards every session's output and exit to this connection, attached
+    /// or not. Output of unattached sessions is sent as it arrives, without
+    /// gap-free resync. The app sends this so it sees exits and can run
+    /// status inference for sessions no pane is showing.
+    Watch,
+    /// Subscribes this connection to a session's output and returns the
+    /// scrollback since `since_offset` as a `ScrollbackChunk`.
+    Attach {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+        #[serde(default, rename = "sinceOffset")]
+        since_offset: u64,
+    },
+    /// Stops forwarding a session's output to this connection. The session
+    /// keeps running.
+    Detach {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+    },
+    /// Writes to a session's stdin.
+    Write {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+        data: String,
+    },
+    /// Resizes a session's PTY.
+    Resize {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+        rows: u16,
+        cols: u16,
+    },
+    /// Terminates a session.
+    Kill {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+    },
+    /// Starts recording a session into `dir`; returns the recording's path.
+    StartRecording {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+        dir: String,
+    },
+    /// Stops recording a session; returns the finished recording's path, or
+    /// null if it was not being recorded.
+    StopRecording {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+    },
+    /// Returns the path of the recording a session is writing, or null.
+    RecordingPath {
+        #[serde(rename = "sessionId")]
+        session_id: u32,
+    },
+    /// Sets the per-session scrollback limit in bytes for new and running
+    /// sessions.
+    SetScrollbackLimit { limit: usize },
+    /// Kills every session and stops the daemon.
+    Shutdown,
+}
+
+/// A request tagged with a client-chosen ID for matching the response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    pub id: u64,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// A message from the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Reply to the request with the same `id`. Exactly one of `result` and
+    /// `error` is set.
+    Response {
+        id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Output from an attached or watched session. Covers the scrollback range
+    /// `end_offset - data.len()..end_offset`.
+    #[serde(rename_all = "camelCase")]
+    Output {
+        session_id: u32,
+        data: String,
+        end_offset: u64,
+    },
+    /// An attached or watched session's child exited. Sent after its remaining output;
+    /// the session is gone from the daemon afterwards.
+    #[serde(rename_all = "camelCase")]
+    Exit { session_id: u32, status: PtyExit },
+}
+
+impl ServerMessage {
+    /// Builds a success response.
+    pub fn ok(id: u64, result: Value) -> Self {
+        Self::Response {
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Builds an error response.
+    pub fn err(id: u64, error: impl Into<String>) -> Self {
+        Self::Response {
+            id,
+            result: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Returned by `Request::Ping`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonInfo {
+    pub pid: u32,
+    pub protocol_version: u32,
+    pub session_count: usize,
+}
+
+/// A session owned by the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonSessionInfo {
+    pub id: u32,
+    pub pid: i32,
+    pub cwd: Option<String>,
+    /// Unix epoch milliseconds when the session was spawned.
+    pub created_at: u64,
+    /// Number of connections currently attached.
+    pub attached_clients: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_wire_format() {
+        let parsed: RequestEnvelope = serde_json::from_str(
+            r#"{"id":7,"method":"attach","params":{"sessionId":3,"sinceOffset":42}}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.id, 7);
+        assert!(matches!(
+            parsed.request,
+            Request::Attach {
+                session_id: 3,
+                since_offset: 42
+            }
+        ));
+
+        // Unit requests need no params
+        let parsed: RequestEnvelope = serde_json::from_str(r#"{"id":1,"method":"list"}"#).unwrap();
+        assert!(matches!(parsed.request, Request::List));
+    }
+
+    #[test]
+    fn test_response_omits_unset_fields() {
+        let json = serde_json::to_value(ServerMessage::err(2, "nope")).unwrap();
+        assert_eq!(json["type"], "response");
+        assert_eq!(json["error"], "nope");
+        assert!(json.get("result").is_none());
+    }
+}
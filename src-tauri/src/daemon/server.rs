@@ -0,0 +1,515 @@
+//! The daemon process: owns the PTYs and serves clients on a Unix socket.
+//!
+//! Each connection gets its own task. Output from every session is published
+//! on one broadcast channel, and each connection forwards only the sessions
+//! it has attached to, or all of them once it sent `watch`. Per-connection
+//! offsets make delivery gap-free for attached sessions: after an `attach`
+//! the connection resumes exactly where the returned scrollback ended, and if
+//! the connection falls behind the broadcast channel it re-reads the missed
+//! range from scrollback instead of dropping output. When a session's child
+//! exits, attached and watching connections get a final `exit` message and
+//! the session is dropped from the daemon.
+//!
+//! Requests are answered in order, except `kill`, which waits out the child's
+//! SIGTERM grace period in its own task so the connection keeps serving its
+//! other sessions meanwhile.
+
+use std::collections::HashMap;
+use std::io;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc, watch};
+
+use super::protocol::{
+    DaemonInfo, DaemonSessionInfo, Request, RequestEnvelope, ServerMessage, PROTOCOL_VERSION,
+};
+use crate::core::{ProcessManager, PtyEventSink, PtyExit};
+
+/// Output messages buffered per connection before it counts as lagging.
+const OUTPUT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Publishes session output and exits on the daemon's broadcast channel.
+struct BroadcastSink {
+    output: broadcast::Sender<ServerMessage>,
+    sessions: Arc<DashMap<u32, SessionMeta>>,
+}
+
+impl PtyEventSink for BroadcastSink {
+    fn output(&self, session_id: u32, data: String, end_offset: u64) {
+        // No receivers just means nobody is attached right now
+        let _ = self.output.send(ServerMessage::Output {
+            session_id,
+            data,
+            end_offset,
+        });
+    }
+
+    fn exit(&self, session_id: u32, status: PtyExit) {
+        // `ProcessManager` has already dropped the session
+        self.sessions.remove(&session_id);
+        let _ = self.output.send(ServerMessage::Exit { session_id, status });
+    }
+}
+
+/// Metadata the daemon keeps alongside each PTY.
+struct SessionMeta {
+    cwd: Option<String>,
+    created_at: u64,
+    attached_clients: usize,
+}
+
+/// State of one client connection.
+struct ClientState {
+    /// Session ID -> offset of the last byte delivered to this connection
+    attached: HashMap<u32, u64>,
+    /// Whether unattached sessions are forwarded too
+    watching: bool,
+    /// Replies finished outside the request loop, e.g. by `kill`
+    replies: mpsc::UnboundedSender<ServerMessage>,
+}
+
+/// Shared state for all connections.
+struct DaemonServer {
+    processes: ProcessManager,
+    sessions: Arc<DashMap<u32, SessionMeta>>,
+    output: broadcast::Sender<ServerMessage>,
+    shutdown: watch::Sender<bool>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Binds `socket_path` and serves clients until a `shutdown` request or
+/// SIGTERM/SIGINT arrives, then kills every session and removes the socket.
+///
+/// Fails with `AddrInUse` if another daemon is already listening. A stale
+/// socket file left by a crashed daemon is removed.
+pub async fn serve(socket_path: &Path) -> io::Result<()> {
+    let listener = bind(socket_path).await?;
+    log::info!("Session daemon listening on {}", socket_path.display());
+
+    let (output, _) = broadcast::channel(OUTPUT_CHANNEL_CAPACITY);
+    let (shutdown, mut shutdown_rx) = watch::channel(false);
+    let server = Arc::new(DaemonServer {
+        processes: ProcessManager::new(),
+        sessions: Arc::new(DashMap::new()),
+        output,
+        shutdown,
+    });
+
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(server.clone().handle_connection(stream));
+                }
+                Err(e) => log::warn!("Failed to accept daemon connection: {}", e),
+            },
+            _ = shutdown_rx.changed() => break,
+            _ = sigterm.recv() => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    log::info!("Session daemon shutting down");
+    let _ = server.shutdown.send(true);
+    if let Err(e) = server.processes.kill_all_sessions().await {
+        log::warn!("Failed to kill daemon sessions: {}", e);
+    }
+    let _ = std::fs::remove_file(socket_path);
+    Ok(())
+}
+
+/// Binds the listener, clearing a stale socket and restricting access to
+/// the current user.
+///
+/// Only Maestro's own socket directory (`default_socket_dir`) is created and
+/// locked down; the parent of a socket placed elsewhere via `SOCKET_ENV` is
+/// left untouched.
+async fn bind(socket_path: &Path) -> io::Result<UnixListener> {
+    if socket_path.parent() == Some(super::default_socket_dir().as_path()) {
+        prepare_socket_dir(&super::default_socket_dir())?;
+    }
+
+    if socket_path.exists() {
+        if UnixStream::connect(socket_path).await.is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", socket_path.display()),
+            ));
+        }
+        std::fs::remove_file(socket_path)?;
+    }
+
+    let listener = UnixListener::bind(socket_path)?;
+    std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Creates `dir` if needed and restricts it to the current user. Refuses a
+/// directory owned by someone else, e.g. one pre-created in a shared temp
+/// directory.
+fn prepare_socket_dir(dir: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let metadata = std::fs::symlink_metadata(dir)?;
+    // SAFETY: geteuid has no preconditions and cannot fail
+    let uid = unsafe { libc::geteuid() };
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is not a directory owned by the current user",
+                dir.display()
+            ),
+        ));
+    }
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+}
+
+/// Serializes a message as one JSON line.
+async fn send<W: AsyncWrite + Unpin>(writer: &mut W, message: &ServerMessage) -> io::Result<()> {
+    let mut line =
+        serde_json::to_vec(message).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    line.push(b'\n');
+    writer.write_all(&line).await
+}
+
+/// Trims `data` to the part past `last_sent`, or returns `None` if the
+/// connection already has all of it.
+fn unsent_part(data: String, end_offset: u64, last_sent: u64) -> Option<String> {
+    if end_offset <= last_sent {
+        return None;
+    }
+    let start_offset = end_offset.saturating_sub(data.len() as u64);
+    if start_offset >= last_sent {
+        return Some(data);
+    }
+    // Offsets always fall on character boundaries, but don't panic if not
+    data.get((last_sent - start_offset) as usize..)
+        .map(str::to_string)
+}
+
+impl DaemonServer {
+    fn sink(&self) -> BroadcastSink {
+        BroadcastSink {
+            output: self.output.clone(),
+            sessions: self.sessions.clone(),
+        }
+    }
+
+    /// Starts tracking metadata for a freshly spawned session.
+    fn track(&self, session_id: u32, cwd: Option<String>) {
+        self.sessions.insert(
+            session_id,
+            SessionMeta {
+                cwd,
+                created_at: now_millis(),
+                attached_clients: 0,
+            },
+        );
+        // A child that exited before the insert was already reported to the
+        // sink, which found nothing to remove
+        if self.processes.get_session_pid(session_id).is_none() {
+            self.sessions.remove(&session_id);
+        }
+    }
+
+    async fn handle_connection(self: Arc<Self>, stream: UnixStream) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut output = self.output.subscribe();
+        let mut shutdown = self.shutdown.subscribe();
+        let (replies, mut deferred) = mpsc::unbounded_channel();
+        let mut client = ClientState {
+            attached: HashMap::new(),
+            watching: false,
+            replies,
+        };
+
+        loop {
+            let result = tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) if line.trim().is_empty() => Ok(()),
+                    Ok(Some(line)) => match self.handle_line(&line, &mut client).await {
+                        Some(reply) => send(&mut writer, &reply).await,
+                        None => Ok(()),
+                    },
+                    Ok(None) => break,
+                    Err(e) => Err(e),
+                },
+                Some(reply) = deferred.recv() => send(&mut writer, &reply).await,
+                message = output.recv() => match message {
+                    Ok(ServerMessage::Output { session_id, data, end_offset }) => {
+                        let data = match client.attached.get_mut(&session_id) {
+                            Some(last_sent) => {
+                                let unsent = unsent_part(data, end_offset, *last_sent);
+                                if unsent.is_some() {
+                                    *last_sent = end_offset;
+                                }
+                                unsent
+                            }
+                            None if client.watching => Some(data),
+                            None => None,
+                        };
+                        match data {
+                            Some(data) => {
+                                send(&mut writer, &ServerMessage::Output {
+                                    session_id,
+                                    data,
+                                    end_offset,
+                                })
+                                .await
+                            }
+                            None => Ok(()),
+                        }
+                    }
+                    Ok(ServerMessage::Exit { session_id, status }) => {
+                        if client.attached.remove(&session_id).is_some() || client.watching {
+                            send(&mut writer, &ServerMessage::Exit { session_id, status }).await
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    Ok(_) => Ok(()),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!(
+                            "Daemon client lagged by {} output messages, resyncing from scrollback",
+                            skipped
+                        );
+                        self.resync(&mut writer, &mut client.attached).await
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = shutdown.changed() => break,
+            };
+
+            if let Err(e) = result {
+                log::debug!("Daemon client connection closed: {}", e);
+                break;
+            }
+        }
+
+        // Closing the connection is an implicit detach from everything
+        for session_id in client.attached.keys() {
+            if let Some(mut meta) = self.sessions.get_mut(session_id) {
+                meta.attached_clients = meta.attached_clients.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Re-sends everything each attached session produced since the last
+    /// delivered offset.
+    async fn resync<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        attached: &mut HashMap<u32, u64>,
+    ) -> io::Result<()> {
+        for (&session_id, last_sent) in attached.iter_mut() {
+            let Ok(chunk) = self.processes.get_scrollback(session_id, *last_sent) else {
+                continue;
+            };
+            if chunk.data.is_empty() {
+                continue;
+            }
+            *last_sent = chunk.end_offset;
+            send(
+                writer,
+                &ServerMessage::Output {
+                    session_id,
+                    data: chunk.data,
+                    end_offset: chunk.end_offset,
+                },
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Handles one request line. Returns `None` when the reply is sent later
+    /// through `ClientState::replies`.
+    async fn handle_line(
+        self: &Arc<Self>,
+        line: &str,
+        client: &mut ClientState,
+    ) -> Option<ServerMessage> {
+        let envelope: RequestEnvelope = match serde_json::from_str(line) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                // Answer with id 0 so the client at least sees the failure
+                return Some(ServerMessage::err(0, format!("Invalid request: {}", e)));
+            }
+        };
+
+        let id = envelope.id;
+        if let Request::Kill { session_id } = envelope.request {
+            // Attached and watching connections, this one included, still
+            // get the `exit` message the kill produces
+            let server = self.clone();
+            let replies = client.replies.clone();
+            tokio::spawn(async move {
+                let reply = match server.processes.kill_session(session_id).await {
+                    Ok(()) => ServerMessage::ok(id, Value::Null),
+                    Err(e) => ServerMessage::err(id, e.to_string()),
+                };
+                let _ = replies.send(reply);
+            });
+            return None;
+        }
+
+        Some(match self.handle_request(envelope.request, client).await {
+            Ok(result) => ServerMessage::ok(id, result),
+            Err(e) => ServerMessage::err(id, e),
+        })
+    }
+
+    async fn handle_request(
+        &self,
+        request: Request,
+        client: &mut ClientState,
+    ) -> Result<Value, String> {
+        match request {
+            Request::Ping => Ok(json!(DaemonInfo {
+                pid: std::process::id(),
+                protocol_version: PROTOCOL_VERSION,
+                session_count: self.sessions.len(),
+            })),
+            Request::Spawn { cwd, env } => {
+                let id = self
+                    .processes
+                    .spawn_shell(self.sink(), cwd.clone(), env)
+                    .map_err(|e| e.to_string())?;
+                self.track(id, cwd);
+                Ok(json!(id))
+            }
+            Request::SpawnCommand {
+                program,
+                args,
+                cwd,
+                env,
+            } => {
+                let id = self
+                    .processes
+                    .spawn_command(self.sink(), &program, &args, cwd.clone(), env)
+                    .map_err(|e| e.to_string())?;
+                self.track(id, cwd);
+                Ok(json!(id))
+            }
+            Request::List => {
+                let mut sessions: Vec<DaemonSessionInfo> = self
+                    .sessions
+                    .iter()
+                    .filter_map(|entry| {
+                        let pid = self.processes.get_session_pid(*entry.key())?;
+                        Some(DaemonSessionInfo {
+                            id: *entry.key(),
+                            pid,
+                            cwd: entry.cwd.clone(),
+                            created_at: entry.created_at,
+                            attached_clients: entry.attached_clients,
+                        })
+                    })
+                    .collect();
+                sessions.sort_by_key(|s| s.id);
+                Ok(json!(sessions))
+            }
+            Request::Watch => {
+                client.watching = true;
+                Ok(Value::Null)
+            }
+            Request::Attach {
+                session_id,
+                since_offset,
+            } => {
+                let chunk = self
+                    .processes
+                    .get_scrollback(session_id, since_offset)
+                    .map_err(|e| e.to_string())?;
+                if client
+                    .attached
+                    .insert(session_id, chunk.end_offset)
+                    .is_none()
+                {
+                    if let Some(mut meta) = self.sessions.get_mut(&session_id) {
+                        meta.attached_clients += 1;
+                    }
+                }
+                Ok(json!(chunk))
+            }
+            Request::Detach { session_id } => {
+                if client.attached.remove(&session_id).is_some() {
+                    if let Some(mut meta) = self.sessions.get_mut(&session_id) {
+                        meta.attached_clients = meta.attached_clients.saturating_sub(1);
+                    }
+                }
+                Ok(Value::Null)
+            }
+            Request::Write { session_id, data } => {
+                self.processes
+                    .write_stdin(session_id, &data)
+                    .map_err(|e| e.to_string())?;
+                Ok(Value::Null)
+            }
+            Request::Resize {
+                session_id,
+                rows,
+                cols,
+            } => {
+                self.processes
+                    .resize_pty(session_id, rows, cols)
+                    .map_err(|e| e.to_string())?;
+                Ok(Value::Null)
+            }
+            Request::Kill { .. } => unreachable!("kill is handled in handle_line"),
+            Request::StartRecording { session_id, dir } => {
+                let path = self
+                    .processes
+                    .start_recording(session_id, Path::new(&dir))
+                    .map_err(|e| e.to_string())?;
+                Ok(json!(path))
+            }
+            Request::StopRecording { session_id } => {
+                let path = self
+                    .processes
+                    .stop_recording(session_id)
+                    .map_err(|e| e.to_string())?;
+                Ok(json!(path))
+            }
+            Request::RecordingPath { session_id } => {
+                Ok(json!(self.processes.recording_path(session_id)))
+            }
+            Request::SetScrollbackLimit { limit } => {
+                self.processes.set_scrollback_limit(limit);
+                Ok(Value::Null)
+            }
+            Request::Shutdown => {
+                let _ = self.shutdown.send(true);
+                Ok(Value::Null)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsent_part_trims_overlap() {
+        assert_eq!(unsent_part("hello".into(), 10, 0).as_deref(), Some("hello"));
+        assert_eq!(unsent_part("hello".into(), 10, 7).as_deref(), Some("llo"));
+        assert_eq!(unsent_part("hello".into(), 10, 10), None);
+        assert_eq!(unsent_part("hello".into(), 10, 12), None);
+    }
+}
@@ -0,0 +1,593 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+use super::error::GitError;
+use super::ops::FileChangeStatus;
+use super::runner::Git;
+
+/// Untracked files larger than this are listed without hunks.
+const MAX_UNTRACKED_DIFF_BYTES: u64 = 1024 * 1024;
+/// How much of a file is checked for NUL bytes to decide it is binary (the
+/// same amount git checks).
+const BINARY_SNIFF_BYTES: u64 = 8000;
+
+/// Whether a diff line was kept, added or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+/// A single line of a hunk. Line numbers are 1-based; `old_line` is `None`
+/// for added lines and `new_line` is `None` for removed ones.
+///
+/// `content` excludes the line's `\n` but keeps a `\r`. `no_newline` marks
+/// the last line of a file that doesn't end in a newline (git's
+/// `\ No newline at end of file`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    #[serde(default)]
+    pub no_newline: bool,
+}
+
+/// A contiguous block of changes, parsed from an `@@ -a,b +c,d @@` header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Text after the closing `@@`, usually the enclosing function.
+    pub header: String,
+    pub lines: Vec<DiffLine>,
+}
+
+/// All changes to one file.
+///
+/// `old_path` is set for renames and copies. Binary files and untracked
+/// files over `MAX_UNTRACKED_DIFF_BYTES` (`too_large`) have no hunks.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: FileChangeStatus,
+    /// Rename/copy similarity percentage as reported by git.
+    pub similarity: Option<u8>,
+    pub binary: bool,
+    pub too_large: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// One record of `git diff --raw -z` output.
+#[derive(Debug)]
+struct RawEntry {
+    status: FileChangeStatus,
+    similarity: Option<u8>,
+    old_path: Option<String>,
+    path: String,
+}
+
+/// Parses the NUL-separated `--raw -z` records: `:<modes> <hashes> <status>`
+/// followed by one path, or two for renames and copies.
+fn parse_raw(raw: &str) -> Result<Vec<RawEntry>, GitError> {
+    let mut fields = raw.split('\0').filter(|f| !f.is_empty());
+    let mut entries = Vec::new();
+    while let Some(meta) = fields.next() {
+        let code = meta
+            .split(' ')
+            .nth(4)
+            .filter(|code| meta.starts_with(':') && !code.is_empty())
+            .ok_or_else(|| GitError::ParseError {
+                message: format!("unexpected diff record: {meta}"),
+            })?;
+        let mut next_path = || {
+            fields
+                .next()
+                .map(str::to_string)
+                .ok_or_else(|| GitError::ParseError {
+                    message: format!("diff record without path: {meta}"),
+                })
+        };
+        let similarity = code[1..].parse().ok();
+        let entry = match code.chars().next() {
+            Some(c @ ('R' | 'C')) => {
+                let old_path = next_path()?;
+                RawEntry {
+                    status: if c == 'R' {
+                        FileChangeStatus::Renamed
+                    } else {
+                        FileChangeStatus::Copied
+                    },
+                    similarity,
+                    old_path: Some(old_path),
+                    path: next_path()?,
+                }
+            }
+            c => RawEntry {
+                status: match c {
+                    Some('A') => FileChangeStatus::Added,
+                    Some('M') | Some('T') => FileChangeStatus::Modified,
+                    Some('D') => FileChangeStatus::Deleted,
+                    _ => FileChangeStatus::Unknown,
+                },
+                similarity: None,
+                old_path: None,
+                path: next_path()?,
+            },
+        };
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Parses `-a,b` / `+c,d` ranges; a missing count means 1.
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    let (start, count) = match range[1..].split_once(',') {
+        Some((start, count)) => (start, count.parse().ok()?),
+        None => (&range[1..], 1),
+    };
+    Some((start.parse().ok()?, count))
+}
+
+/// Parses an `@@ -a,b +c,d @@ header` line into an empty hunk.
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ ")?;
+    let (ranges, header) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        header: header.trim_start().to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Fills in the hunks and binary flag of `file` from its section of the patch
+/// (everything from its `diff --git` line up to the next one).
+fn parse_patch(section: &[&str], file: &mut FileDiff) {
+    let mut old_line = 0;
+    let mut new_line = 0;
+    for line in section {
+        if let Some(hunk) = parse_hunk_header(line) {
+            old_line = hunk.old_start;
+            new_line = hunk.new_start;
+            file.hunks.push(hunk);
+            continue;
+        }
+        let Some(hunk) = file.hunks.last_mut() else {
+            if line.starts_with("Binary files ") || *line == "GIT binary patch" {
+                file.binary = true;
+            }
+            continue;
+        };
+        let (kind, content) = match line.split_at(line.len().min(1)) {
+            ("+", content) => (DiffLineKind::Added, content),
+            ("-", content) => (DiffLineKind::Removed, content),
+            (" ", content) => (DiffLineKind::Context, content),
+            // "\ No newline at end of file" applies to the line before it
+            ("\\", _) => {
+                if let Some(last) = hunk.lines.last_mut() {
+                    last.no_newline = true;
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let (old, new) = match kind {
+            DiffLineKind::Added => {
+                file.additions += 1;
+                new_line += 1;
+                (None, Some(new_line - 1))
+            }
+            DiffLineKind::Removed => {
+                file.deletions += 1;
+                old_line += 1;
+                (Some(old_line - 1), None)
+            }
+            DiffLineKind::Context => {
+                old_line += 1;
+                new_line += 1;
+                (Some(old_line - 1), Some(new_line - 1))
+            }
+        };
+        hunk.lines.push(DiffLine {
+            kind,
+            content: content.to_string(),
+            old_line: old,
+            new_line: new,
+            no_newline: false,
+        });
+    }
+}
+
+/// Parses `git diff --raw -p -z` output. The raw records come first and end
+/// with an empty field; the patch sections that follow are in the same order.
+fn parse_diff(output: &str) -> Result<Vec<FileDiff>, GitError> {
+    let (raw, patch) = output.split_once("\0\0").unwrap_or((output, ""));
+    let mut files: Vec<FileDiff> = parse_raw(raw)?
+        .into_iter()
+        .map(|entry| FileDiff {
+            path: entry.path,
+            old_path: entry.old_path,
+            status: entry.status,
+            similarity: entry.similarity,
+            binary: false,
+            too_large: false,
+            additions: 0,
+            deletions: 0,
+            hunks: Vec::new(),
+        })
+        .collect();
+
+    // Split on `\n` only: `str::lines` would also drop the `\r` of CRLF lines
+    let lines: Vec<&str> = patch.split('\n').collect();
+    let starts: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].starts_with("diff --git "))
+        .collect();
+    if starts.len() != files.len() {
+        return Err(GitError::ParseError {
+            message: format!(
+                "diff has {} files but {} patch sections",
+                files.len(),
+                starts.len()
+            ),
+        });
+    }
+    for (i, file) in files.iter_mut().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+        parse_patch(&lines[starts[i]..end], file);
+    }
+    Ok(files)
+}
+
+/// Whether `bytes` looks binary: a NUL in the first `BINARY_SNIFF_BYTES`,
+/// the same check git uses.
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .take(BINARY_SNIFF_BYTES as usize)
+        .any(|&b| b == 0)
+}
+
+/// Reads an untracked file for `untracked_file_diff`. Stops after the first
+/// `BINARY_SNIFF_BYTES` if the file is binary or too large to diff; the flag
+/// says whether it was too large.
+async fn read_untracked(path: &Path) -> io::Result<(Vec<u8>, bool)> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let too_large = file.metadata().await?.len() > MAX_UNTRACKED_DIFF_BYTES;
+    let mut bytes = Vec::new();
+    (&mut file)
+        .take(BINARY_SNIFF_BYTES)
+        .read_to_end(&mut bytes)
+        .await?;
+    if !too_large && !is_binary(&bytes) {
+        file.read_to_end(&mut bytes).await?;
+    }
+    Ok((bytes, too_large))
+}
+
+/// Builds the diff of a file git doesn't track yet: one hunk adding every
+/// line, or no hunks if it looks binary or is too large.
+fn untracked_file_diff(path: String, bytes: &[u8], too_large: bool) -> FileDiff {
+    let mut file = FileDiff {
+        path,
+        old_path: None,
+        status: FileChangeStatus::Added,
+        similarity: None,
+        binary: is_binary(bytes),
+        too_large,
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+    };
+    if file.binary || file.too_large || bytes.is_empty() {
+        return file;
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines: Vec<DiffLine> = text
+        .split_inclusive('\n')
+        .zip(1..)
+        .map(|(content, n)| DiffLine {
+            kind: DiffLineKind::Added,
+            content: content.strip_suffix('\n').unwrap_or(content).to_string(),
+            old_line: None,
+            new_line: Some(n),
+            no_newline: false,
+        })
+        .collect();
+    if !text.ends_with('\n') {
+        if let Some(last) = lines.last_mut() {
+            last.no_newline = true;
+        }
+    }
+    file.additions = lines.len();
+    file.hunks.push(DiffHunk {
+        old_start: 0,
+        old_lines: 0,
+        new_start: 1,
+        new_lines: lines.len() as u32,
+        header: String::new(),
+        lines,
+    });
+    file
+}
+
+impl Git {
+    /// Returns what changed in this worktree, file by file.
+    ///
+    /// With `against = None` this is the uncommitted state: staged and
+    /// unstaged changes relative to `HEAD` (the empty tree before the first
+    /// commit), plus untracked files (ignored ones excluded). With
+    /// `against = Some(base)` it is everything the checked out branch has
+    /// committed since it forked from `base` (`base...HEAD`), which is what
+    /// a session's branch would bring into `base`.
+    ///
+    /// Renames are detected. Binary files are flagged and carry no hunks.
+    pub async fn diff_worktree(&self, against: Option<&str>) -> Result<Vec<FileDiff>, GitError> {
+        let range = match against {
+            Some(base) => format!("{base}...HEAD"),
+            None => self.head_or_empty_tree().await?,
+        };
+        let output = self
+            .run(&[
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--find-renames",
+                "--raw",
+                "-p",
+                "-z",
+                &range,
+                "--",
+            ])
+            .await?;
+        let mut files = parse_diff(&output.stdout)?;
+
+        if against.is_none() {
+            files.extend(self.untracked_diffs().await?);
+        }
+        Ok(files)
+    }
+
+    /// Returns the unstaged changes: the worktree relative to the index,
+    /// plus untracked files. These are the hunks `stage_hunk` applies to the
+    /// index, so their context matches it even when part of a file is
+    /// already staged.
+    pub async fn diff_unstaged(&self) -> Result<Vec<FileDiff>, GitError> {
+        let output = self
+            .run(&[
+                "diff",
+                "--no-color",
+                "--no-ext-diff",
+                "--raw",
+                "-p",
+                "-z",
+                "--",
+            ])
+            .await?;
+        let mut files = parse_diff(&output.stdout)?;
+        files.extend(self.untracked_diffs().await?);
+        Ok(files)
+    }
+
+    /// Diffs of every untracked file, ignored ones excluded.
+    async fn untracked_diffs(&self) -> Result<Vec<FileDiff>, GitError> {
+        let root = PathBuf::from(self.run(&["rev-parse", "--show-toplevel"]).await?.trimmed());
+        let untracked = self
+            .run(&[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "--full-name",
+                "-z",
+            ])
+            .await?;
+        let mut files = Vec::new();
+        for path in untracked.stdout.split('\0').filter(|p| !p.is_empty()) {
+            // Skip files that vanish between listing and reading
+            if let Ok((bytes, too_large)) = read_untracked(&root.join(path)).await {
+                files.push(untracked_file_diff(path.to_string(), &bytes, too_large));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Returns `HEAD`, or the empty tree's ID in a repository without
+    /// commits, where everything staged then shows up as added.
+    async fn head_or_empty_tree(&self) -> Result<String, GitError> {
+        let (code, _) = self
+            .run_with_code(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .await?;
+        if code == 0 {
+            return Ok("HEAD".to_string());
+        }
+        let tree = self
+            .run_with_stdin(&["hash-object", "-t", "tree", "--stdin"], Some(b""))
+            .await?;
+        Ok(tree.trimmed().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn create_test_repo() -> (TempDir, Git) {
+        let dir = TempDir::new().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+
+        tokio::fs::write(dir.path().join("a.txt"), "a\nb\nc\nd\ne\nf\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("data.bin"), b"x\0y")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("old name.txt"), "keep\n")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        (dir, git)
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        let hunk = parse_hunk_header("@@ -1,5 +1,6 @@ fn main() {").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 5));
+        assert_eq!((hunk.new_start, hunk.new_lines), (1, 6));
+        assert_eq!(hunk.header, "fn main() {");
+
+        let hunk = parse_hunk_header("@@ -1 +0,0 @@").unwrap();
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 1));
+        assert_eq!((hunk.new_start, hunk.new_lines), (0, 0));
+        assert!(parse_hunk_header("not a hunk").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_diff_uncommitted_changes() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("a.txt"), "a\nB\nc\nd\ne\nf\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("data.bin"), b"z\0y")
+            .await
+            .unwrap();
+        git.run(&["mv", "old name.txt", "new name.txt"])
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("untracked.txt"), "one\ntwo\n")
+            .await
+            .unwrap();
+
+        let files = git.diff_worktree(None).await.unwrap();
+        let file = |path: &str| files.iter().find(|f| f.path == path).unwrap();
+
+        let a = file("a.txt");
+        assert!(matches!(a.status, FileChangeStatus::Modified));
+        assert_eq!((a.additions, a.deletions), (1, 1));
+        assert_eq!(a.hunks.len(), 1);
+        let removed = a.hunks[0]
+            .lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Removed)
+            .unwrap();
+        assert_eq!(removed.content, "b");
+        assert_eq!((removed.old_line, removed.new_line), (Some(2), None));
+
+        let bin = file("data.bin");
+        assert!(bin.binary);
+        assert!(bin.hunks.is_empty());
+
+        let renamed = file("new name.txt");
+        assert!(matches!(renamed.status, FileChangeStatus::Renamed));
+        assert_eq!(renamed.old_path.as_deref(), Some("old name.txt"));
+        assert_eq!(renamed.similarity, Some(100));
+
+        let untracked = file("untracked.txt");
+        assert!(matches!(untracked.status, FileChangeStatus::Added));
+        assert_eq!(untracked.additions, 2);
+        assert_eq!(untracked.hunks[0].lines[1].new_line, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_diff_against_base_branch() {
+        let (dir, git) = create_test_repo().await;
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::remove_file(dir.path().join("a.txt"))
+            .await
+            .unwrap();
+        git.run(&["commit", "-am", "remove a"]).await.unwrap();
+        // Uncommitted work is not part of the branch diff
+        tokio::fs::write(dir.path().join("scratch.txt"), "wip")
+            .await
+            .unwrap();
+
+        let files = git.diff_worktree(Some("main")).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "a.txt");
+        assert!(matches!(files[0].status, FileChangeStatus::Deleted));
+        assert_eq!(files[0].deletions, 6);
+        assert_eq!(files[0].hunks[0].new_lines, 0);
+
+        assert!(git.diff_worktree(Some("feature")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diff_line_endings_and_large_files() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("a.txt"), "a\nb\nc\nd\ne\nF")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("crlf.txt"), "one\r\ntwo")
+            .await
+            .unwrap();
+        let big = "x".repeat(MAX_UNTRACKED_DIFF_BYTES as usize + 1);
+        tokio::fs::write(dir.path().join("big.txt"), big)
+            .await
+            .unwrap();
+
+        let files = git.diff_worktree(None).await.unwrap();
+        let file = |path: &str| files.iter().find(|f| f.path == path).unwrap();
+
+        let lines = &file("a.txt").hunks[0].lines;
+        let added = lines
+            .iter()
+            .find(|l| l.kind == DiffLineKind::Added)
+            .unwrap();
+        assert_eq!(added.content, "F");
+        assert!(added.no_newline);
+        assert_eq!(lines.iter().filter(|l| l.no_newline).count(), 1);
+
+        let crlf = &file("crlf.txt").hunks[0].lines;
+        assert_eq!(crlf[0].content, "one\r");
+        assert!(!crlf[0].no_newline);
+        assert_eq!(crlf[1].content, "two");
+        assert!(crlf[1].no_newline);
+
+        let big = file("big.txt");
+        assert!(big.too_large);
+        assert!(!big.binary);
+        assert!(big.hunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_diff_without_commits() {
+        let dir = TempDir::new().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        tokio::fs::write(dir.path().join("staged.txt"), "s\n")
+            .await
+            .unwrap();
+        git.run(&["add", "staged.txt"]).await.unwrap();
+        tokio::fs::write(dir.path().join("loose.txt"), "l\n")
+            .await
+            .unwrap();
+
+        let files = git.diff_worktree(None).await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files
+            .iter()
+            .all(|f| matches!(f.status, FileChangeStatus::Added) && f.additions == 1));
+    }
+}
@@ -50,6 +50,14 @@ pub enum GitError {
     /// The specified worktree path does not exist in git's worktree list.
     #[error("worktree not found: {0}")]
     WorktreeNotFound(String),
+
+    /// An operation needs a branch checked out, but no worktree has it.
+    #[error("branch '{0}' is not checked out in any worktree")]
+    BranchNotCheckedOut(String),
+
+    /// An operation needs a clean worktree, but it has uncommitted changes.
+    #[error("worktree {0} has uncommitted changes")]
+    DirtyWorktree(String),
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
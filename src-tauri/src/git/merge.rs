@@ -0,0 +1,456 @@
+use serde::{Deserialize, Serialize};
+
+use super::error::GitError;
+use super::runner::Git;
+
+/// How a branch is brought into its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// `git merge`, creating a merge commit unless it fast-forwards.
+    Merge,
+    /// `git merge --squash` followed by a single commit on the target.
+    Squash,
+    /// `git rebase`, replaying the branch on top of the target, which is
+    /// then fast-forwarded to it.
+    Rebase,
+}
+
+/// A file the merge could not resolve on its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeConflict {
+    pub path: String,
+    /// Conflict type as git names it, e.g. `content`, `add/add` or
+    /// `modify/delete`.
+    pub kind: Option<String>,
+    /// Git's description of the conflict.
+    pub message: Option<String>,
+}
+
+/// Result of a dry-run merge of `source_branch` into `target_branch`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeCheck {
+    pub source_branch: String,
+    pub target_branch: String,
+    /// Commits on the source branch that the target doesn't have.
+    pub commits_ahead: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeCheck {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Result of merging, squashing or rebasing a branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeResult {
+    pub strategy: MergeStrategy,
+    /// Whether the branch was brought in. `false` when the dry run (or the
+    /// operation itself) hit conflicts, in which case nothing was changed.
+    pub merged: bool,
+    /// The resulting HEAD of the updated branch (the target, or the source
+    /// for a rebase).
+    pub commit: Option<String>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Parses `git merge-tree --write-tree --name-only -z` output: the tree ID,
+/// the conflicted paths and an empty entry, then for each message the number
+/// of paths it concerns, those paths, its type and its text.
+pub(crate) fn parse_merge_tree(output: &str) -> Vec<MergeConflict> {
+    let mut fields = output.split('\0');
+    let _tree = fields.next();
+    let mut conflicts: Vec<MergeConflict> = fields
+        .by_ref()
+        .take_while(|f| !f.is_empty())
+        .map(|path| MergeConflict {
+            path: path.to_string(),
+            kind: None,
+            message: None,
+        })
+        .collect();
+
+    while let Some(count) = fields.next().and_then(|f| f.parse::<usize>().ok()) {
+        let paths: Vec<&str> = fields.by_ref().take(count).collect();
+        let _type = fields.next();
+        let Some(message) = fields.next() else {
+            break;
+        };
+        // "CONFLICT (add/add): Merge conflict in h"
+        let Some(kind) = message
+            .strip_prefix("CONFLICT (")
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(kind, _)| kind)
+        else {
+            continue;
+        };
+        for path in paths {
+            if let Some(conflict) = conflicts
+                .iter_mut()
+                .find(|c| c.path == path && c.kind.is_none())
+            {
+                conflict.kind = Some(kind.to_string());
+                conflict.message = Some(message.trim().to_string());
+            }
+        }
+    }
+    conflicts
+}
+
+impl Git {
+    /// Checks whether `source` merges cleanly into `target` without touching
+    /// any worktree, using `git merge-tree --write-tree` (git 2.38+).
+    pub async fn merge_check(&self, source: &str, target: &str) -> Result<MergeCheck, GitError> {
+        let range = format!("{}..{}", target, source);
+        let ahead = self.run(&["rev-list", "--count", &range]).await?;
+        let commits_ahead = ahead.trimmed().parse().map_err(|_| GitError::ParseError {
+            message: format!("unexpected rev-list count: {}", ahead.trimmed()),
+        })?;
+
+        let args = [
+            "merge-tree",
+            "--write-tree",
+            "--name-only",
+            "-z",
+            target,
+            source,
+        ];
+        let (code, output) = self.run_with_code(&args).await?;
+        let conflicts = match code {
+            0 => Vec::new(),
+            1 => parse_merge_tree(&output.stdout),
+            code => {
+                return Err(GitError::CommandFailed {
+                    code,
+                    stderr: output.stderr.trim().to_string(),
+                    command: format!("git {}", args.join(" ")),
+                })
+            }
+        };
+
+        Ok(MergeCheck {
+            source_branch: source.to_string(),
+            target_branch: target.to_string(),
+            commits_ahead,
+            conflicts,
+        })
+    }
+
+    /// Merges, squash-merges or rebases `source` onto `target`.
+    ///
+    /// Runs `merge_check` first and returns its conflicts without changing
+    /// anything if there are any. Merges happen in the worktree that has
+    /// `target` checked out, rebases in the one that has `source`, after
+    /// which `target` is fast-forwarded (in its worktree, if it has one).
+    /// Those worktrees must have no uncommitted changes to tracked files.
+    /// Should the operation itself stop on conflicts, it is aborted and
+    /// they are returned.
+    pub async fn merge_branch(
+        &self,
+        source: &str,
+        target: &str,
+        strategy: MergeStrategy,
+        message: Option<&str>,
+    ) -> Result<MergeResult, GitError> {
+        let check = self.merge_check(source, target).await?;
+        if !check.is_clean() {
+            return Ok(MergeResult {
+                strategy,
+                merged: false,
+                commit: None,
+                conflicts: check.conflicts,
+            });
+        }
+
+        let checked_out = match strategy {
+            MergeStrategy::Rebase => source,
+            MergeStrategy::Merge | MergeStrategy::Squash => target,
+        };
+        let worktrees = self.worktree_list().await?;
+        let worktree_of = |branch: &str| {
+            worktrees
+                .iter()
+                .find(|wt| wt.branch.as_deref() == Some(branch))
+        };
+        let worktree = worktree_of(checked_out)
+            .ok_or_else(|| GitError::BranchNotCheckedOut(checked_out.to_string()))?;
+        let git = Git::new(&worktree.path);
+        git.ensure_no_tracked_changes(&worktree.path).await?;
+
+        // A rebase ends by fast-forwarding the target, which must exist
+        // locally and, if it is checked out, be clean as well
+        let target_worktree = match strategy {
+            MergeStrategy::Rebase => {
+                let target_ref = format!("refs/heads/{}", target);
+                let oid = self.run(&["rev-parse", "--verify", &target_ref]).await?;
+                let wt = worktree_of(target);
+                if let Some(wt) = wt {
+                    Git::new(&wt.path)
+                        .ensure_no_tracked_changes(&wt.path)
+                        .await?;
+                }
+                Some((target_ref, oid.trimmed().to_string(), wt))
+            }
+            MergeStrategy::Merge | MergeStrategy::Squash => None,
+        };
+
+        let (args, abort): (Vec<&str>, [&str; 2]) = match strategy {
+            MergeStrategy::Merge => (vec!["merge", "--no-edit", source], ["merge", "--abort"]),
+            MergeStrategy::Squash => (vec!["merge", "--squash", source], ["reset", "--merge"]),
+            MergeStrategy::Rebase => (vec!["rebase", target], ["rebase", "--abort"]),
+        };
+        if let Err(e) = git.run(&args).await {
+            let conflicts = git.unmerged_paths().await.unwrap_or_default();
+            let _ = git.run(&abort).await;
+            if conflicts.is_empty() {
+                return Err(e);
+            }
+            return Ok(MergeResult {
+                strategy,
+                merged: false,
+                commit: None,
+                conflicts,
+            });
+        }
+
+        if strategy == MergeStrategy::Squash {
+            // Nothing staged means the target already has all the changes
+            let (staged, _) = git.run_with_code(&["diff", "--cached", "--quiet"]).await?;
+            if staged != 0 {
+                let default_message = format!("Squashed commit of branch '{}'", source);
+                git.run(&["commit", "-m", message.unwrap_or(&default_message)])
+                    .await?;
+            }
+        }
+
+        let head = git.run(&["rev-parse", "HEAD"]).await?;
+        if let Some((target_ref, old_oid, wt)) = target_worktree {
+            match wt {
+                Some(wt) => {
+                    Git::new(&wt.path)
+                        .run(&["merge", "--ff-only", source])
+                        .await?;
+                }
+                None => {
+                    self.run(&["update-ref", &target_ref, head.trimmed(), &old_oid])
+                        .await?;
+                }
+            }
+        }
+
+        Ok(MergeResult {
+            strategy,
+            merged: true,
+            commit: Some(head.trimmed().to_string()),
+            conflicts: Vec::new(),
+        })
+    }
+
+    /// Fails with `DirtyWorktree` if tracked files in this worktree have
+    /// uncommitted changes. Untracked files are left to git, which refuses
+    /// to overwrite them.
+    async fn ensure_no_tracked_changes(&self, path: &str) -> Result<(), GitError> {
+        let status = self
+            .run(&["status", "--porcelain", "--untracked-files=no"])
+            .await?;
+        if status.lines().is_empty() {
+            Ok(())
+        } else {
+            Err(GitError::DirtyWorktree(path.to_string()))
+        }
+    }
+
+    /// Paths left unmerged by a merge or rebase that stopped on conflicts.
+    async fn unmerged_paths(&self) -> Result<Vec<MergeConflict>, GitError> {
+        let output = self
+            .run(&["diff", "--name-only", "--diff-filter=U"])
+            .await?;
+        Ok(output
+            .lines()
+            .into_iter()
+            .map(|path| MergeConflict {
+                path: path.to_string(),
+                kind: None,
+                message: None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Helper: a repo on `main` with a `feature` branch one commit ahead.
+    /// `conflicting` also changes the same line on `main`.
+    async fn create_test_repo(conflicting: bool) -> (tempfile::TempDir, Git) {
+        let dir = tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-b", "main"]).await.unwrap();
+        git.run(&["config", "user.email", "test@test.com"])
+            .await
+            .unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+
+        let file = dir.path().join("file.txt");
+        tokio::fs::write(&file, "one\ntwo\n").await.unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "initial"]).await.unwrap();
+
+        git.run(&["checkout", "-b", "feature"]).await.unwrap();
+        tokio::fs::write(&file, "one\nfeature\n").await.unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "new")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "feature work"]).await.unwrap();
+        git.run(&["checkout", "main"]).await.unwrap();
+
+        if conflicting {
+            tokio::fs::write(&file, "one\nmain\n").await.unwrap();
+            git.run(&["commit", "-am", "main work"]).await.unwrap();
+        }
+        (dir, git)
+    }
+
+    #[test]
+    fn test_parse_merge_tree() {
+        let output = "929ff7\0f\0g\0h\0\0\
+            1\0f\0Auto-merging\0Auto-merging f\n\0\
+            1\0f\0CONFLICT (contents)\0CONFLICT (content): Merge conflict in f\n\0\
+            1\0g\0CONFLICT (modify/delete)\0CONFLICT (modify/delete): g deleted in feat and modified in main.\n\0\
+            1\0h\0CONFLICT (contents)\0CONFLICT (add/add): Merge conflict in h\n\0";
+        let conflicts = parse_merge_tree(output);
+        let kinds: Vec<(&str, Option<&str>)> = conflicts
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind.as_deref()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("f", Some("content")),
+                ("g", Some("modify/delete")),
+                ("h", Some("add/add"))
+            ]
+        );
+        assert_eq!(
+            conflicts[0].message.as_deref(),
+            Some("CONFLICT (content): Merge conflict in f")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_check_reports_conflicts() {
+        let (_dir, git) = create_test_repo(true).await;
+        let check = git.merge_check("feature", "main").await.unwrap();
+        assert_eq!(check.commits_ahead, 1);
+        assert_eq!(check.conflicts.len(), 1);
+        assert_eq!(check.conflicts[0].path, "file.txt");
+        assert_eq!(check.conflicts[0].kind.as_deref(), Some("content"));
+
+        // Nothing is changed when the dry run finds conflicts
+        let result = git
+            .merge_branch("feature", "main", MergeStrategy::Merge, None)
+            .await
+            .unwrap();
+        assert!(!result.merged);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(git.uncommitted_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_squash_merge() {
+        let (dir, git) = create_test_repo(false).await;
+        assert!(git.merge_check("feature", "main").await.unwrap().is_clean());
+
+        let result = git
+            .merge_branch(
+                "feature",
+                "main",
+                MergeStrategy::Squash,
+                Some("Add feature"),
+            )
+            .await
+            .unwrap();
+        assert!(result.merged);
+        let log = git.run(&["log", "--format=%s %P", "-1"]).await.unwrap();
+        // A single-parent commit with the given message
+        assert_eq!(log.trimmed().split(' ').count(), 3);
+        assert!(log.trimmed().starts_with("Add feature"));
+        assert!(dir.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rebase_in_source_worktree() {
+        let (dir, git) = create_test_repo(false).await;
+        tokio::fs::write(dir.path().join("other.txt"), "main")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "main work"]).await.unwrap();
+        let main_head = git.run(&["rev-parse", "main"]).await.unwrap();
+
+        // The source branch must be checked out somewhere
+        assert!(matches!(
+            git.merge_branch("feature", "main", MergeStrategy::Rebase, None)
+                .await,
+            Err(GitError::BranchNotCheckedOut(_))
+        ));
+
+        let wt_path = dir.path().join("wt-feature");
+        git.worktree_add(&wt_path, None, Some("feature"))
+            .await
+            .unwrap();
+        // Untracked files don't count as uncommitted changes
+        tokio::fs::write(wt_path.join("scratch.txt"), "notes")
+            .await
+            .unwrap();
+        let result = git
+            .merge_branch("feature", "main", MergeStrategy::Rebase, None)
+            .await
+            .unwrap();
+        assert!(result.merged);
+        let parent = git.run(&["rev-parse", "feature~1"]).await.unwrap();
+        assert_eq!(parent.trimmed(), main_head.trimmed());
+
+        // The checked-out target was fast-forwarded to the rebased branch
+        let main = git.run(&["rev-parse", "main"]).await.unwrap();
+        assert_eq!(Some(main.trimmed()), result.commit.as_deref());
+        assert!(dir.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_rebase_fast_forwards_target_without_worktree() {
+        let (dir, git) = create_test_repo(false).await;
+        tokio::fs::write(dir.path().join("other.txt"), "main")
+            .await
+            .unwrap();
+        git.run(&["add", "."]).await.unwrap();
+        git.run(&["commit", "-m", "main work"]).await.unwrap();
+        git.run(&["checkout", "feature"]).await.unwrap();
+
+        // Tracked changes still block it
+        tokio::fs::write(dir.path().join("file.txt"), "edited")
+            .await
+            .unwrap();
+        assert!(matches!(
+            git.merge_branch("feature", "main", MergeStrategy::Rebase, None)
+                .await,
+            Err(GitError::DirtyWorktree(_))
+        ));
+        git.run(&["checkout", "--", "file.txt"]).await.unwrap();
+
+        let result = git
+            .merge_branch("feature", "main", MergeStrategy::Rebase, None)
+            .await
+            .unwrap();
+        assert!(result.merged);
+        let main = git.run(&["rev-parse", "main"]).await.unwrap();
+        assert_eq!(Some(main.trimmed()), result.commit.as_deref());
+        let feature = git.run(&["rev-parse", "feature"]).await.unwrap();
+        assert_eq!(main.trimmed(), feature.trimmed());
+    }
+}
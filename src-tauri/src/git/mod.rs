@@ -1,7 +1,14 @@
+pub mod diff;
 pub mod error;
+pub mod merge;
 pub mod ops;
 pub mod runner;
 
+pub use diff::{DiffHunk, DiffLine, DiffLineKind, FileDiff};
 pub use error::GitError;
-pub use ops::{BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, WorktreeInfo};
+pub use merge::{MergeCheck, MergeConflict, MergeResult, MergeStrategy};
+pub use ops::{
+    BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, StatusCode,
+    StatusEntry, WorktreeInfo,
+};
 pub use runner::Git;
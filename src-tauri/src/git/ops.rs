@@ -1,6 +1,7 @@
 use serde::Serialize;
 use std::path::Path;
 
+use super::diff::{DiffHunk, DiffLineKind};
 use super::error::GitError;
 use super::runner::Git;
 
@@ -82,6 +83,148 @@ pub struct RemoteInfo {
     pub url: String,
 }
 
+/// One side (index or worktree) of a status entry's `XY` code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusCode {
+    Unmodified,
+    Modified,
+    TypeChanged,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    Unmerged,
+    Untracked,
+    Ignored,
+}
+
+impl StatusCode {
+    fn from_char(c: char) -> Self {
+        match c {
+            'M' => Self::Modified,
+            'T' => Self::TypeChanged,
+            'A' => Self::Added,
+            'D' => Self::Deleted,
+            'R' => Self::Renamed,
+            'C' => Self::Copied,
+            'U' => Self::Unmerged,
+            '?' => Self::Untracked,
+            '!' => Self::Ignored,
+            _ => Self::Unmodified,
+        }
+    }
+}
+
+/// A file with changes, parsed from `git status --porcelain=v2`.
+///
+/// `index` is the staged state and `worktree` the unstaged one. Untracked
+/// files have both set to `Untracked`. `orig_path` is set for renames and
+/// copies in the index.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusEntry {
+    pub path: String,
+    pub orig_path: Option<String>,
+    pub index: StatusCode,
+    pub worktree: StatusCode,
+    /// Whether the file has merge conflicts.
+    pub conflicted: bool,
+}
+
+/// Parses `git status --porcelain=v2 -z` records. Changed entries (`1`),
+/// renames/copies (`2`, followed by the original path in its own field),
+/// unmerged (`u`), untracked (`?`) and ignored (`!`) entries are supported;
+/// `#` header lines are skipped.
+fn parse_status_v2(output: &str) -> Result<Vec<StatusEntry>, GitError> {
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    let mut entries = Vec::new();
+    while let Some(record) = fields.next() {
+        // Number of space-separated fields before the path
+        let skip = match record.chars().next() {
+            Some('1') => 8,
+            Some('2') => 9,
+            Some('u') => 10,
+            Some('?') | Some('!') => 1,
+            Some('#') => continue,
+            _ => {
+                return Err(GitError::ParseError {
+                    message: format!("unexpected status record: {record}"),
+                })
+            }
+        };
+        let parts: Vec<&str> = record.splitn(skip + 1, ' ').collect();
+        let (Some(code), Some(path)) = (parts.get(1), parts.get(skip)) else {
+            return Err(GitError::ParseError {
+                message: format!("truncated status record: {record}"),
+            });
+        };
+        let (index, worktree) = if skip == 1 {
+            let code = StatusCode::from_char(record.chars().next().unwrap_or('?'));
+            (code, code)
+        } else {
+            let mut xy = code.chars().map(StatusCode::from_char);
+            (
+                xy.next().unwrap_or(StatusCode::Unmodified),
+                xy.next().unwrap_or(StatusCode::Unmodified),
+            )
+        };
+        let orig_path = if skip == 9 {
+            fields.next().map(str::to_string)
+        } else {
+            None
+        };
+        entries.push(StatusEntry {
+            path: path.to_string(),
+            orig_path,
+            index,
+            worktree,
+            conflicted: skip == 10,
+        });
+    }
+    Ok(entries)
+}
+
+/// Builds a patch containing a single hunk of `path`, for `git apply`.
+fn hunk_patch(path: &str, hunk: &DiffHunk) -> String {
+    let mut patch = format!("diff --git a/{path} b/{path}\n--- a/{path}\n");
+    patch.push_str(&format!(
+        "+++ b/{path}\n@@ -{},{} +{},{} @@\n",
+        hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+    ));
+    for line in &hunk.lines {
+        let prefix = match line.kind {
+            DiffLineKind::Context => ' ',
+            DiffLineKind::Added => '+',
+            DiffLineKind::Removed => '-',
+        };
+        patch.push(prefix);
+        patch.push_str(&line.content);
+        patch.push('\n');
+        if line.no_newline {
+            patch.push_str("\\ No newline at end of file\n");
+        }
+    }
+    patch
+}
+
+/// Resolves `path`, relative to the directory `git` runs in, to a path
+/// relative to the repository root like the ones `git status` prints.
+/// `prefix` is that directory's own root-relative path
+/// (`git rev-parse --show-prefix`).
+fn repo_relative(prefix: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = prefix.split('/').filter(|p| !p.is_empty()).collect();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
 impl Git {
     /// Lists all local and remote branches, excluding `HEAD` pointer entries.
     ///
@@ -619,6 +762,125 @@ impl Git {
         self.run(&["checkout", "--detach"]).await?;
         Ok(())
     }
+
+    /// Lists changed, untracked and conflicted files from
+    /// `git status --porcelain=v2 -z`. Untracked directories are expanded
+    /// into their files so each can be staged on its own.
+    pub async fn status_porcelain_v2(&self) -> Result<Vec<StatusEntry>, GitError> {
+        let output = self
+            .run(&["status", "--porcelain=v2", "-z", "--untracked-files=all"])
+            .await?;
+        parse_status_v2(&output.stdout)
+    }
+
+    /// Stages the given paths, including deletions (`git add -A`).
+    pub async fn stage_paths(&self, paths: &[&str]) -> Result<(), GitError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["add", "-A", "--"];
+        args.extend_from_slice(paths);
+        self.run(&args).await?;
+        Ok(())
+    }
+
+    /// Unstages the given paths, leaving the worktree untouched.
+    pub async fn unstage_paths(&self, paths: &[&str]) -> Result<(), GitError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args = vec!["restore", "--staged", "--"];
+        args.extend_from_slice(paths);
+        self.run(&args).await?;
+        Ok(())
+    }
+
+    /// Stages a single hunk of `path`, as returned by `diff_unstaged`.
+    ///
+    /// The hunk is applied to the index with `git apply --cached --recount`,
+    /// so its line counts need not be exact and small offsets from earlier
+    /// staging are tolerated. Fails with `CommandFailed` if the hunk's
+    /// context no longer matches the index.
+    ///
+    /// An untracked file's only hunk is the whole file, so it is staged
+    /// with `git add` instead, which keeps its exact bytes.
+    pub async fn stage_hunk(&self, path: &str, hunk: &DiffHunk) -> Result<(), GitError> {
+        let (code, _) = self
+            .run_with_code(&["cat-file", "-e", &format!(":{path}")])
+            .await?;
+        if code != 0 {
+            return self.stage_paths(&[path]).await;
+        }
+        let patch = hunk_patch(path, hunk);
+        self.run_with_stdin(
+            &["apply", "--cached", "--recount", "--whitespace=nowarn", "-"],
+            Some(patch.as_bytes()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Throws away unstaged changes to the given paths. Tracked files are
+    /// restored from the index (staged changes are kept) and untracked files
+    /// are deleted.
+    pub async fn discard_paths(&self, paths: &[&str]) -> Result<(), GitError> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let untracked: Vec<String> = self
+            .status_porcelain_v2()
+            .await?
+            .into_iter()
+            .filter(|e| e.worktree == StatusCode::Untracked)
+            .map(|e| e.path)
+            .collect();
+        // Status paths are relative to the repository root, `paths` to the
+        // directory git runs in
+        let prefix = self.run(&["rev-parse", "--show-prefix"]).await?;
+        let (new, tracked): (Vec<&str>, Vec<&str>) = paths.iter().partition(|p| {
+            let path = repo_relative(prefix.trimmed(), p);
+            untracked.contains(&path)
+        });
+
+        if !tracked.is_empty() {
+            let mut args = vec!["restore", "--worktree", "--"];
+            args.extend_from_slice(&tracked);
+            self.run(&args).await?;
+        }
+        if !new.is_empty() {
+            let mut args = vec!["clean", "-f", "--"];
+            args.extend_from_slice(&new);
+            self.run(&args).await?;
+        }
+        Ok(())
+    }
+
+    /// Commits the staged changes and returns the new commit's hash.
+    ///
+    /// With `amend`, replaces `HEAD` instead; an empty message then keeps
+    /// the existing one. `signoff` adds a `Signed-off-by` trailer.
+    pub async fn commit(
+        &self,
+        message: &str,
+        amend: bool,
+        signoff: bool,
+    ) -> Result<String, GitError> {
+        let mut args = vec!["commit"];
+        if amend {
+            args.push("--amend");
+        }
+        if signoff {
+            args.push("--signoff");
+        }
+        if amend && message.trim().is_empty() {
+            args.push("--no-edit");
+        } else {
+            args.extend_from_slice(&["-m", message]);
+        }
+        self.run(&args).await?;
+        let head = self.run(&["rev-parse", "HEAD"]).await?;
+        Ok(head.trimmed().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -809,4 +1071,167 @@ mod tests {
             current
         );
     }
+
+    #[test]
+    fn test_parse_status_v2() {
+        let output = "# branch.oid abc\0\
+            1 .M N... 100644 100644 100644 aaa aaa src/main.rs\0\
+            2 R. N... 100644 100644 100644 aaa aaa R100 new name.rs\0old name.rs\0\
+            u UU N... 100644 100644 100644 100644 aaa bbb ccc conflict.rs\0\
+            ? notes/todo file.txt\0";
+        let entries = parse_status_v2(output).unwrap();
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].path, "src/main.rs");
+        assert_eq!(entries[0].index, StatusCode::Unmodified);
+        assert_eq!(entries[0].worktree, StatusCode::Modified);
+
+        assert_eq!(entries[1].path, "new name.rs");
+        assert_eq!(entries[1].orig_path.as_deref(), Some("old name.rs"));
+        assert_eq!(entries[1].index, StatusCode::Renamed);
+
+        assert!(entries[2].conflicted);
+        assert_eq!(entries[2].index, StatusCode::Unmerged);
+
+        assert_eq!(entries[3].path, "notes/todo file.txt");
+        assert_eq!(entries[3].worktree, StatusCode::Untracked);
+    }
+
+    #[tokio::test]
+    async fn test_stage_unstage_and_commit() {
+        let (dir, git) = create_test_repo().await;
+        let initial = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Changed")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("new.txt"), "new").await.unwrap();
+
+        git.stage_paths(&["README.md", "new.txt"]).await.unwrap();
+        let status = git.status_porcelain_v2().await.unwrap();
+        assert!(status.iter().all(|e| e.worktree == StatusCode::Unmodified));
+        assert!(status
+            .iter()
+            .any(|e| e.path == "new.txt" && e.index == StatusCode::Added));
+
+        git.unstage_paths(&["new.txt"]).await.unwrap();
+        let status = git.status_porcelain_v2().await.unwrap();
+        let new = status.iter().find(|e| e.path == "new.txt").unwrap();
+        assert_eq!(new.worktree, StatusCode::Untracked);
+
+        let hash = git.commit("Update readme", false, true).await.unwrap();
+        let log = git.commit_log(1, false).await.unwrap();
+        assert_eq!(log[0].hash, hash);
+        let body = git.run(&["log", "-1", "--format=%B"]).await.unwrap();
+        assert!(body.stdout.contains("Signed-off-by: Test <test@test.com>"));
+
+        // Amending with no message keeps the existing one
+        git.stage_paths(&["new.txt"]).await.unwrap();
+        let amended = git.commit("", true, false).await.unwrap();
+        assert_ne!(amended, hash);
+        let parent = git.run(&["rev-parse", "HEAD~1"]).await.unwrap();
+        assert_eq!(parent.trimmed(), initial.trimmed());
+        let log = git.commit_log(1, false).await.unwrap();
+        assert_eq!(log[0].summary, "Update readme");
+    }
+
+    #[tokio::test]
+    async fn test_stage_hunk() {
+        let (dir, git) = create_test_repo().await;
+        let original: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+        tokio::fs::write(dir.path().join("lines.txt"), &original)
+            .await
+            .unwrap();
+        git.stage_paths(&["lines.txt"]).await.unwrap();
+        git.commit("add lines", false, false).await.unwrap();
+
+        let changed = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 19\n", "line nineteen\n");
+        tokio::fs::write(dir.path().join("lines.txt"), &changed)
+            .await
+            .unwrap();
+        let diff = git.diff_unstaged().await.unwrap();
+        assert_eq!(diff[0].hunks.len(), 2);
+
+        // Staging only the second hunk leaves the first unstaged
+        git.stage_hunk("lines.txt", &diff[0].hunks[1]).await.unwrap();
+        let staged = git.run(&["diff", "--cached"]).await.unwrap();
+        assert!(staged.stdout.contains("+line nineteen"));
+        assert!(!staged.stdout.contains("+line two"));
+        let unstaged = git.run(&["diff"]).await.unwrap();
+        assert!(unstaged.stdout.contains("+line two"));
+
+        // Remaining hunks are relative to the index, so they still apply
+        // once part of the file is staged
+        let diff = git.diff_unstaged().await.unwrap();
+        assert_eq!(diff[0].hunks.len(), 1);
+        git.stage_hunk("lines.txt", &diff[0].hunks[0]).await.unwrap();
+        let unstaged = git.run(&["diff"]).await.unwrap();
+        assert!(unstaged.stdout.is_empty());
+
+        // Untracked files are staged byte for byte
+        tokio::fs::write(dir.path().join("fresh.txt"), "a\r\nb\r\n")
+            .await
+            .unwrap();
+        let diff = git.diff_unstaged().await.unwrap();
+        let fresh = diff.iter().find(|f| f.path == "fresh.txt").unwrap();
+        git.stage_hunk("fresh.txt", &fresh.hunks[0]).await.unwrap();
+        let staged = git.run(&["cat-file", "blob", ":fresh.txt"]).await.unwrap();
+        assert_eq!(staged.stdout, "a\r\nb\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_stage_hunk_without_trailing_newline() {
+        let (dir, git) = create_test_repo().await;
+        let original: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+        tokio::fs::write(dir.path().join("lines.txt"), &original)
+            .await
+            .unwrap();
+        git.stage_paths(&["lines.txt"]).await.unwrap();
+        git.commit("add lines", false, false).await.unwrap();
+
+        let changed = original.replace("line 10\n", "line ten");
+        tokio::fs::write(dir.path().join("lines.txt"), &changed)
+            .await
+            .unwrap();
+        let diff = git.diff_unstaged().await.unwrap();
+        git.stage_hunk("lines.txt", &diff[0].hunks[0]).await.unwrap();
+        let staged = git.run(&["cat-file", "blob", ":lines.txt"]).await.unwrap();
+        assert_eq!(staged.stdout, changed);
+    }
+
+    #[tokio::test]
+    async fn test_discard_paths() {
+        let (dir, git) = create_test_repo().await;
+        tokio::fs::write(dir.path().join("README.md"), "# Changed")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("scratch.txt"), "tmp")
+            .await
+            .unwrap();
+
+        git.discard_paths(&["README.md", "scratch.txt"])
+            .await
+            .unwrap();
+        let readme = tokio::fs::read_to_string(dir.path().join("README.md"))
+            .await
+            .unwrap();
+        assert_eq!(readme, "# Test");
+        assert!(!dir.path().join("scratch.txt").exists());
+        assert!(git.status_porcelain_v2().await.unwrap().is_empty());
+
+        // Paths are relative to the directory git runs in
+        let sub = dir.path().join("sub");
+        tokio::fs::create_dir(&sub).await.unwrap();
+        tokio::fs::write(sub.join("scratch.txt"), "tmp").await.unwrap();
+        tokio::fs::write(dir.path().join("README.md"), "# Changed")
+            .await
+            .unwrap();
+        Git::new(&sub)
+            .discard_paths(&["scratch.txt", "../README.md"])
+            .await
+            .unwrap();
+        assert!(!sub.join("scratch.txt").exists());
+        assert!(git.status_porcelain_v2().await.unwrap().is_empty());
+    }
 }
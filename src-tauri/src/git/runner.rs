@@ -1,4 +1,6 @@
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
@@ -52,6 +54,41 @@ impl Git {
     /// other I/O failures, and `CommandFailed` for non-zero exit codes.
     /// Both stdout and stderr are decoded as UTF-8 (returns `InvalidUtf8` on failure).
     pub async fn run(&self, args: &[&str]) -> Result<GitOutput, GitError> {
+        self.run_with_stdin(args, None).await
+    }
+
+    /// Like `run`, but writes `input` to the command's stdin first (e.g. a
+    /// patch for `git apply -`).
+    pub async fn run_with_stdin(
+        &self,
+        args: &[&str],
+        input: Option<&[u8]>,
+    ) -> Result<GitOutput, GitError> {
+        let (code, output, command_str) = self.exec(args, input).await?;
+        if code == 0 {
+            Ok(output)
+        } else {
+            Err(GitError::CommandFailed {
+                code,
+                stderr: output.stderr.trim().to_string(),
+                command: command_str,
+            })
+        }
+    }
+
+    /// Like `run`, but returns the exit code alongside the output instead of
+    /// failing on a non-zero exit, for commands that report results through
+    /// it (e.g. `merge-tree` exits 1 on conflicts).
+    pub async fn run_with_code(&self, args: &[&str]) -> Result<(i32, GitOutput), GitError> {
+        let (code, output, _) = self.exec(args, None).await?;
+        Ok((code, output))
+    }
+
+    async fn exec(
+        &self,
+        args: &[&str],
+        input: Option<&[u8]>,
+    ) -> Result<(i32, GitOutput, String), GitError> {
         let mut cmd = Command::new("git");
         cmd.arg("-C")
             .arg(&self.repo_path)
@@ -63,8 +100,24 @@ impl Git {
 
         let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
 
-        let output = timeout(Duration::from_secs(30), cmd.output())
-            .await
+        let output = timeout(Duration::from_secs(30), async {
+            let Some(input) = input else {
+                return cmd.output().await;
+            };
+            let mut child = cmd
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                // A write error means git exited early; its exit code and
+                // stderr describe why, so they are what gets reported.
+                let _ = stdin.write_all(input).await;
+                // Dropping stdin closes it so git sees EOF
+            }
+            child.wait_with_output().await
+        })
+        .await
             .map_err(|_| GitError::CommandFailed {
                 code: -1,
                 stderr: format!("Command timed out after 30s: {}", command_str),
@@ -83,16 +136,9 @@ impl Git {
 
         let stdout = String::from_utf8(output.stdout)?;
         let stderr = String::from_utf8(output.stderr)?;
+        let code = output.status.code().unwrap_or(-1);
 
-        if output.status.success() {
-            Ok(GitOutput { stdout, stderr })
-        } else {
-            Err(GitError::CommandFailed {
-                code: output.status.code().unwrap_or(-1),
-                stderr: stderr.trim().to_string(),
-                command: command_str,
-            })
-        }
+        Ok((code, GitOutput { stdout, stderr }, command_str))
     }
 
     /// Convenience wrapper that runs a git command in a different directory
@@ -1,22 +1,38 @@
 mod commands;
 mod core;
+mod daemon;
 mod git;
 
 use std::sync::Arc;
 
 use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 use core::marketplace_manager::MarketplaceManager;
+use core::mcp_http_server::McpHttpServer;
 use core::mcp_manager::McpManager;
+use core::notifications::Notifier;
 use core::plugin_manager::PluginManager;
-use core::status_server::StatusServer;
+use core::status_server::{
+    StatusServer, StatusServerConfig, BIND_ENV, PORTS_ENV, SOCKET_ENV,
+};
 use core::ProcessManager;
+use core::session_journal::SessionJournal;
 use core::session_manager::SessionManager;
+use core::recording::ReplayManager;
+use core::scrollback::{
+    MAX_SCROLLBACK_LIMIT, MIN_SCROLLBACK_LIMIT, SCROLLBACK_LIMIT_KEY, SCROLLBACK_STORE,
+};
+use core::status_detector::StatusInference;
+use core::task_board::TaskBoards;
+use core::webhooks::Webhooks;
 use core::worktree_manager::WorktreeManager;
+use daemon::DaemonClient;
 
 /// Entry point for the Tauri application.
 ///
-/// Registers plugins (store, dialog), injects shared state (ProcessManager,
+/// Registers plugins (store, dialog, notification), injects shared state (ProcessManager,
 /// SessionManager, WorktreeManager), verifies git availability at startup
 /// (non-fatal -- logs an error but does not abort), and mounts all IPC
 /// command handlers for the terminal, git, and session subsystems.
@@ -31,7 +47,8 @@ pub fn run() {
 
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
-        .plugin(tauri_plugin_dialog::init());
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init());
 
     // Register macOS permissions plugin (for Full Disk Access check)
     #[cfg(target_os = "macos")]
@@ -45,6 +62,9 @@ pub fn run() {
         .manage(PluginManager::new())
         .manage(ProcessManager::new())
         .manage(SessionManager::new())
+        .manage(StatusInference::new())
+        .manage(TaskBoards::new())
+        .manage(ReplayManager::new())
         .manage(WorktreeManager::new())
         .setup(|app| {
             // Generate a unique instance ID for this Maestro run
@@ -52,27 +72,91 @@ pub fn run() {
             let instance_id = uuid::Uuid::new_v4().to_string();
             log::info!("Maestro instance ID: {}", instance_id);
 
+            // Attach the session journal so sessions survive restarts.
+            // Lives next to the per-project store files in the app data dir.
+            match app.path().app_data_dir() {
+                Ok(data_dir) => {
+                    app.state::<SessionManager>()
+                        .attach_journal(SessionJournal::open(data_dir));
+                }
+                Err(e) => {
+                    log::warn!("No app data dir, session journal disabled: {}", e);
+                }
+            }
+
+            // Apply the user's scrollback limit before any session spawns,
+            // in-process and (on connect) in the session daemon
+            let saved_limit = app
+                .store(SCROLLBACK_STORE)
+                .ok()
+                .and_then(|store| store.get(SCROLLBACK_LIMIT_KEY))
+                .and_then(|v| v.as_u64())
+                .map(|v| v as usize)
+                .filter(|v| (MIN_SCROLLBACK_LIMIT..=MAX_SCROLLBACK_LIMIT).contains(v));
+            if let Some(limit) = saved_limit {
+                app.state::<ProcessManager>().set_scrollback_limit(limit);
+            }
+
+            // Desktop notifications for sessions that need attention
+            app.manage(Notifier::new(Box::new(app.handle().clone())));
+
+            // Outgoing webhooks for session lifecycle and status events
+            let webhooks = Webhooks::new(app.handle().clone());
+            app.state::<SessionManager>().attach_observer(webhooks.clone());
+            app.manage(webhooks);
+
+            // Client for the headless session daemon. Connects lazily on first use;
+            // its sessions' output and exits go through the same sink as local ones
+            let daemon_client = DaemonClient::new(daemon::socket_path(), app.handle().clone());
+            if let Some(limit) = saved_limit {
+                daemon_client.configure_scrollback_limit(limit);
+            }
+            app.manage(daemon_client);
+
             // Start the HTTP status server for MCP status reporting
             // IMPORTANT: This must be done synchronously so the server is ready
             // before any commands try to use it
             let app_handle = app.handle().clone();
             let server = tauri::async_runtime::block_on(async {
-                StatusServer::start(app_handle, instance_id).await
+                let config = StatusServerConfig::from_env()?;
+                StatusServer::start(app_handle, instance_id, config).await
             });
 
             match server {
-                Some(server) => {
-                    log::info!(
-                        "Status server started on port {}, URL: {}",
-                        server.port(),
-                        server.status_url()
-                    );
+                Ok(server) => {
+                    log::info!("Status server started, URL: {}", server.status_url());
+                    // Opt-in shared MCP server that every session connects to over HTTP
+                    if McpHttpServer::enabled() {
+                        let started = tauri::async_runtime::block_on(McpHttpServer::start(
+                            &server.status_url(),
+                            server.instance_id(),
+                        ));
+                        match started {
+                            Ok(http_server) => {
+                                log::info!("Shared MCP server listening on {}", http_server.url());
+                                app.manage(Arc::new(http_server));
+                            }
+                            Err(e) => {
+                                log::warn!("Shared MCP server unavailable, using stdio: {}", e);
+                            }
+                        }
+                    }
                     app.manage(Arc::new(server));
                 }
-                None => {
-                    log::error!("Failed to start status server - MCP status reporting will not work");
-                    // Return error to prevent app from starting without status server
-                    return Err("Failed to start status server".into());
+                Err(e) => {
+                    // Keep the UI up so the user can see why; sessions can't
+                    // report status until the configuration is fixed
+                    log::error!("Failed to start status server: {}", e);
+                    app.dialog()
+                        .message(format!(
+                            "{}\n\nAgent status reporting is disabled. Set {} or {} to \
+                             choose another address, or {} to use a Unix socket.",
+                            e, PORTS_ENV, BIND_ENV, SOCKET_ENV
+                        ))
+                        .kind(MessageDialogKind::Error)
+                        .title("Status server unavailable")
+                        .show(|_| {});
+                    app.manage(e);
                 }
             }
 
@@ -81,15 +165,42 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // PTY commands (existing)
             commands::terminal::spawn_shell,
+            commands::terminal::spawn_agent,
+            commands::terminal::get_launch_profile,
+            commands::terminal::save_launch_profile,
             commands::terminal::write_stdin,
+            commands::terminal::get_session_scrollback,
+            commands::terminal::get_scrollback_limit,
+            commands::terminal::set_scrollback_limit,
             commands::terminal::resize_pty,
             commands::terminal::kill_session,
             commands::terminal::kill_all_sessions,
+            commands::terminal::detach_session,
+            commands::terminal::sessions_persist,
             commands::terminal::check_cli_available,
             commands::terminal::get_backend_info,
             commands::terminal::get_session_process_tree,
             commands::terminal::get_all_process_trees,
             commands::terminal::kill_process,
+            // Session recording commands
+            commands::recording::start_session_recording,
+            commands::recording::stop_session_recording,
+            commands::recording::get_session_recording,
+            commands::recording::list_recordings,
+            commands::recording::get_recording,
+            commands::recording::replay_recording,
+            commands::recording::set_replay_speed,
+            commands::recording::stop_replay,
+            // Session daemon commands
+            commands::daemon::daemon_status,
+            commands::daemon::daemon_list_sessions,
+            commands::daemon::daemon_spawn_shell,
+            commands::daemon::daemon_attach_session,
+            commands::daemon::daemon_detach_session,
+            commands::daemon::daemon_write_stdin,
+            commands::daemon::daemon_resize_pty,
+            commands::daemon::daemon_kill_session,
+            commands::daemon::daemon_shutdown,
             // Git commands
             commands::git::git_branches,
             commands::git::git_current_branch,
@@ -111,6 +222,15 @@ pub fn run() {
             commands::git::git_set_remote_url,
             commands::git::git_get_default_branch,
             commands::git::git_set_default_branch,
+            commands::git::git_merge_check,
+            commands::git::git_diff_worktree,
+            commands::git::git_diff_unstaged,
+            commands::git::git_status,
+            commands::git::git_stage_paths,
+            commands::git::git_unstage_paths,
+            commands::git::git_stage_hunk,
+            commands::git::git_discard_paths,
+            commands::git::git_commit,
             // Session commands (new)
             commands::session::get_sessions,
             commands::session::create_session,
@@ -119,9 +239,36 @@ pub fn run() {
             commands::session::remove_session,
             commands::session::get_sessions_for_project,
             commands::session::remove_sessions_for_project,
+            commands::session::get_restorable_sessions,
+            commands::session::restore_sessions,
+            commands::session::discard_restorable_sessions,
+            commands::session::get_builtin_status_rules,
+            commands::session::get_status_rules,
+            commands::session::save_status_rules,
+            // Notification commands
+            commands::notifications::get_notification_rules,
+            commands::notifications::set_notification_rules,
+            commands::notifications::set_focused_session,
+            // Webhook commands
+            commands::webhooks::get_webhooks,
+            commands::webhooks::set_webhooks,
+            commands::webhooks::send_test_webhook,
+            // Task board commands
+            commands::task_board::get_task_board,
+            commands::task_board::create_task,
+            commands::task_board::assign_task,
+            commands::task_board::complete_task,
+            commands::task_board::fail_task,
+            commands::task_board::reopen_task,
+            commands::task_board::delete_task,
             // Worktree commands
             commands::worktree::prepare_session_worktree,
             commands::worktree::cleanup_session_worktree,
+            commands::worktree::merge_session_branch,
+            commands::worktree::get_worktree_bootstrap,
+            commands::worktree::set_worktree_bootstrap,
+            commands::worktree::scan_worktree_gc,
+            commands::worktree::apply_worktree_gc,
             // MCP commands
             commands::mcp::get_project_mcp_servers,
             commands::mcp::refresh_project_mcp_servers,
@@ -133,6 +280,10 @@ pub fn run() {
             commands::mcp::add_mcp_project,
             commands::mcp::remove_mcp_project,
             commands::mcp::remove_session_status,
+            commands::mcp::answer_session_prompt,
+            commands::mcp::get_session_progress,
+            commands::mcp::get_session_status_history,
+            commands::mcp::get_project_activity,
             commands::mcp::write_session_mcp_config,
             commands::mcp::remove_session_mcp_config,
             commands::mcp::generate_project_hash,
@@ -187,6 +338,33 @@ pub fn run() {
         .expect("error while running Maestro");
 }
 
+/// Entry point for the `maestro-daemon` binary.
+///
+/// Runs the headless session daemon on `daemon::socket_path()` until it is
+/// asked to shut down or receives SIGTERM/SIGINT. Exits with status 1 if the
+/// socket cannot be bound (e.g. another daemon is already running).
+pub fn run_daemon() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .format_timestamp_millis()
+        .init();
+
+    #[cfg(unix)]
+    {
+        let socket_path = daemon::socket_path();
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+        if let Err(e) = runtime.block_on(daemon::server::serve(&socket_path)) {
+            log::error!("Session daemon failed on {}: {}", socket_path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        log::error!("{}", daemon::DaemonError::Unsupported);
+        std::process::exit(1);
+    }
+}
+
 // Note: We intentionally don't check git availability at startup.
 // Spawning processes during Tauri's app initialization phase can cause
 // crashes on some systems (particularly macOS with certain shell configurations).
@@ -1,7 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { GitFork, RefreshCw, X } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
-import { killSession } from "@/lib/terminal";
+import { killSession, sessionsPersist } from "@/lib/terminal";
 import { useOpenProject } from "@/lib/useOpenProject";
 import { useSessionStore } from "@/stores/useSessionStore";
 import { useWorkspaceStore } from "@/stores/useWorkspaceStore";
@@ -55,9 +55,11 @@ function App() {
   }, [theme]);
 
   // Clean up orphaned PTY sessions on mount (e.g., after page reload)
-  // This ensures no stale processes remain from the previous frontend state
+  // This ensures no stale processes remain from the previous frontend state.
+  // Sessions hosted by the session daemon are left running; grids reattach to them.
   useEffect(() => {
-    invoke<number>("kill_all_sessions")
+    sessionsPersist()
+      .then((persist) => (persist ? 0 : invoke<number>("kill_all_sessions")))
       .then((count) => {
         if (count > 0) {
           console.log(`Cleaned up ${count} orphaned PTY session(s) from previous page load`);
@@ -9,6 +9,7 @@ import {
   Cpu,
   Edit2,
   FileText,
+  Film,
   GitBranch,
   Globe,
   Home,
@@ -45,9 +46,11 @@ import { MarketplaceBrowser } from "@/components/marketplace";
 import { McpServerEditorModal } from "@/components/mcp";
 import { ClaudeMdEditorModal } from "@/components/claudemd";
 import { CliSettingsModal } from "@/components/terminal/CliSettingsModal";
+import { RecordingReplayModal } from "@/components/terminal/RecordingReplayModal";
 import { TerminalSettingsModal } from "@/components/terminal/TerminalSettingsModal";
 import type { McpCustomServer } from "@/lib/mcp";
 import { checkClaudeMd, type ClaudeMdStatus } from "@/lib/claudemd";
+import { listRecordings, type RecordingInfo } from "@/lib/recording";
 
 type SidebarTab = "config" | "processes";
 
@@ -1408,6 +1411,8 @@ function ProcessesTab() {
       <ProcessTreeSection />
       {divider}
       <OrphanedProcessesSection />
+      {divider}
+      <RecordingsSection />
     </>
   );
 }
@@ -1720,3 +1725,83 @@ function OrphanedProcessesSection() {
     </div>
   );
 }
+
+/* ── 4. Recordings ── */
+
+function RecordingsSection() {
+  const [recordings, setRecordings] = useState<RecordingInfo[]>([]);
+  const [isLoading, setIsLoading] = useState(false);
+  const [replaying, setReplaying] = useState<RecordingInfo | null>(null);
+
+  const refresh = useCallback(() => {
+    setIsLoading(true);
+    listRecordings()
+      .then(setRecordings)
+      .catch(console.error)
+      .finally(() => setIsLoading(false));
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return (
+    <>
+      <div className={cardClass}>
+        <SectionHeader
+          icon={Film}
+          label="Recordings"
+          iconColor="text-maestro-accent"
+          badge={
+            <span className="bg-maestro-accent/20 text-maestro-accent text-[10px] px-1.5 rounded-full font-bold">
+              {recordings.length}
+            </span>
+          }
+          right={
+            <button
+              type="button"
+              onClick={refresh}
+              className="rounded p-0.5 hover:bg-maestro-border/40"
+            >
+              <RefreshCw size={12} className={`text-maestro-muted ${isLoading ? "animate-spin" : ""}`} />
+            </button>
+          }
+        />
+        <div className="space-y-0.5">
+          {recordings.length === 0 ? (
+            <div className="px-2 py-1 text-[11px] text-maestro-muted/60">No recordings</div>
+          ) : (
+            recordings.map((r) => (
+              <div
+                key={r.id}
+                className="flex items-center gap-2 rounded-md px-2 py-1 text-xs text-maestro-text hover:bg-maestro-border/40"
+              >
+                <span className="flex-1 truncate">
+                  <span className="font-medium">
+                    {r.title ?? (r.sessionId !== null ? `Session #${r.sessionId}` : r.id)}
+                  </span>{" "}
+                  {r.startedAt !== null && (
+                    <span className="text-maestro-muted">
+                      {new Date(r.startedAt * 1000).toLocaleString()}
+                    </span>
+                  )}
+                </span>
+                <button
+                  type="button"
+                  onClick={() => setReplaying(r)}
+                  className="rounded p-0.5 hover:bg-maestro-border/40"
+                  title="Replay"
+                >
+                  <Play size={12} className="text-maestro-green" />
+                </button>
+              </div>
+            ))
+          )}
+        </div>
+      </div>
+      {replaying && (
+        <RecordingReplayModal recording={replaying} onClose={() => setReplaying(null)} />
+      )}
+    </>
+  );
+}
@@ -0,0 +1,70 @@
+import { Send } from "lucide-react";
+import { useState } from "react";
+import { answerSessionPrompt } from "@/lib/mcp";
+
+interface PromptAnswerBarProps {
+  /** Session whose `needs_input` prompt is being answered */
+  sessionId: number;
+  /** The question the agent asked */
+  prompt: string;
+}
+
+/**
+ * Inline answer box for a session's `needs_input` prompt. The answer goes
+ * back to the agent through its Maestro MCP server (`maestro_ask_user`)
+ * instead of being typed into the terminal.
+ */
+export function PromptAnswerBar({ sessionId, prompt }: PromptAnswerBarProps) {
+  const [answer, setAnswer] = useState("");
+  const [sending, setSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const submit = async () => {
+    const text = answer.trim();
+    if (!text || sending) return;
+    setSending(true);
+    setError(null);
+    try {
+      await answerSessionPrompt(sessionId, text);
+      setAnswer("");
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setSending(false);
+    }
+  };
+
+  return (
+    <div
+      className="flex shrink-0 flex-col gap-1 border-t border-maestro-border bg-maestro-surface px-2 py-1.5"
+      onClick={(e) => e.stopPropagation()}
+    >
+      <span className="truncate text-[11px] text-maestro-yellow" title={prompt}>
+        {prompt}
+      </span>
+      <div className="flex items-center gap-1">
+        <input
+          type="text"
+          value={answer}
+          onChange={(e) => setAnswer(e.target.value)}
+          onKeyDown={(e) => {
+            if (e.key === "Enter") void submit();
+          }}
+          placeholder="Answer the agent…"
+          disabled={sending}
+          className="min-w-0 flex-1 rounded border border-maestro-border bg-maestro-bg px-1.5 py-0.5 text-[11px] text-maestro-text outline-none focus:border-maestro-accent"
+        />
+        <button
+          type="button"
+          onClick={() => void submit()}
+          disabled={sending || !answer.trim()}
+          title="Send answer"
+          className="inline-flex items-center justify-center rounded p-1 text-maestro-muted transition-colors hover:bg-maestro-card hover:text-maestro-text disabled:cursor-not-allowed disabled:opacity-50"
+        >
+          <Send size={11} />
+        </button>
+      </div>
+      {error && <span className="truncate text-[10px] text-maestro-red">{error}</span>}
+    </div>
+  );
+}
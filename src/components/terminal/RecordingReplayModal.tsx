@@ -0,0 +1,202 @@
+import { Terminal } from "@xterm/xterm";
+import { Film, RotateCcw, X } from "lucide-react";
+import { useEffect, useRef, useState } from "react";
+import "@xterm/xterm/css/xterm.css";
+
+import { buildFontFamily } from "@/lib/fonts";
+import {
+  onReplayEvent,
+  type RecordingInfo,
+  replayRecording,
+  setReplaySpeed,
+  stopReplay,
+} from "@/lib/recording";
+import { DEFAULT_THEME, LIGHT_THEME, toXtermTheme } from "@/lib/terminalTheme";
+import { useTerminalSettingsStore } from "@/stores/useTerminalSettingsStore";
+
+interface RecordingReplayModalProps {
+  recording: RecordingInfo;
+  onClose: () => void;
+}
+
+/** Playback speeds offered in the speed selector. */
+const SPEEDS = [0.5, 1, 2, 4, 8];
+
+/**
+ * Modal that replays a session recording into a read-only terminal pane.
+ * The terminal starts at the recorded size and follows recorded resizes.
+ */
+export function RecordingReplayModal({ recording, onClose }: RecordingReplayModalProps) {
+  const modalRef = useRef<HTMLDivElement>(null);
+  const containerRef = useRef<HTMLDivElement>(null);
+  const termRef = useRef<Terminal | null>(null);
+  const replayIdRef = useRef<number | null>(null);
+  const speedRef = useRef(1);
+  const [speed, setSpeed] = useState(1);
+  const [finished, setFinished] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  // Bumped to start the replay over from the beginning.
+  const [run, setRun] = useState(0);
+
+  // Close on outside click
+  useEffect(() => {
+    const handleClick = (e: MouseEvent) => {
+      if (modalRef.current && !modalRef.current.contains(e.target as Node)) {
+        onClose();
+      }
+    };
+    document.addEventListener("mousedown", handleClick);
+    return () => document.removeEventListener("mousedown", handleClick);
+  }, [onClose]);
+
+  // Close on Escape
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === "Escape") {
+        onClose();
+      }
+    };
+    document.addEventListener("keydown", handleKeyDown);
+    return () => document.removeEventListener("keydown", handleKeyDown);
+  }, [onClose]);
+
+  useEffect(() => {
+    const container = containerRef.current;
+    if (!container) return;
+
+    const settings = useTerminalSettingsStore.getState();
+    const initialTheme =
+      document.documentElement.getAttribute("data-theme") === "light" ? LIGHT_THEME : DEFAULT_THEME;
+    const term = new Terminal({
+      cols: Math.max(recording.width, 1),
+      rows: Math.max(recording.height, 1),
+      fontSize: settings.settings.fontSize,
+      fontFamily: buildFontFamily(settings.getEffectiveFontFamily()),
+      lineHeight: settings.settings.lineHeight,
+      theme: toXtermTheme(initialTheme),
+      disableStdin: true,
+      cursorBlink: false,
+      scrollback: 3000,
+    });
+    term.open(container);
+    termRef.current = term;
+
+    // Pane IDs become part of the event name, so keep them to [A-Za-z0-9_-].
+    const paneId = `replay-${Date.now()}-${Math.random().toString(36).slice(2, 10)}`;
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    setFinished(false);
+    setError(null);
+
+    // Subscribe before starting the replay so the first events are not lost.
+    onReplayEvent(paneId, (event) => {
+      switch (event.type) {
+        case "output":
+          term.write(event.data);
+          break;
+        case "resize":
+          term.resize(Math.max(event.cols, 1), Math.max(event.rows, 1));
+          break;
+        case "finished":
+          replayIdRef.current = null;
+          setFinished(true);
+          break;
+      }
+    })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+          return;
+        }
+        unlisten = fn;
+        return replayRecording(recording.id, paneId, speedRef.current).then((id) => {
+          if (disposed) {
+            stopReplay(id).catch(console.error);
+          } else {
+            replayIdRef.current = id;
+          }
+        });
+      })
+      .catch((err) => {
+        if (!disposed) setError(String(err));
+      });
+
+    return () => {
+      disposed = true;
+      if (replayIdRef.current !== null) {
+        stopReplay(replayIdRef.current).catch(console.error);
+        replayIdRef.current = null;
+      }
+      unlisten?.();
+      term.dispose();
+      termRef.current = null;
+    };
+  }, [recording, run]);
+
+  const changeSpeed = (next: number) => {
+    speedRef.current = next;
+    setSpeed(next);
+    if (replayIdRef.current !== null) {
+      setReplaySpeed(replayIdRef.current, next).catch(console.error);
+    }
+  };
+
+  const title = recording.title ?? (recording.sessionId !== null ? `Session #${recording.sessionId}` : recording.id);
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+      <div
+        ref={modalRef}
+        className="flex max-h-[90vh] max-w-[90vw] flex-col rounded-lg border border-maestro-border bg-maestro-bg shadow-2xl"
+      >
+        {/* Header */}
+        <div className="flex items-center justify-between gap-4 border-b border-maestro-border px-4 py-3">
+          <div className="flex min-w-0 items-center gap-2">
+            <Film size={16} className="shrink-0 text-maestro-accent" />
+            <h2 className="truncate text-sm font-semibold text-maestro-text">{title}</h2>
+            {finished && (
+              <span className="rounded bg-maestro-green/20 px-1.5 py-0.5 text-[10px] font-medium text-maestro-green">
+                Finished
+              </span>
+            )}
+          </div>
+          <div className="flex items-center gap-2">
+            <select
+              value={speed}
+              onChange={(e) => changeSpeed(Number(e.target.value))}
+              className="rounded border border-maestro-border bg-maestro-card px-1.5 py-0.5 text-xs text-maestro-text"
+            >
+              {SPEEDS.map((s) => (
+                <option key={s} value={s}>
+                  {s}x
+                </option>
+              ))}
+            </select>
+            <button
+              type="button"
+              onClick={() => setRun((r) => r + 1)}
+              className="rounded p-1 hover:bg-maestro-border/40"
+              title="Replay from start"
+            >
+              <RotateCcw size={14} className="text-maestro-muted" />
+            </button>
+            <button
+              type="button"
+              onClick={onClose}
+              className="rounded p-1 hover:bg-maestro-border/40"
+            >
+              <X size={16} className="text-maestro-muted" />
+            </button>
+          </div>
+        </div>
+
+        {/* Content */}
+        <div className="overflow-auto p-3">
+          {error && <div className="mb-2 text-xs text-maestro-red">{error}</div>}
+          <div ref={containerRef} />
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -16,16 +16,23 @@ import {
 import {
   AI_CLI_CONFIG,
   assignSessionBranch,
-  buildCliCommand,
-  checkCliAvailable,
+  buildCliArgs,
   createSession,
+  discardRestorableSessions,
+  getRestorableSessions,
   killSession,
+  restoreSessions,
+  sessionsPersist,
+  spawnAgent,
   spawnShell,
-  waitForTerminalReady,
-  writeStdin,
+  type SessionConfig,
 } from "@/lib/terminal";
 import { useCliSettingsStore } from "@/stores/useCliSettingsStore";
-import { cleanupSessionWorktree, prepareSessionWorktree } from "@/lib/worktreeManager";
+import {
+  cleanupSessionWorktree,
+  onWorktreeBootstrapProgress,
+  prepareSessionWorktree,
+} from "@/lib/worktreeManager";
 import { useTerminalKeyboard } from "@/hooks/useTerminalKeyboard";
 import { useMcpStore } from "@/stores/useMcpStore";
 import { usePluginStore } from "@/stores/usePluginStore";
@@ -124,7 +131,7 @@ export interface TerminalGridHandle {
 }
 
 /**
- * @property projectPath - Working directory passed to `spawnShell`; when absent the backend
+ * @property projectPath - Working directory for spawned sessions; when absent the backend
  *   uses its own default cwd.
  * @property tabId - Workspace tab ID for session-project association.
  * @property preserveOnHide - If true, don't kill sessions when component unmounts (for project switching).
@@ -144,8 +151,11 @@ interface TerminalGridProps {
  * - Active terminal views (connected to a backend PTY session)
  *
  * Lifecycle:
- * - On mount, creates a single empty slot for the user to configure.
- * - User configures AI mode and branch, then clicks "Launch" to spawn a shell.
+ * - On mount, creates a single empty slot for the user to configure. Sessions
+ *   left open by a previous app run are reattached in its place when they
+ *   persist in the session daemon, or offered for restoring otherwise.
+ * - User configures AI mode and branch, then clicks "Launch" to spawn its CLI
+ *   (or a shell for Plain).
  * - `addSession` creates new pre-launch slots up to MAX_SESSIONS.
  * - "Launch All" spawns all unlaunched slots with their configured settings.
  * - When all sessions are killed by the user, an auto-respawn effect creates
@@ -176,6 +186,8 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
   // Track session slots (pre-launch and launched)
   const [slots, setSlots] = useState<SessionSlot[]>(() => [createEmptySlot()]);
   const [error, setError] = useState<string | null>(null);
+  // Sessions the previous app run left open, offered for restoring
+  const [restorable, setRestorable] = useState<SessionConfig[]>([]);
 
   // Track which terminal slot is focused (by slot ID)
   const [focusedSlotId, setFocusedSlotId] = useState<string | null>(null);
@@ -235,6 +247,31 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
     onSessionCountChange?.(slots.length, launchedCount);
   }, [slots, onSessionCountChange]);
 
+  // Log the progress of worktree bootstraps, which hold a launch until they finish.
+  // A bootstrap that fails comes back as the worktree warning of its launch.
+  useEffect(() => {
+    const unlisten = onWorktreeBootstrapProgress((progress) => {
+      switch (progress.type) {
+        case "command_started":
+          console.log(
+            `[Worktree] Bootstrap ${progress.index + 1}/${progress.total} in ${progress.worktree_path}: ${progress.command}`
+          );
+          break;
+        case "command_finished":
+          if (progress.result.exit_code !== 0) {
+            console.error(
+              `[Worktree] Bootstrap command failed in ${progress.worktree_path}:`,
+              progress.result
+            );
+          }
+          break;
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Fetch branches and MCP servers when projectPath is available
   useEffect(() => {
     if (!projectPath) {
@@ -262,6 +299,65 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
     fetchPlugins(projectPath).catch(console.error);
   }, [projectPath, fetchMcpServers, fetchPlugins]);
 
+  /**
+   * Brings back the sessions the previous app run left open in this project.
+   * Sessions still running in the session daemon are reattached; the rest
+   * get a fresh shell in their worktree.
+   */
+  const restorePrevious = useCallback(async () => {
+    if (!projectPath) return;
+    setRestorable([]);
+    const projectHash = await invoke<string>("generate_project_hash", { projectPath });
+    const restored = await restoreSessions(projectPath, { MAESTRO_PROJECT_HASH: projectHash });
+    if (restored.length === 0) return;
+
+    await invoke("add_mcp_project", { projectPath });
+    const restoredSlots = restored.map(({ session }): SessionSlot => {
+      useSessionStore.getState().addSession({
+        ...session,
+        status: session.status as import("@/stores/useSessionStore").BackendSessionStatus,
+      });
+      if (tabId) {
+        addSessionToProject(tabId, session.id);
+      }
+      return {
+        ...createEmptySlot(),
+        mode: session.mode,
+        branch: session.branch,
+        sessionId: session.id,
+        worktreePath: session.worktree_path,
+      };
+    });
+    // Restored sessions replace the untouched pre-launch slots
+    setSlots((prev) => [...restoredSlots, ...prev.filter((s) => s.sessionId !== null)]);
+  }, [projectPath, tabId, addSessionToProject]);
+
+  /** Forgets the sessions the previous app run left open in this project. */
+  const discardPrevious = useCallback(async () => {
+    if (!projectPath) return;
+    setRestorable([]);
+    await discardRestorableSessions(projectPath);
+  }, [projectPath]);
+
+  // Look for sessions the previous app run left open. Sessions that persist
+  // in the session daemon are still running, so they are reattached right
+  // away; otherwise the user is offered to start them again.
+  useEffect(() => {
+    if (!projectPath) return;
+    const check = async () => {
+      const saved = await getRestorableSessions(projectPath);
+      if (saved.length === 0) return;
+      if (await sessionsPersist()) {
+        await restorePrevious();
+      } else {
+        setRestorable(saved);
+      }
+    };
+    check().catch((err) => {
+      console.error("Failed to restore sessions:", err);
+    });
+  }, [projectPath, restorePrevious]);
+
   // Update slot enabled MCP servers when servers are fetched
   useEffect(() => {
     if (mcpServers.length > 0) {
@@ -389,7 +485,7 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
 
   /**
    * Inner implementation of launchSlot, called within the project lock.
-   * Spawns a shell with the configured settings. If a branch is selected,
+   * Spawns the mode's CLI (or a shell for Plain) with the configured settings. If a branch is selected,
    * prepares a worktree for that branch first.
    */
   const launchSlotInner = useCallback(async (slotId: string) => {
@@ -435,9 +531,52 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
         envVars = { MAESTRO_PROJECT_HASH: projectHash };
       }
 
-      // Spawn the shell in the correct directory (worktree or project path)
+      // Spawn the session in the correct directory (worktree or project path).
+      // AI modes run their CLI directly as the PTY's child; Plain gets a shell.
       // MAESTRO_SESSION_ID is automatically injected by the backend
-      const sessionId = await spawnShell(workingDirectory, envVars);
+      let sessionId: number;
+      let agentLaunched = false;
+      if (slot.mode === "Plain") {
+        sessionId = await spawnShell(workingDirectory, envVars);
+      } else {
+        const cliFlags = useCliSettingsStore.getState().getFlags(slot.mode);
+        try {
+          sessionId = await spawnAgent(
+            slot.mode,
+            workingDirectory,
+            envVars,
+            buildCliArgs(slot.mode, cliFlags)
+          );
+          agentLaunched = true;
+        } catch (err) {
+          console.warn(
+            `Failed to launch ${slot.mode} CLI (install with: ${AI_CLI_CONFIG[slot.mode].installHint}):`,
+            err
+          );
+          sessionId = await spawnShell(workingDirectory, envVars);
+        }
+      }
+
+      // Write MCP config right away: the CLI reads .mcp.json while starting up,
+      // and an IPC round trip beats its startup by a wide margin. The session
+      // name is only known once spawned, so this can't happen any earlier.
+      if (agentLaunched && workingDirectory && slot.mode === "Claude") {
+        try {
+          await writeSessionMcpConfig(
+            workingDirectory,
+            sessionId,
+            projectPath ?? workingDirectory,
+            slot.enabledMcpServers
+          );
+        } catch (err) {
+          console.error("Failed to write MCP config:", err);
+          // Non-fatal - the CLI runs without MCP servers
+        }
+
+        // NOTE: We no longer write plugin config to settings.local.json
+        // Claude CLI auto-discovers plugins from ~/.claude/plugins/
+        // Writing a `plugins` array was interfering with auto-discovery
+      }
 
       // Register the session in SessionManager (required before assigning branch)
       if (projectPath) {
@@ -471,11 +610,9 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
         await setSessionPlugins(projectPath, sessionId, slot.enabledPlugins);
       }
 
-      // Update slot state FIRST to mount TerminalView and initialize xterm.js.
-      // This MUST happen before sending any commands to the PTY, otherwise
-      // xterm.js won't be listening when output arrives and it will be lost.
-      // This is also critical because CLIs like Codex send DSR (cursor position)
-      // queries on startup, and xterm.js must be mounted to respond to them.
+      // Update slot state to mount TerminalView and initialize xterm.js.
+      // Output the CLI printed before then is replayed from the session's
+      // scrollback when the pane attaches.
       setSlots((prev) =>
         prev.map((s) =>
           s.id === slotId ? { ...s, sessionId, worktreePath, worktreeWarning } : s
@@ -487,78 +624,19 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
         addSessionToProject(tabId, sessionId);
       }
 
-      // Auto-launch AI CLI after shell initializes
-      // IMPORTANT: For Claude mode, we must write MCP config and launch CLI atomically
-      // to prevent race conditions when multiple sessions launch without worktrees.
-      // Without worktrees, all sessions share the same .mcp.json file, so we must:
-      // 1. Write .mcp.json for this session
-      // 2. Launch CLI immediately (before any other session can overwrite .mcp.json)
-      // 3. Wait for CLI to read the config
-      if (slot.mode !== "Plain") {
-        const cliConfig = AI_CLI_CONFIG[slot.mode];
-        if (cliConfig.command) {
-          const isAvailable = await checkCliAvailable(cliConfig.command);
-
-          if (isAvailable) {
-            // Write MCP config IMMEDIATELY before launching CLI
-            // This allows the CLI to discover MCP servers including the Maestro status server
-            if (workingDirectory && slot.mode === "Claude") {
-              try {
-                await writeSessionMcpConfig(
-                  workingDirectory,
-                  sessionId,
-                  projectPath ?? workingDirectory,
-                  slot.enabledMcpServers
-                );
-              } catch (err) {
-                console.error("Failed to write MCP config:", err);
-                // Non-fatal - continue with CLI launch, MCP servers just won't be available
-              }
-
-              // NOTE: We no longer write plugin config to settings.local.json
-              // Claude CLI auto-discovers plugins from ~/.claude/plugins/
-              // Writing a `plugins` array was interfering with auto-discovery
-            }
-
-            // Wait for xterm.js to mount and start listening for PTY output
-            // This ensures we don't send CLI commands before the terminal is ready
-            // (which would cause output to be lost since Tauri events aren't buffered)
-            try {
-              await waitForTerminalReady(sessionId);
-            } catch (err) {
-              console.warn("Terminal ready timeout, proceeding anyway:", err);
-            }
-
-            // Brief delay for shell to initialize
-            await new Promise((resolve) => setTimeout(resolve, 100));
-
-            // Build CLI command with user-configured flags
-            const cliFlags = useCliSettingsStore.getState().getFlags(slot.mode);
-            const cliCommand = buildCliCommand(slot.mode, cliFlags);
-
-            // Send CLI launch command
-            await writeStdin(sessionId, `${cliCommand}\r`);
-
-            // Brief delay for CLI initialization.
-            // With session-specific MCP server names (maestro-1, maestro-2, etc.),
-            // we no longer have race conditions on .mcp.json, so we only need
-            // a minimal delay for general CLI startup.
-            await new Promise((resolve) => setTimeout(resolve, 500));
-          } else {
-            console.warn(
-              `CLI '${cliConfig.command}' not found. Install with: ${cliConfig.installHint}`
-            );
-          }
-        }
+      // Hold the project lock a little longer so the next launch can't
+      // rewrite a shared .mcp.json before this CLI has read it.
+      if (agentLaunched && slot.mode === "Claude") {
+        await new Promise((resolve) => setTimeout(resolve, 500));
       }
     } catch (err) {
-      console.error("Failed to spawn shell:", err);
+      console.error("Failed to start session:", err);
       setError("Failed to start terminal session");
     }
   }, [projectPath, tabId, addSessionToProject]);
 
   /**
-   * Launches a single slot by spawning a shell with the configured settings.
+   * Launches a single slot with its configured settings.
    *
    * NOTE: Uses withProjectLock to serialize launches within the same project.
    * This prevents race conditions where multiple sessions share the same .mcp.json file.
@@ -854,41 +932,74 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
   }
 
   return (
-    <div className={`grid h-full ${gridClass(slots.length)} gap-2 bg-maestro-bg p-2`}>
-      {slots.map((slot) =>
-        slot.sessionId !== null ? (
-          <TerminalView
-            key={slot.id}
-            sessionId={slot.sessionId}
-            isFocused={focusedSlotId === slot.id}
-            onFocus={() => setFocusedSlotId(slot.id)}
-            onKill={handleKill}
-          />
-        ) : (
-          <PreLaunchCard
-            key={slot.id}
-            slot={slot}
-            projectPath={projectPath ?? ""}
-            branches={branches}
-            isLoadingBranches={isLoadingBranches}
-            isGitRepo={isGitRepo}
-            mcpServers={mcpServers}
-            skills={skills}
-            plugins={plugins}
-            onModeChange={(mode) => updateSlotMode(slot.id, mode)}
-            onBranchChange={(branch) => updateSlotBranch(slot.id, branch)}
-            onMcpToggle={(serverName) => toggleSlotMcp(slot.id, serverName)}
-            onSkillToggle={(skillId) => toggleSlotSkill(slot.id, skillId)}
-            onPluginToggle={(pluginId) => toggleSlotPlugin(slot.id, pluginId)}
-            onMcpSelectAll={() => selectAllMcp(slot.id)}
-            onMcpUnselectAll={() => unselectAllMcp(slot.id)}
-            onPluginsSelectAll={() => selectAllPlugins(slot.id)}
-            onPluginsUnselectAll={() => unselectAllPlugins(slot.id)}
-            onLaunch={() => launchSlot(slot.id)}
-            onRemove={() => removeSlot(slot.id)}
-          />
-        )
+    <div className="flex h-full flex-col bg-maestro-bg">
+      {restorable.length > 0 && (
+        <div className="flex items-center justify-between gap-3 border-b border-maestro-border px-3 py-2 text-xs text-maestro-muted">
+          <span>
+            {restorable.length === 1
+              ? "1 session from your last run can be restored."
+              : `${restorable.length} sessions from your last run can be restored.`}
+          </span>
+          <div className="flex items-center gap-2">
+            <button
+              type="button"
+              onClick={() => {
+                restorePrevious().catch((err) => {
+                  console.error("Failed to restore sessions:", err);
+                });
+              }}
+              className="rounded bg-maestro-accent px-3 py-1 text-xs text-white hover:bg-maestro-accent/80"
+            >
+              Restore
+            </button>
+            <button
+              type="button"
+              onClick={() => {
+                discardPrevious().catch(console.error);
+              }}
+              className="rounded bg-maestro-border px-3 py-1 text-xs text-maestro-text hover:bg-maestro-muted/20"
+            >
+              Discard
+            </button>
+          </div>
+        </div>
       )}
+      <div className={`grid min-h-0 flex-1 ${gridClass(slots.length)} gap-2 p-2`}>
+        {slots.map((slot) =>
+          slot.sessionId !== null ? (
+            <TerminalView
+              key={slot.id}
+              sessionId={slot.sessionId}
+              isFocused={focusedSlotId === slot.id}
+              onFocus={() => setFocusedSlotId(slot.id)}
+              onKill={handleKill}
+            />
+          ) : (
+            <PreLaunchCard
+              key={slot.id}
+              slot={slot}
+              projectPath={projectPath ?? ""}
+              branches={branches}
+              isLoadingBranches={isLoadingBranches}
+              isGitRepo={isGitRepo}
+              mcpServers={mcpServers}
+              skills={skills}
+              plugins={plugins}
+              onModeChange={(mode) => updateSlotMode(slot.id, mode)}
+              onBranchChange={(branch) => updateSlotBranch(slot.id, branch)}
+              onMcpToggle={(serverName) => toggleSlotMcp(slot.id, serverName)}
+              onSkillToggle={(skillId) => toggleSlotSkill(slot.id, skillId)}
+              onPluginToggle={(pluginId) => toggleSlotPlugin(slot.id, pluginId)}
+              onMcpSelectAll={() => selectAllMcp(slot.id)}
+              onMcpUnselectAll={() => unselectAllMcp(slot.id)}
+              onPluginsSelectAll={() => selectAllPlugins(slot.id)}
+              onPluginsUnselectAll={() => unselectAllPlugins(slot.id)}
+              onLaunch={() => launchSlot(slot.id)}
+              onRemove={() => removeSlot(slot.id)}
+            />
+          )
+        )}
+      </div>
     </div>
   );
 });
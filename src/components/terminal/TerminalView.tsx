@@ -6,11 +6,24 @@ import "@xterm/xterm/css/xterm.css";
 import { QuickActionsManager } from "@/components/quickactions/QuickActionsManager";
 import { useSessionBranch } from "@/hooks/useSessionBranch";
 import { buildFontFamily, waitForFont } from "@/lib/fonts";
-import { getBackendInfo, killSession, onPtyOutput, resizePty, signalTerminalReady, writeStdin, type BackendInfo } from "@/lib/terminal";
+import { setFocusedSession } from "@/lib/notifications";
+import {
+  detachSession,
+  getBackendInfo,
+  getSessionScrollback,
+  killSession,
+  onPtyExit,
+  onPtyOutput,
+  resizePty,
+  signalTerminalReady,
+  writeStdin,
+  type BackendInfo,
+} from "@/lib/terminal";
 import { DEFAULT_THEME, LIGHT_THEME, toXtermTheme } from "@/lib/terminalTheme";
 import { useMcpStore } from "@/stores/useMcpStore";
 import { type AiMode, type BackendSessionStatus, useSessionStore } from "@/stores/useSessionStore";
 import { useTerminalSettingsStore } from "@/stores/useTerminalSettingsStore";
+import { PromptAnswerBar } from "./PromptAnswerBar";
 import { QuickActionPills } from "./QuickActionPills";
 import { type AIProvider, type SessionStatus, TerminalHeader } from "./TerminalHeader";
 
@@ -30,6 +43,10 @@ interface TerminalViewProps {
   onKill: (sessionId: number) => void;
 }
 
+/** Scrollback offsets count UTF-8 bytes, not UTF-16 code units. */
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
 /** Map backend AiMode to frontend AIProvider */
 function mapAiMode(mode: AiMode): AIProvider {
   const map: Record<AiMode, AIProvider> = {
@@ -93,7 +110,8 @@ function cellStatusClass(status: SessionStatus): string {
  *
  * On unmount: sets a `disposed` flag to prevent late PTY writes, disconnects the
  * ResizeObserver, disposes xterm listeners, unsubscribes the Tauri event listener
- * (even if the listener promise hasn't resolved yet), and destroys the Terminal.
+ * (even if the listener promise hasn't resolved yet), detaches from a daemon-hosted
+ * session (which keeps running), and destroys the Terminal.
  */
 export function TerminalView({ sessionId, status = "idle", isFocused = false, onFocus, onKill }: TerminalViewProps) {
   const sessionConfig = useSessionStore((s) => s.sessions.find((sess) => sess.id === sessionId));
@@ -236,6 +254,7 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
     let term: Terminal | null = null;
     let fitAddon: FitAddon | null = null;
     let unlisten: (() => void) | null = null;
+    let unlistenExit: (() => void) | null = null;
     let dataDisposable: { dispose: () => void } | null = null;
     let resizeDisposable: { dispose: () => void } | null = null;
     let resizeObserver: ResizeObserver | null = null;
@@ -323,7 +342,7 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
         return true; // Let xterm handle all other keys
       });
 
-      const listenerReady = onPtyOutput(sessionId, (data) => {
+      const enqueueOutput = (data: string) => {
         if (disposed || !term) return;
         outputBuffer += data;
         if (flushScheduled) return;
@@ -351,13 +370,43 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
           });
         };
         flush();
+      };
+
+      // Live output that arrives while the scrollback replay is in flight is
+      // held back, then trimmed against the replay's end offset so a remounted
+      // pane shows every byte exactly once.
+      let replayedUntil = 0;
+      let pendingLive: [string, number][] | null = [];
+      const writeLive = (data: string, endOffset: number) => {
+        if (endOffset <= replayedUntil) return;
+        const bytes = encoder.encode(data);
+        const overlap = replayedUntil - (endOffset - bytes.length);
+        enqueueOutput(overlap > 0 ? decoder.decode(bytes.subarray(overlap)) : data);
+      };
+
+      const listenerReady = onPtyOutput(sessionId, (data, endOffset) => {
+        if (pendingLive) {
+          pendingLive.push([data, endOffset]);
+        } else {
+          writeLive(data, endOffset);
+        }
       });
       listenerReady
-        .then((fn) => {
+        .then(async (fn) => {
           if (disposed) {
             fn();
           } else {
             unlisten = fn;
+            try {
+              const chunk = await getSessionScrollback(sessionId);
+              replayedUntil = chunk.endOffset;
+              if (chunk.data) enqueueOutput(chunk.data);
+            } catch (err) {
+              console.warn("Scrollback replay failed:", err);
+            }
+            const held = pendingLive ?? [];
+            pendingLive = null;
+            for (const [data, endOffset] of held) writeLive(data, endOffset);
             // Signal that the terminal is ready to receive PTY output
             // This allows TerminalGrid to know it can now send CLI commands
             signalTerminalReady(sessionId);
@@ -369,6 +418,26 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
           }
         });
 
+      // Agent sessions run the CLI as the PTY's child, so the pane goes dead
+      // when it exits; say so instead of leaving the last frame on screen.
+      onPtyExit(sessionId, (status) => {
+        if (status.killed) return;
+        const reason = status.signal ? `signal ${status.signal}` : `code ${status.exitCode}`;
+        enqueueOutput(`\r\n\x1b[2m[Process exited with ${reason}]\x1b[0m\r\n`);
+      })
+        .then((fn) => {
+          if (disposed) {
+            fn();
+          } else {
+            unlistenExit = fn;
+          }
+        })
+        .catch((err) => {
+          if (!disposed) {
+            console.error("PTY exit listener failed:", err);
+          }
+        });
+
       resizeObserver = new ResizeObserver(() => {
         scheduleFit();
       });
@@ -401,6 +470,9 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
       dataDisposable?.dispose();
       resizeDisposable?.dispose();
       if (unlisten) unlisten();
+      if (unlistenExit) unlistenExit();
+      // A daemon-hosted session keeps running; the next mount reattaches
+      detachSession(sessionId).catch(console.error);
       term?.dispose();
       termRef.current = null;
       fitAddonRef.current = null;
@@ -419,6 +491,9 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
     <div
       className={`terminal-cell flex h-full flex-col bg-maestro-bg ${cellStatusClass(effectiveStatus)} ${isFocused ? "ring-2 ring-maestro-accent ring-inset" : ""}`}
       onClick={onFocus}
+      // Sessions whose pane has focus don't show desktop notifications
+      onFocus={() => setFocusedSession(sessionId).catch(console.error)}
+      onBlur={() => setFocusedSession(null).catch(console.error)}
     >
       {/* Rich header bar */}
       <TerminalHeader
@@ -435,6 +510,13 @@ export function TerminalView({ sessionId, status = "idle", isFocused = false, on
       {/* xterm.js container */}
       <div ref={containerRef} className="flex-1 overflow-hidden" />
 
+      {/* Answer box for questions asked through maestro_ask_user */}
+      {effectiveStatus === "needs-input" &&
+        sessionConfig?.awaitingReply &&
+        sessionConfig.needsInputPrompt && (
+          <PromptAnswerBar sessionId={sessionId} prompt={sessionConfig.needsInputPrompt} />
+        )}
+
       {/* Quick action pills */}
       <QuickActionPills
         onAction={handleQuickAction}
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { FileChangeStatus } from "../stores/useGitStore";
 import { listWorktrees } from "./worktreeManager";
 
 /** Branch info from the backend. */
@@ -60,3 +61,204 @@ export async function getBranchesWithWorktreeStatus(
 export async function getCurrentBranch(repoPath: string): Promise<string> {
   return invoke<string>("git_current_branch", { repoPath });
 }
+
+/**
+ * How a session branch is brought into its target. A rebase replays the
+ * branch on the target and then fast-forwards the target to it.
+ */
+export type MergeStrategy = "merge" | "squash" | "rebase";
+
+/** A file the merge could not resolve on its own. */
+export interface MergeConflict {
+  path: string;
+  /** Conflict type as git names it, e.g. "content" or "modify/delete". */
+  kind: string | null;
+  message: string | null;
+}
+
+/** Result of a dry-run merge of one branch into another. */
+export interface MergeCheck {
+  source_branch: string;
+  target_branch: string;
+  /** Commits on the source branch that the target doesn't have. */
+  commits_ahead: number;
+  conflicts: MergeConflict[];
+}
+
+/** Result of merging, squashing or rebasing a branch. */
+export interface MergeResult {
+  strategy: MergeStrategy;
+  /** False when conflicts were found; nothing was changed in that case. */
+  merged: boolean;
+  /** The new HEAD of the updated branch. */
+  commit: string | null;
+  conflicts: MergeConflict[];
+}
+
+/**
+ * Dry-runs merging a branch into another without touching any worktree.
+ * @param repoPath - Path to the git repository
+ * @param sourceBranch - Branch to merge
+ * @param targetBranch - Branch to merge into
+ * @returns Commits ahead and the files that would conflict
+ */
+export async function checkMerge(
+  repoPath: string,
+  sourceBranch: string,
+  targetBranch: string
+): Promise<MergeCheck> {
+  return invoke<MergeCheck>("git_merge_check", { repoPath, sourceBranch, targetBranch });
+}
+
+/** One line of a diff hunk. Line numbers are 1-based. */
+export interface DiffLine {
+  kind: "context" | "added" | "removed";
+  content: string;
+  /** Null for added lines. */
+  old_line: number | null;
+  /** Null for removed lines. */
+  new_line: number | null;
+  /** True on the last line of a file that doesn't end in a newline. */
+  no_newline: boolean;
+}
+
+/** A contiguous block of changes. */
+export interface DiffHunk {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  /** Text after the closing `@@`, usually the enclosing function. */
+  header: string;
+  lines: DiffLine[];
+}
+
+/** All changes to one file. Binary and too-large files have no hunks. */
+export interface FileDiff {
+  path: string;
+  /** Original path for renames and copies. */
+  old_path: string | null;
+  status: FileChangeStatus;
+  /** Rename/copy similarity percentage. */
+  similarity: number | null;
+  binary: boolean;
+  /** Untracked file too large to diff. */
+  too_large: boolean;
+  additions: number;
+  deletions: number;
+  hunks: DiffHunk[];
+}
+
+/**
+ * Gets what changed in a worktree, file by file.
+ * @param repoPath - Path to the worktree
+ * @param against - Base branch to compare the worktree's branch with
+ *   (`against...HEAD`), or null for uncommitted changes including untracked files
+ * @returns Per-file hunks
+ */
+export async function diffWorktree(
+  repoPath: string,
+  against: string | null = null
+): Promise<FileDiff[]> {
+  return invoke<FileDiff[]>("git_diff_worktree", { repoPath, against });
+}
+
+/**
+ * Gets the changes in a worktree that are not staged yet, file by file,
+ * including untracked files. Hunks are relative to the index, so they can be
+ * passed to `stageHunk`.
+ * @param repoPath - Path to the git repository or worktree
+ * @returns Per-file hunks
+ */
+export async function diffUnstaged(repoPath: string): Promise<FileDiff[]> {
+  return invoke<FileDiff[]>("git_diff_unstaged", { repoPath });
+}
+
+/** One side (staged or unstaged) of a file's status. */
+export type StatusCode =
+  | "unmodified"
+  | "modified"
+  | "typechanged"
+  | "added"
+  | "deleted"
+  | "renamed"
+  | "copied"
+  | "unmerged"
+  | "untracked"
+  | "ignored";
+
+/** A changed, untracked or conflicted file. */
+export interface StatusEntry {
+  path: string;
+  /** Original path for staged renames and copies. */
+  orig_path: string | null;
+  /** Staged state. */
+  index: StatusCode;
+  /** Unstaged state. */
+  worktree: StatusCode;
+  conflicted: boolean;
+}
+
+/**
+ * Gets the working tree status.
+ * @param repoPath - Path to the git repository or worktree
+ * @returns Changed, untracked and conflicted files
+ */
+export async function getStatus(repoPath: string): Promise<StatusEntry[]> {
+  return invoke<StatusEntry[]>("git_status", { repoPath });
+}
+
+/**
+ * Stages files, including deletions.
+ * @param repoPath - Path to the git repository or worktree
+ * @param paths - Paths relative to the repository root
+ */
+export async function stagePaths(repoPath: string, paths: string[]): Promise<void> {
+  await invoke("git_stage_paths", { repoPath, paths });
+}
+
+/**
+ * Unstages files without touching the working tree.
+ * @param repoPath - Path to the git repository or worktree
+ * @param paths - Paths relative to the repository root
+ */
+export async function unstagePaths(repoPath: string, paths: string[]): Promise<void> {
+  await invoke("git_unstage_paths", { repoPath, paths });
+}
+
+/**
+ * Stages a single hunk from `diffUnstaged` output.
+ * @param repoPath - Path to the git repository or worktree
+ * @param path - The file the hunk belongs to
+ * @param hunk - The hunk to stage
+ */
+export async function stageHunk(repoPath: string, path: string, hunk: DiffHunk): Promise<void> {
+  await invoke("git_stage_hunk", { repoPath, path, hunk });
+}
+
+/**
+ * Throws away unstaged changes. Tracked files are restored from the index
+ * and untracked files are deleted.
+ * @param repoPath - Path to the git repository or worktree
+ * @param paths - Paths relative to the repository root
+ */
+export async function discardPaths(repoPath: string, paths: string[]): Promise<void> {
+  await invoke("git_discard_paths", { repoPath, paths });
+}
+
+/**
+ * Commits the staged changes.
+ * @param repoPath - Path to the git repository or worktree
+ * @param message - Commit message (may be empty when amending to keep the old one)
+ * @param amend - Replace HEAD instead of adding a commit
+ * @param signoff - Add a Signed-off-by trailer
+ * @returns The new commit hash
+ */
+export async function commit(
+  repoPath: string,
+  message: string,
+  amend = false,
+  signoff = false
+): Promise<string> {
+  return invoke<string>("git_commit", { repoPath, message, amend, signoff });
+}
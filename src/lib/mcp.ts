@@ -5,6 +5,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 /** Environment variables for stdio MCP servers. */
 export type McpEnv = Record<string, string>;
@@ -128,8 +129,9 @@ export async function loadProjectMcpDefaults(
 /**
  * Writes a session-specific `.mcp.json` to the working directory.
  *
- * This MUST be called BEFORE launching the Claude CLI so it can discover
- * the configured MCP servers, including the Maestro status server.
+ * This MUST be called before the Claude CLI reads its config at startup
+ * (i.e. right after spawning it) so it can discover the configured MCP
+ * servers, including the Maestro status server.
  *
  * @param workingDir - Directory where the CLI will be launched
  * @param sessionId - Session ID for the Maestro MCP server env vars
@@ -166,6 +168,107 @@ export async function removeSessionMcpConfig(
   return invoke("remove_session_mcp_config", { workingDir, sessionId });
 }
 
+/**
+ * Answers a session's `needs_input` prompt. The answer is queued for the
+ * session's Maestro MCP server to read back from the status server.
+ */
+export async function answerSessionPrompt(
+  sessionId: number,
+  answer: string,
+): Promise<void> {
+  return invoke("answer_session_prompt", { sessionId, answer });
+}
+
+/** Files and links an agent has produced so far. */
+export interface ProgressArtifacts {
+  /** Files touched, relative to the worktree. */
+  files: string[];
+  pr_url: string | null;
+}
+
+/** The plan and position an agent last reported with `maestro_progress`. */
+export interface SessionProgress {
+  steps: string[];
+  /** Index into `steps`; equals `steps.length` once every step is done. */
+  current_step: number;
+  /** Percent complete (0-100), when the agent can tell. */
+  percent: number | null;
+  artifacts: ProgressArtifacts;
+  /** Milliseconds since the Unix epoch. */
+  updated_at: number;
+}
+
+/** Payload of the `session-progress-changed` event. */
+export interface SessionProgressChanged {
+  session_id: number;
+  project_path: string;
+  progress: SessionProgress;
+}
+
+/** Returns a session's latest progress, or null if it hasn't reported any. */
+export async function getSessionProgress(sessionId: number): Promise<SessionProgress | null> {
+  return invoke<SessionProgress | null>("get_session_progress", { sessionId });
+}
+
+/**
+ * Subscribes to progress reports from every session.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onSessionProgressChanged(
+  callback: (change: SessionProgressChanged) => void,
+): Promise<UnlistenFn> {
+  return listen<SessionProgressChanged>("session-progress-changed", (event) => {
+    callback(event.payload);
+  });
+}
+
+/** One status a session moved to. */
+export interface StatusTransition {
+  /** Session status as shown in the UI (`Working`, `NeedsInput`, ...). */
+  status: string;
+  message: string;
+  needs_input_prompt: string | null;
+  /** Milliseconds since the Unix epoch. */
+  timestamp: number;
+}
+
+/** A session's statuses, oldest first. */
+export interface SessionStatusHistory {
+  session_id: number;
+  project_path: string;
+  transitions: StatusTransition[];
+  /** When the session ended, or null while it is running. */
+  ended_at: number | null;
+}
+
+/** What a session did in a window of time. */
+export interface SessionActivity {
+  session_id: number;
+  /** Transitions in the window, preceded by the one in effect at its start. */
+  transitions: StatusTransition[];
+  /** Milliseconds spent in each status within the window. */
+  time_in_status: Record<string, number>;
+  ended_at: number | null;
+}
+
+/** Returns every status a session moved to, or null if it has no status yet. */
+export async function getSessionStatusHistory(
+  sessionId: number,
+): Promise<SessionStatusHistory | null> {
+  return invoke<SessionStatusHistory | null>("get_session_status_history", { sessionId });
+}
+
+/**
+ * Summarizes what each of a project's sessions did since `since`
+ * (milliseconds since the Unix epoch), including earlier runs.
+ */
+export async function getProjectActivity(
+  projectPath: string,
+  since?: number,
+): Promise<SessionActivity[]> {
+  return invoke<SessionActivity[]>("get_project_activity", { projectPath, since });
+}
+
 /**
  * Gets all custom MCP servers configured by the user.
  * Custom servers are stored globally and available across all projects.
@@ -0,0 +1,48 @@
+/**
+ * Thin wrappers around Tauri `invoke` for per-project desktop notification
+ * rules. Notifications themselves are shown by the backend when a session
+ * reports a status the project's rules watch.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+/** A daily local-time window with no notifications; may span midnight. */
+export interface QuietHours {
+  /** Minutes after local midnight the window opens (0-1439). */
+  start_minute: number;
+  /** Minutes after local midnight the window closes (0-1439). */
+  end_minute: number;
+}
+
+/** When a project's sessions show desktop notifications. */
+export interface NotificationRules {
+  enabled: boolean;
+  /** Session statuses that notify when entered, e.g. `NeedsInput`, `Done`, `Error`. */
+  states: string[];
+  quiet_hours: QuietHours | null;
+  /** Re-entering the same status within this many seconds doesn't notify again. */
+  dedupe_window_secs: number;
+  /** Play the system sound with the notification. */
+  sound: boolean;
+}
+
+/** Returns a project's notification rules (defaults if never configured). */
+export async function getNotificationRules(projectPath: string): Promise<NotificationRules> {
+  return invoke<NotificationRules>("get_notification_rules", { projectPath });
+}
+
+/** Replaces a project's notification rules. */
+export async function setNotificationRules(
+  projectPath: string,
+  rules: NotificationRules,
+): Promise<void> {
+  return invoke("set_notification_rules", { projectPath, rules });
+}
+
+/**
+ * Tells the backend which session's pane has keyboard focus, or null when none does.
+ * Sessions the user is looking at don't notify.
+ */
+export async function setFocusedSession(sessionId: number | null): Promise<void> {
+  return invoke("set_focused_session", { sessionId });
+}
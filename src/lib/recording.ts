@@ -0,0 +1,109 @@
+/**
+ * Thin wrappers around Tauri `invoke` / `listen` for session recordings,
+ * which are asciicast v2 files in the app data dir, and their replay into
+ * read-only panes.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/** Summary of a recording file. */
+export interface RecordingInfo {
+  id: string;
+  path: string;
+  /** Session the recording was made from, if known. */
+  sessionId: number | null;
+  /** Unix time in seconds when recording started. */
+  startedAt: number | null;
+  width: number;
+  height: number;
+  title: string | null;
+  sizeBytes: number;
+}
+
+/** A single recorded event. */
+export interface CastEvent {
+  /** Seconds since the recording started. */
+  time: number;
+  /** `"o"` (output), `"i"` (input) or `"r"` (resize). */
+  code: string;
+  data: string;
+}
+
+/** A full recording: its summary plus every event. */
+export interface Recording {
+  info: RecordingInfo;
+  /** Time of the last event, in seconds. */
+  duration: number;
+  events: CastEvent[];
+}
+
+/** One event of a replay, delivered on `recording-replay-{paneId}`. */
+export type ReplayEvent =
+  | { type: "output"; data: string }
+  | { type: "resize"; cols: number; rows: number }
+  | { type: "finished" };
+
+/** Starts recording a session. Returns the new recording's ID. */
+export async function startSessionRecording(sessionId: number): Promise<string> {
+  return invoke<string>("start_session_recording", { sessionId });
+}
+
+/**
+ * Stops recording a session. Returns the finished recording's ID, or null
+ * if the session was not being recorded.
+ */
+export async function stopSessionRecording(sessionId: number): Promise<string | null> {
+  return invoke<string | null>("stop_session_recording", { sessionId });
+}
+
+/** Returns the ID of the recording a session is writing, if any. */
+export async function getSessionRecording(sessionId: number): Promise<string | null> {
+  return invoke<string | null>("get_session_recording", { sessionId });
+}
+
+/** Lists all recordings, newest first. */
+export async function listRecordings(): Promise<RecordingInfo[]> {
+  return invoke<RecordingInfo[]>("list_recordings");
+}
+
+/** Returns a recording with all of its events. */
+export async function getRecording(recordingId: string): Promise<Recording> {
+  return invoke<Recording>("get_recording", { recordingId });
+}
+
+/**
+ * Replays a recording into the pane `paneId` at `speed` times real time.
+ * Subscribe with `onReplayEvent` first so no event is missed. Returns the
+ * replay ID for `setReplaySpeed` and `stopReplay`.
+ */
+export async function replayRecording(
+  recordingId: string,
+  paneId: string,
+  speed?: number,
+): Promise<number> {
+  return invoke<number>("replay_recording", { recordingId, paneId, speed: speed ?? null });
+}
+
+/** Changes a running replay's speed. Returns false if it already finished. */
+export async function setReplaySpeed(replayId: number, speed: number): Promise<boolean> {
+  return invoke<boolean>("set_replay_speed", { replayId, speed });
+}
+
+/** Stops a running replay. Returns false if it already finished. */
+export async function stopReplay(replayId: number): Promise<boolean> {
+  return invoke<boolean>("stop_replay", { replayId });
+}
+
+/**
+ * Subscribes to the events of replays into the pane `paneId`.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onReplayEvent(
+  paneId: string,
+  callback: (event: ReplayEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<ReplayEvent>(`recording-replay-${paneId}`, (event) => {
+    callback(event.payload);
+  });
+}
@@ -0,0 +1,89 @@
+/**
+ * Thin wrappers around Tauri `invoke` / `listen` for the per-project task
+ * board. Agents work the same board through the `maestro_*_task` MCP tools.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/** Where a task is in its lifecycle. */
+export type TaskStatus = "Open" | "Claimed" | "Done" | "Failed";
+
+/** One unit of work on a project's task board. */
+export interface Task {
+  id: number;
+  body: string;
+  status: TaskStatus;
+  /** Session that claimed the task; kept after it finishes. */
+  assignee: number | null;
+  /** Outcome reported on completion, or the reason it failed. */
+  result: string | null;
+  /** Milliseconds since the Unix epoch. */
+  created_at: number;
+  updated_at: number;
+}
+
+/** Payload of the `task-board-changed` event. */
+export interface TaskBoardChanged {
+  project_path: string;
+  tasks: Task[];
+}
+
+/** Returns a project's tasks, oldest first. */
+export async function getTaskBoard(projectPath: string): Promise<Task[]> {
+  return invoke<Task[]>("get_task_board", { projectPath });
+}
+
+/** Adds an open task to a project's board. */
+export async function createTask(projectPath: string, body: string): Promise<Task> {
+  return invoke<Task>("create_task", { projectPath, body });
+}
+
+/** Assigns an open task to a session. */
+export async function assignTask(
+  projectPath: string,
+  taskId: number,
+  sessionId: number,
+): Promise<Task> {
+  return invoke<Task>("assign_task", { projectPath, taskId, sessionId });
+}
+
+/** Marks a task done. */
+export async function completeTask(
+  projectPath: string,
+  taskId: number,
+  result?: string,
+): Promise<Task> {
+  return invoke<Task>("complete_task", { projectPath, taskId, result });
+}
+
+/** Marks a task failed. */
+export async function failTask(
+  projectPath: string,
+  taskId: number,
+  reason?: string,
+): Promise<Task> {
+  return invoke<Task>("fail_task", { projectPath, taskId, reason });
+}
+
+/** Puts a task back in the queue, unassigned. */
+export async function reopenTask(projectPath: string, taskId: number): Promise<Task> {
+  return invoke<Task>("reopen_task", { projectPath, taskId });
+}
+
+/** Removes a task from a project's board. */
+export async function deleteTask(projectPath: string, taskId: number): Promise<void> {
+  return invoke("delete_task", { projectPath, taskId });
+}
+
+/**
+ * Subscribes to board changes from the UI or any agent, for every project.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onTaskBoardChanged(
+  callback: (change: TaskBoardChanged) => void,
+): Promise<UnlistenFn> {
+  return listen<TaskBoardChanged>("task-board-changed", (event) => {
+    callback(event.payload);
+  });
+}
@@ -21,6 +21,46 @@ export async function spawnShell(cwd?: string, env?: Record<string, string>): Pr
   return invoke<number>("spawn_shell", { cwd: cwd ?? null, env: env ?? null });
 }
 
+/**
+ * Launches an AI mode's CLI directly in a new PTY (no shell in between), using
+ * the mode's launch profile. Plain mode falls back to a login shell.
+ * @param args - Extra CLI arguments appended after the profile's default flags.
+ * @returns The numeric session ID assigned by the backend.
+ */
+export async function spawnAgent(
+  mode: AiMode,
+  cwd?: string,
+  env?: Record<string, string>,
+  args?: string[],
+): Promise<number> {
+  return invoke<number>("spawn_agent", {
+    mode,
+    cwd: cwd ?? null,
+    env: env ?? null,
+    args: args ?? null,
+  });
+}
+
+/** Binary, default flags and extra environment used to launch a mode's CLI. */
+export interface LaunchProfile {
+  program: string;
+  args: string[];
+  env: Record<string, string>;
+}
+
+/** Returns the effective launch profile for a mode (null for Plain). */
+export async function getLaunchProfile(mode: AiMode): Promise<LaunchProfile | null> {
+  return invoke<LaunchProfile | null>("get_launch_profile", { mode });
+}
+
+/** Saves a launch profile override; pass null to restore the default. */
+export async function saveLaunchProfile(
+  mode: AiMode,
+  profile: LaunchProfile | null,
+): Promise<void> {
+  return invoke("save_launch_profile", { mode, profile });
+}
+
 /** Writes raw bytes to the PTY stdin of the given session. */
 export async function writeStdin(sessionId: number, data: string): Promise<void> {
   return invoke("write_stdin", { sessionId, data });
@@ -103,16 +143,128 @@ export async function assignSessionBranch(
   return invoke<SessionConfig>("assign_session_branch", { sessionId, branch, worktreePath });
 }
 
+/** Payload of a `pty-output-{sessionId}` event. */
+export interface PtyOutput {
+  data: string;
+  /** Scrollback offset just past `data`; the batch starts at `endOffset - bytes(data)`. */
+  endOffset: number;
+}
+
 /**
  * Subscribes to the per-session `pty-output-{sessionId}` Tauri event.
  * Returns a promise that resolves to an unlisten function. The caller must
  * invoke the unlisten function on cleanup to avoid leaked event listeners.
+ *
+ * `endOffset` lines live output up with `getSessionScrollback`: after
+ * replaying a chunk, skip live bytes before its `endOffset`.
  */
 export function onPtyOutput(
   sessionId: number,
-  callback: (data: string) => void,
+  callback: (data: string, endOffset: number) => void,
 ): Promise<UnlistenFn> {
-  return listen<string>(`pty-output-${sessionId}`, (event) => {
+  return listen<PtyOutput>(`pty-output-${sessionId}`, (event) => {
+    callback(event.payload.data, event.payload.endOffset);
+  });
+}
+
+/** Buffered output returned by `getSessionScrollback`. */
+export interface ScrollbackChunk {
+  data: string;
+  /** Stream offset of the first byte in `data`. */
+  startOffset: number;
+  /** Stream offset just past `data`; pass it back to read only newer output. */
+  endOffset: number;
+  /** True if output before `startOffset` was already evicted. */
+  truncated: boolean;
+}
+
+/**
+ * Returns a session's buffered output since `sinceOffset` (default: all of it),
+ * for repopulating a pane after a webview reload or remount.
+ */
+export async function getSessionScrollback(
+  sessionId: number,
+  sinceOffset?: number,
+): Promise<ScrollbackChunk> {
+  return invoke<ScrollbackChunk>("get_session_scrollback", {
+    sessionId,
+    sinceOffset: sinceOffset ?? null,
+  });
+}
+
+/**
+ * Stops delivering a daemon-hosted session's output to this window. The
+ * session keeps running; `getSessionScrollback` attaches to it again.
+ * No-op for in-process sessions.
+ */
+export async function detachSession(sessionId: number): Promise<void> {
+  return invoke("detach_session", { sessionId });
+}
+
+/** True when sessions run in the session daemon and survive closing the app. */
+export async function sessionsPersist(): Promise<boolean> {
+  return invoke<boolean>("sessions_persist");
+}
+
+/** Returns the sessions the previous run left open in a project. */
+export async function getRestorableSessions(projectPath: string): Promise<SessionConfig[]> {
+  return invoke<SessionConfig[]>("get_restorable_sessions", { projectPath });
+}
+
+/** Forgets the sessions the previous run left open in a project. */
+export async function discardRestorableSessions(projectPath: string): Promise<void> {
+  return invoke("discard_restorable_sessions", { projectPath });
+}
+
+/** A session brought back by `restoreSessions`. */
+export interface RestoredSession {
+  /** ID the session had in the previous run. */
+  previous_id: number;
+  /** The restored session; same ID as before if it was still running. */
+  session: SessionConfig;
+}
+
+/**
+ * Brings back the sessions the previous run left open in a project. Sessions
+ * still running in the session daemon are reattached; the rest get a fresh shell.
+ */
+export async function restoreSessions(
+  projectPath: string,
+  env?: Record<string, string>,
+): Promise<RestoredSession[]> {
+  return invoke<RestoredSession[]>("restore_sessions", { projectPath, env: env ?? null });
+}
+
+/** Returns the per-session scrollback limit in bytes. */
+export async function getScrollbackLimit(): Promise<number> {
+  return invoke<number>("get_scrollback_limit");
+}
+
+/** Sets and saves the per-session scrollback limit in bytes (64 KiB to 64 MiB). */
+export async function setScrollbackLimit(limit: number): Promise<void> {
+  await invoke("set_scrollback_limit", { limit });
+}
+
+/** Exit status of a session's child process. */
+export interface PtyExit {
+  exitCode: number;
+  signal: string | null;
+  success: boolean;
+  /** True when the exit was caused by `killSession`. */
+  killed: boolean;
+}
+
+/**
+ * Subscribes to the per-session `pty-exit-{sessionId}` Tauri event, emitted
+ * once when the session's child process exits. Unless `killed` is set, the
+ * backend has already moved the session to Done/Error and removed its
+ * MCP/plugin config.
+ */
+export function onPtyExit(
+  sessionId: number,
+  callback: (status: PtyExit) => void,
+): Promise<UnlistenFn> {
+  return listen<PtyExit>(`pty-exit-${sessionId}`, (event) => {
     callback(event.payload);
   });
 }
@@ -246,3 +398,29 @@ export function buildCliCommand(mode: AiMode, flags?: CliFlags): string | null {
 
   return parts.join(" ");
 }
+
+/**
+ * Builds the extra arguments passed to `spawnAgent` for a mode's CLI flags.
+ *
+ * Custom flags are split on whitespace; single or double quotes keep a
+ * value with spaces together, as they would when typed into a shell.
+ *
+ * @example
+ * buildCliArgs("Claude", { skipPermissions: true, customFlags: "--model 'opus'" })
+ * // Returns: ["--dangerously-skip-permissions", "--model", "opus"]
+ */
+export function buildCliArgs(mode: AiMode, flags?: CliFlags): string[] {
+  const args: string[] = [];
+  if (!flags) return args;
+
+  const skipFlag = AI_CLI_CONFIG[mode].skipPermissionsFlag;
+  if (flags.skipPermissions && skipFlag) {
+    args.push(skipFlag);
+  }
+
+  const pattern = /"([^"]*)"|'([^']*)'|(\S+)/g;
+  for (const match of flags.customFlags.matchAll(pattern)) {
+    args.push(match[1] ?? match[2] ?? match[3]);
+  }
+  return args;
+}
@@ -0,0 +1,41 @@
+/**
+ * Thin wrappers around Tauri `invoke` for outgoing webhooks. Hooks fire on
+ * session create/remove and whenever a session's status changes.
+ */
+
+import { invoke } from "@tauri-apps/api/core";
+
+/** One outgoing webhook. */
+export interface WebhookConfig {
+  id: string;
+  url: string;
+  enabled: boolean;
+  /**
+   * Events that fire the hook; all when empty. `session.created`,
+   * `session.removed`, `session.status`, `session.status:<Status>` or `*`.
+   */
+  events: string[];
+  /**
+   * JSON body with `{{event}}`, `{{session_id}}`, `{{project_path}}`,
+   * `{{project_name}}`, `{{status}}`, `{{message}}`, `{{branch}}` and
+   * `{{timestamp}}` placeholders. The event is sent as JSON when null.
+   */
+  template: string | null;
+  /** Signs bodies as `X-Maestro-Signature-256: sha256=<hmac>` when set. */
+  secret: string | null;
+}
+
+/** Returns the configured webhooks. */
+export async function getWebhooks(): Promise<WebhookConfig[]> {
+  return invoke<WebhookConfig[]>("get_webhooks");
+}
+
+/** Replaces the configured webhooks; rejects invalid URLs or templates. */
+export async function setWebhooks(hooks: WebhookConfig[]): Promise<void> {
+  return invoke("set_webhooks", { hooks });
+}
+
+/** Sends a sample event to `hook` once, rejecting with the failure. */
+export async function sendTestWebhook(hook: WebhookConfig): Promise<void> {
+  return invoke("send_test_webhook", { hook });
+}
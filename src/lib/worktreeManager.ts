@@ -1,5 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { homeDir } from "@tauri-apps/api/path";
+import type { MergeResult, MergeStrategy } from "./git";
 
 /** Worktree info from the backend. */
 export interface WorktreeInfo {
@@ -21,8 +23,57 @@ export interface WorktreePreparationResult {
   created: boolean;
   /** Warning message if something unexpected happened but we recovered. */
   warning: string | null;
+  /**
+   * What applying the project's bootstrap config to a newly created worktree
+   * did, if it has one. Progress is reported by `onWorktreeBootstrapProgress`
+   * while it runs.
+   */
+  bootstrap: BootstrapResult | null;
 }
 
+/** How a project's new worktrees are set up. */
+export interface WorktreeBootstrap {
+  /** Files or globs, relative to the main checkout, copied into the worktree. */
+  copy: string[];
+  /** Files or globs symlinked instead of copied (e.g. node_modules). */
+  symlink: string[];
+  /** Shell commands run in the worktree, in order; a failure stops the rest. */
+  commands: string[];
+  /** Longest each command may run before it is killed. */
+  command_timeout_secs: number;
+}
+
+/** Outcome of one bootstrap command. */
+export interface BootstrapCommandResult {
+  command: string;
+  /** Null if the command was killed or could not be started. */
+  exit_code: number | null;
+  stdout: string;
+  stderr: string;
+  timed_out: boolean;
+  duration_ms: number;
+}
+
+/** What bootstrapping a worktree did. */
+export interface BootstrapResult {
+  copied: string[];
+  symlinked: string[];
+  /** Matches left alone because the worktree already has them. */
+  skipped: string[];
+  commands: BootstrapCommandResult[];
+  errors: string[];
+}
+
+/** One step of a running worktree bootstrap. */
+export type BootstrapProgress =
+  | { type: "files_placed"; copied: number; symlinked: number; skipped: number }
+  | { type: "command_started"; index: number; total: number; command: string }
+  | { type: "command_finished"; index: number; result: BootstrapCommandResult }
+  | { type: "finished"; result: BootstrapResult };
+
+/** Payload of the `worktree-bootstrap-progress` event. */
+export type BootstrapProgressEvent = BootstrapProgress & { worktree_path: string };
+
 /**
  * Generates a hash from a string for creating unique worktree paths.
  */
@@ -228,6 +279,7 @@ export async function prepareSessionWorktree(
       worktree_path: null,
       created: false,
       warning: `Failed to prepare worktree: ${err}`,
+      bootstrap: null,
     };
   }
 }
@@ -266,3 +318,130 @@ export async function cleanupSessionWorktree(
     return false;
   }
 }
+
+/** Result of merging a session's branch. */
+export interface SessionMergeResult extends MergeResult {
+  /** Whether the session's worktree was removed after the merge. */
+  worktree_cleaned: boolean;
+}
+
+/**
+ * Merges, squash-merges or rebases a session's branch onto a target branch.
+ *
+ * A dry run runs first; if it finds conflicts they are returned and nothing
+ * changes. A rebase also fast-forwards the target to the rebased branch. On
+ * success the session's worktree is removed if one is given.
+ *
+ * @param projectPath - The path to the main repository
+ * @param sourceBranch - The session's branch
+ * @param targetBranch - The branch to merge into
+ * @param strategy - Merge, squash or rebase
+ * @param message - Commit message for a squash merge
+ * @param cleanupWorktreePath - The session's worktree to remove afterwards
+ */
+export async function mergeSessionBranch(
+  projectPath: string,
+  sourceBranch: string,
+  targetBranch: string,
+  strategy: MergeStrategy,
+  message: string | null = null,
+  cleanupWorktreePath: string | null = null
+): Promise<SessionMergeResult> {
+  return invoke<SessionMergeResult>("merge_session_branch", {
+    projectPath,
+    sourceBranch,
+    targetBranch,
+    strategy,
+    message,
+    cleanupWorktreePath,
+  });
+}
+
+/**
+ * Gets how a project's new worktrees are set up.
+ *
+ * @param projectPath - The path to the main repository
+ */
+export async function getWorktreeBootstrap(projectPath: string): Promise<WorktreeBootstrap> {
+  return invoke<WorktreeBootstrap>("get_worktree_bootstrap", { projectPath });
+}
+
+/**
+ * Replaces a project's worktree bootstrap config.
+ *
+ * @param projectPath - The path to the main repository
+ * @param config - Files to copy or symlink and commands to run
+ */
+export async function setWorktreeBootstrap(
+  projectPath: string,
+  config: WorktreeBootstrap
+): Promise<void> {
+  await invoke("set_worktree_bootstrap", { projectPath, config });
+}
+
+/**
+ * Subscribes to the progress of worktree bootstraps started by
+ * `prepareSessionWorktree`. Each bootstrap ends with a `finished` step.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onWorktreeBootstrapProgress(
+  callback: (progress: BootstrapProgressEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<BootstrapProgressEvent>("worktree-bootstrap-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
+/** What the garbage collector found a managed worktree to be. */
+export type WorktreeGcState = "InUse" | "Stale" | "Dirty" | "Orphaned" | "MissingRepo";
+
+/** One directory under the managed worktree base dir. */
+export interface WorktreeGcEntry {
+  path: string;
+  /** The repository the worktree belongs to, if it could be determined. */
+  repo_path: string | null;
+  branch: string | null;
+  state: WorktreeGcState;
+  /** Bytes used on disk. */
+  disk_usage: number;
+}
+
+/** Result of a dry-run GC pass. */
+export interface WorktreeGcReport {
+  entries: WorktreeGcEntry[];
+  /** Bytes freed by removing every Stale entry. */
+  reclaimable_bytes: number;
+  /** Bytes used by Orphaned entries, freed only by a forced apply. */
+  orphaned_bytes: number;
+}
+
+/** Result of applying the GC. */
+export interface WorktreeGcResult {
+  removed: string[];
+  /** Paths that were not removed, with the reason. */
+  skipped: [string, string][];
+  reclaimed_bytes: number;
+}
+
+/**
+ * Scans all Maestro-managed worktrees for ones no session uses anymore.
+ * Nothing is removed.
+ */
+export async function scanWorktreeGc(): Promise<WorktreeGcReport> {
+  return invoke<WorktreeGcReport>("scan_worktree_gc");
+}
+
+/**
+ * Removes the worktrees the user confirmed from a scan. Only Stale
+ * worktrees are removed, plus Orphaned ones if `forceOrphaned` is set;
+ * everything else is reported as skipped.
+ *
+ * @param paths - Paths from `scanWorktreeGc` entries
+ * @param forceOrphaned - Also delete Orphaned worktrees, which git can't vouch for
+ */
+export async function applyWorktreeGc(
+  paths: string[],
+  forceOrphaned = false
+): Promise<WorktreeGcResult> {
+  return invoke<WorktreeGcResult>("apply_worktree_gc", { paths, forceOrphaned });
+}
@@ -31,6 +31,8 @@ const SESSION_STARTUP_TIMEOUT_MS = 30000;
  * @property project_path - Canonicalized project directory this session belongs to.
  * @property statusMessage - Brief description of what the agent is doing (from MCP status).
  * @property needsInputPrompt - When status is NeedsInput, the specific question for the user.
+ * @property awaitingReply - The agent asked through `maestro_ask_user` and waits for an
+ *   answer from the UI.
  */
 export interface SessionConfig {
   id: number;
@@ -41,6 +43,7 @@ export interface SessionConfig {
   project_path: string;
   statusMessage?: string;
   needsInputPrompt?: string;
+  awaitingReply?: boolean;
 }
 
 /** Shape of the Tauri `session-status-changed` event payload. */
@@ -50,6 +53,7 @@ interface SessionStatusPayload {
   status: BackendSessionStatus;
   message?: string;
   needs_input_prompt?: string;
+  awaiting_reply?: boolean;
 }
 
 /**
@@ -171,6 +175,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
         status: bufferedStatus.status,
         statusMessage: bufferedStatus.message,
         needsInputPrompt: bufferedStatus.needs_input_prompt,
+        awaitingReply: bufferedStatus.awaiting_reply,
       };
     }
 
@@ -265,7 +270,8 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
       if (!activeUnlisten) {
         if (!pendingInit) {
           pendingInit = listen<SessionStatusPayload>("session-status-changed", (event) => {
-            const { session_id, project_path, status, message, needs_input_prompt } = event.payload;
+            const { session_id, project_path, status, message, needs_input_prompt, awaiting_reply } =
+              event.payload;
 
             // Check if session exists in store
             const sessionExists = get().sessions.some(
@@ -293,6 +299,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
                       status,
                       statusMessage: message,
                       needsInputPrompt: needs_input_prompt,
+                      awaitingReply: awaiting_reply,
                     }
                   : s
               ),